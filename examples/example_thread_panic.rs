@@ -27,7 +27,7 @@ fn thread_two() {
 }
 
 fn thread_three(chex: ChexInstance) {
-    while !chex.poll_exit() { }
+    chex.block_until_exit();
     println!("thread_three got exit signal");
 }
 
@@ -62,7 +62,7 @@ fn main() {
         }
     }).expect("Failed to spawn thread");
 
-    while !chex.poll_exit() { }
+    chex.block_until_exit();
 
     println!("main thread got exit signal");
 
@@ -0,0 +1,30 @@
+/*
+ * C interface to the global Chex exit signal, built with the "ffi" feature.
+ */
+#ifndef CHEX_H
+#define CHEX_H
+
+#include <stdbool.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Initialize the global Chex.  Returns 0. */
+int chex_init(bool exit_on_panic);
+
+/* 1 if this call signalled exit first, 0 if already signalled, -1 on error. */
+int chex_signal_exit(void);
+
+/* 1 if exit has been signalled, 0 if not, -1 if Chex is not initialized. */
+int chex_poll_exit(void);
+
+/* fd that becomes readable once exit is signalled (unix only).  Owned by chex: don't close or
+ * read it.  -1 on error. */
+int chex_exit_fd(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* CHEX_H */
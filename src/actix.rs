@@ -0,0 +1,54 @@
+//! Keeps an actix-web server and chex in sync.
+//!
+//! Enabled with the "actix" feature, for actix-web 4.
+
+use crate::{ChexInstance,ExitOrigin,ExitReason};
+use actix_web::dev::Server;
+use std::future::Future;
+use std::panic::Location;
+use std::pin::Pin;
+use std::task::Poll;
+
+/// Drive server (from `HttpServer::run()`) to completion on the actix System, keeping both
+/// shutdown mechanisms in sync:
+///
+/// - once exit is signalled on ci, the server is stopped through its ServerHandle, gracefully
+///   (finishing in-flight requests within the server's shutdown_timeout()) if graceful is set;
+/// - once the server finishes on its own (ie actix caught SIGTERM itself), exit is signalled
+///   on ci, with the caller of run_server() as origin.
+///
+/// Returns the server's result.  To leave SIGTERM to chex (ie handle_sigterm() with a pre-stop
+/// delay), build the server with `HttpServer::disable_signals()`.
+#[track_caller]
+pub fn run_server(ci: ChexInstance, server: Server, graceful: bool) -> impl Future<Output = std::io::Result<()>> {
+    let location = Location::caller();
+    async move {
+        let handle = server.handle();
+        let mut server = std::pin::pin!(server);
+        let mut exit = std::pin::pin!(crate::http::shutdown_signal_for(ci.clone()));
+        let mut stopping: Option<Pin<Box<dyn Future<Output = ()>>>> = None;
+        let mut stopped = false;
+
+        let output = std::future::poll_fn(|cx| {
+            if let Poll::Ready(output) = server.as_mut().poll(cx) {
+                return Poll::Ready(output);
+            }
+            if !stopped && exit.as_mut().poll(cx).is_ready() {
+                stopped = true;
+                stopping = Some(Box::pin(handle.stop(graceful)));
+            }
+            /*
+             * Once the stop command is delivered, the server future completes on its own.
+             */
+            if stopping.as_mut().is_some_and(|f| f.as_mut().poll(cx).is_ready()) {
+                stopping = None;
+            }
+            Poll::Pending
+        }).await;
+
+        if !ci.poll_exit() {
+            ci.shared.signal_exit_from(ExitReason::Manual, ExitOrigin::from_location(location));
+        }
+        output
+    }
+}
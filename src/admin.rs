@@ -0,0 +1,122 @@
+//! A tiny control endpoint for operators to trigger and inspect shutdown of the global Chex,
+//! without sending signals through the container runtime.
+//!
+//! Enabled with the "admin" feature.  Clients send one command per line, ie with
+//! `echo soft-exit | nc -U /run/myapp/admin.sock`:
+//!
+//! - `exit`: signal exit (ExitReason::Manual, with the serve() call as origin);
+//! - `soft-exit`: begin draining, see ChexInstance::start_draining();
+//! - `status`: the Chex::dump_state() snapshot.
+//!
+//! Every response ends with an empty line.
+
+use crate::{Chex,ExitOrigin,ExitReason};
+use crate::sink::{self,ChexEvent};
+use std::io::{BufRead,BufReader,Write};
+use std::net::{SocketAddr,TcpListener};
+use std::panic::Location;
+use std::time::Duration;
+
+/*
+ * How long a client may take to send a command before it is disconnected, so one stuck client
+ * can't lock out the others.
+ */
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/*
+ * Where serve() listens.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AdminAddr {
+    /// A loopback TCP address, ie 127.0.0.1:9901.  Other addresses are refused.
+    Tcp(SocketAddr),
+    /// A Unix domain socket path, which must not exist yet.
+    #[cfg(unix)]
+    Unix(std::path::PathBuf),
+}
+
+/// Serve admin commands for the global Chex on addr, from a "chex-admin" thread.  Clients are
+/// served one at a time.
+///
+/// Panics if Chex is not initialized.
+#[track_caller]
+pub fn serve(addr: AdminAddr) -> std::io::Result<()> {
+    let origin = ExitOrigin::from_location(Location::caller());
+    crate::GLOBAL_CHECK_EXIT.instance().expect("Failed to initialize Chex before admin::serve()");
+
+    match addr {
+        AdminAddr::Tcp(addr) => {
+            if !addr.ip().is_loopback() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "admin endpoint must listen on a loopback address",
+                ));
+            }
+            let listener = TcpListener::bind(addr)?;
+            spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let _ = stream.set_read_timeout(Some(CLIENT_TIMEOUT));
+                    if let Ok(writer) = stream.try_clone() {
+                        session(BufReader::new(stream), writer, &origin);
+                    }
+                }
+            });
+        }
+        #[cfg(unix)]
+        AdminAddr::Unix(path) => {
+            let listener = std::os::unix::net::UnixListener::bind(path)?;
+            spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let _ = stream.set_read_timeout(Some(CLIENT_TIMEOUT));
+                    if let Ok(writer) = stream.try_clone() {
+                        session(BufReader::new(stream), writer, &origin);
+                    }
+                }
+            });
+        }
+    }
+    Ok(())
+}
+
+fn spawn(f: impl FnOnce() + Send + 'static) {
+    if let Err(e) = std::thread::Builder::new().name("chex-admin".to_string()).spawn(f) {
+        sink::emit(ChexEvent::ThreadSpawnFailed { thread: "chex-admin", error: &e });
+    }
+}
+
+/// Answer commands until the client hangs up or goes quiet.
+fn session(reader: impl BufRead, mut writer: impl Write, origin: &ExitOrigin) {
+    for line in reader.lines() {
+        let Ok(line) = line else { return };
+        let response = command(line.trim(), origin);
+        if writer.write_all(response.as_bytes()).and_then(|()| writer.write_all(b"\n")).is_err() {
+            return;
+        }
+    }
+}
+
+fn command(command: &str, origin: &ExitOrigin) -> String {
+    let chex: &Chex = &crate::GLOBAL_CHECK_EXIT;
+    let Some(ci) = chex.instance() else {
+        return "error: chex not initialized\n".to_string();
+    };
+    match command {
+        "exit" => {
+            if ci.shared.signal_exit_from(ExitReason::Manual, origin.clone()) {
+                "ok: exit signalled\n".to_string()
+            } else {
+                "ok: exit already signalled\n".to_string()
+            }
+        }
+        "soft-exit" => {
+            if ci.start_draining() {
+                "ok: draining\n".to_string()
+            } else {
+                "ok: already draining\n".to_string()
+            }
+        }
+        "status" => chex.dump_state().to_string(),
+        _ => format!("error: unknown command '{command}'\n"),
+    }
+}
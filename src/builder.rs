@@ -0,0 +1,140 @@
+//! Chex::builder(), for initializing the global Chex with more than exit-on-panic.
+
+use crate::{Chex,ChexConfig,ForcedExit,GLOBAL_CHECK_EXIT};
+use crate::clock::Clock;
+use crate::guard::MainGuard;
+use crate::panic::PanicHookMode;
+use crate::sink::{self,ChexSink,LogLevel};
+use std::time::Duration;
+
+/*
+ * Settings for the global Chex, applied by init().  Settings left unset keep their defaults (or
+ * are read from the environment, see ChexConfig::from_env()).
+ */
+#[derive(Default)]
+#[must_use = "call .init() to initialize Chex"]
+pub struct ChexBuilder {
+    config: ChexConfig,
+    sink: Option<Box<dyn ChexSink>>,
+    panic_hook_mode: Option<PanicHookMode>,
+    panic_grace: Option<Duration>,
+    #[cfg(all(feature = "sigterm", unix))]
+    sigterm: Option<crate::sigterm::SigtermOptions>,
+}
+
+impl ChexBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start from config rather than the defaults.  Replaces the settings made so far which
+    /// belong to ChexConfig.
+    pub fn config(mut self, config: ChexConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// See ChexConfig::exit_on_panic().
+    pub fn exit_on_panic(mut self, exit_on_panic: bool) -> Self {
+        self.config = self.config.exit_on_panic(exit_on_panic);
+        self
+    }
+
+    /// See ChexConfig::exit_process_on_error().
+    #[deprecated(note = "has no effect: signalling exit cannot fail once Chex is initialized")]
+    #[allow(deprecated)]
+    pub fn exit_process_on_error(mut self, exit_process_on_error: bool) -> Self {
+        self.config = self.config.exit_process_on_error(exit_process_on_error);
+        self
+    }
+
+    /// See ChexConfig::forced_exit().
+    pub fn forced_exit(mut self, forced_exit: ForcedExit) -> Self {
+        self.config = self.config.forced_exit(forced_exit);
+        self
+    }
+
+    /// Terminate the process if it is still alive grace after exit is signalled.  See
+    /// ChexConfig::grace().
+    pub fn grace(mut self, grace: Duration) -> Self {
+        self.config = self.config.grace(Some(grace));
+        self
+    }
+
+    /// See ChexConfig::log_level().
+    pub fn log_level(mut self, level: LogLevel) -> Self {
+        self.config = self.config.log_level(level);
+        self
+    }
+
+    /// See ChexConfig::clock().
+    pub fn clock<C: Clock>(mut self, clock: C) -> Self {
+        self.config = self.config.clock(clock);
+        self
+    }
+
+    /// Route internal diagnostics to sink, from before initialization on.  See Chex::set_sink().
+    pub fn sink<S: ChexSink>(mut self, sink: S) -> Self {
+        self.sink = Some(Box::new(sink));
+        self
+    }
+
+    /// See Chex::set_panic_hook_mode().
+    pub fn panic_hook_mode(mut self, mode: PanicHookMode) -> Self {
+        self.panic_hook_mode = Some(mode);
+        self
+    }
+
+    /// See Chex::set_panic_grace().
+    pub fn panic_grace(mut self, grace: Duration) -> Self {
+        self.panic_grace = Some(grace);
+        self
+    }
+
+    /// Handle SIGTERM as configured by options.  See ChexInstance::handle_sigterm().
+    #[cfg(all(feature = "sigterm", unix))]
+    pub fn handle_signals(mut self, options: crate::sigterm::SigtermOptions) -> Self {
+        self.sigterm = Some(options);
+        self
+    }
+
+    /// Initialize the global Chex and apply the settings.  Like Chex::init(), if Chex was
+    /// already initialized its config stays in effect, but exit on panic (if set here) and the
+    /// other settings are still applied.
+    #[track_caller]
+    pub fn init(self) -> &'static Chex {
+        if let Some(sink) = self.sink {
+            sink::set(sink);
+        }
+
+        let (existing, initialized_here) = Chex::init_config(self.config.clone());
+        let exit_on_panic = if initialized_here {
+            existing.exit_on_panic_enabled()
+        } else {
+            self.config.exit_on_panic_enabled()
+        };
+
+        let chex: &'static Chex = &GLOBAL_CHECK_EXIT;
+        if let Some(mode) = self.panic_hook_mode {
+            chex.set_panic_hook_mode(mode);
+        }
+        if let Some(grace) = self.panic_grace {
+            chex.set_panic_grace(Some(grace));
+        }
+        if exit_on_panic {
+            chex.set_exit_on_panic();
+        }
+        #[cfg(all(feature = "sigterm", unix))]
+        if let Some(options) = self.sigterm {
+            chex.handle_sigterm(options);
+        }
+        chex
+    }
+
+    /// Like init(), but exit is also signalled once the returned MainGuard is dropped.  See
+    /// MainGuard.
+    #[track_caller]
+    pub fn init_guarded(self) -> MainGuard {
+        MainGuard::new(self.init(), std::panic::Location::caller())
+    }
+}
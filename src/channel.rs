@@ -0,0 +1,76 @@
+//! Channel receives which also end once exit is signalled, so consumer loops don't each need a
+//! two-arm select.
+//!
+//! Enabled with the "tokio" feature.
+
+use crate::{ChexExited,ChexInstance};
+use std::future::Future;
+use std::task::{Context,Poll};
+use tokio::sync::mpsc;
+
+/// A tokio mpsc receiver, bounded or unbounded.
+pub trait MpscReceiver<T> {
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>>;
+}
+
+impl<T> MpscReceiver<T> for mpsc::Receiver<T> {
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        mpsc::Receiver::poll_recv(self, cx)
+    }
+}
+
+impl<T> MpscReceiver<T> for mpsc::UnboundedReceiver<T> {
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        mpsc::UnboundedReceiver::poll_recv(self, cx)
+    }
+}
+
+/// Receive the next message from rx, unless exit is signalled on ci first.  Resolves to
+/// Ok(Some(message)), Ok(None) once every sender is gone, or Err(ChexExited).  Exit wins over
+/// messages still queued, like a biased select.
+///
+/// ```no_run
+/// # async fn consume(mut rx: tokio::sync::mpsc::Receiver<u32>, mut ci: chex::ChexInstance) {
+/// while let Ok(Some(message)) = chex::channel::recv_or_exit(&mut rx, &mut ci).await {
+///     // ...
+/// }
+/// # }
+/// ```
+pub async fn recv_or_exit<T, R, U>(rx: &mut R, ci: &mut ChexInstance<U>) -> Result<Option<T>, ChexExited>
+where
+    R: MpscReceiver<T>,
+{
+    let mut exit = std::pin::pin!(ci.check_exit_async());
+    std::future::poll_fn(|cx| {
+        if exit.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(Err(ChexExited));
+        }
+        rx.poll_recv(cx).map(Ok)
+    }).await
+}
+
+/*
+ * A receiver bundled with the coordinator whose exit ends it.
+ */
+pub struct ExitAwareReceiver<R, U = ()> {
+    rx: R,
+    ci: ChexInstance<U>,
+}
+
+impl<R, U> ExitAwareReceiver<R, U> {
+    pub fn new(rx: R, ci: ChexInstance<U>) -> Self {
+        Self { rx, ci }
+    }
+
+    /// Receive the next message.  See recv_or_exit().
+    pub async fn recv<T>(&mut self) -> Result<Option<T>, ChexExited>
+    where
+        R: MpscReceiver<T>,
+    {
+        recv_or_exit(&mut self.rx, &mut self.ci).await
+    }
+
+    pub fn into_inner(self) -> (R, ChexInstance<U>) {
+        (self.rx, self.ci)
+    }
+}
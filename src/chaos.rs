@@ -0,0 +1,78 @@
+//! Injecting exit at a random point in time, so integration environments continuously exercise
+//! the teardown path instead of only when something actually fails.
+//!
+//! Enabled with the "chaos" feature.  Install a ChaosTrigger like any other ExitTrigger:
+//! `ci.install_trigger(ChaosTrigger::within(Duration::from_secs(60), Duration::from_secs(600)))`.
+
+use crate::{ChexSignaler,ExitOrigin,ExitReason};
+use crate::trigger::ExitTrigger;
+use std::panic::Location;
+use std::time::Duration;
+
+/*
+ * Signals exit with ExitReason::Chaos at a random delay between min and max after it was
+ * installed, unless exit was signalled by then.  The delay is derived from the seed, so a run
+ * can be reproduced by passing the seed recorded in the ExitReason to seed().
+ */
+#[derive(Clone, Debug)]
+pub struct ChaosTrigger {
+    min: Duration,
+    max: Duration,
+    seed: Option<u64>,
+    origin: ExitOrigin,
+}
+
+impl ChaosTrigger {
+    /// Signal exit at a random point between min and max after installing the trigger.
+    #[track_caller]
+    pub fn within(min: Duration, max: Duration) -> Self {
+        Self {
+            min,
+            max: max.max(min),
+            seed: None,
+            origin: ExitOrigin::from_location(Location::caller()),
+        }
+    }
+
+    /// Derive the delay from seed instead of a fresh random seed.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// The delay this trigger waits for with seed.
+    pub fn delay_for(&self, seed: u64) -> Duration {
+        let span = (self.max - self.min).as_nanos();
+        if span == 0 {
+            return self.min;
+        }
+        let offset = u128::from(splitmix64(seed)) % (span + 1);
+        self.min + Duration::from_nanos(offset as u64)
+    }
+}
+
+impl ExitTrigger for ChaosTrigger {
+    fn watch(self, signaler: ChexSignaler) {
+        let seed = self.seed.unwrap_or_else(fresh_seed);
+        if signaler.shared.block_until_exit(Some(self.delay_for(seed))) {
+            return;
+        }
+        signaler.shared.signal_exit_from(ExitReason::Chaos { seed }, self.origin);
+    }
+}
+
+/// One round of SplitMix64, which is plenty to spread a seed over the window.
+fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// A seed which differs between processes and runs.
+fn fresh_seed() -> u64 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64);
+    nanos ^ (u64::from(std::process::id()) << 32)
+}
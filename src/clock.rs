@@ -0,0 +1,165 @@
+//! The time seen by chex's timer threads: deadlines (signal_exit_after(), signal_exit_at()), the
+//! grace period (ChexConfig::grace(), signal_exit_with_deadline()) and watchdogs.  The
+//! timeouts of block_until_exit_timeout(), wait_for_shutdown() and wait_for_subsystems(), the
+//! straggler timeout, the panic threshold window and panic grace, the SIGTERM pre-stop delay,
+//! interceptor max delays, adopted children's and runtimes' grace periods, the http module's
+//! deadlines, and the exit timestamps (exited_at(), exit_elapsed()) follow it too.
+//!
+//! Configure one with ChexConfig::clock().  SystemClock is the default.  Tests can inject a
+//! MockClock ("test-util" feature) and advance() it, or a TokioClock ("tokio" feature) to follow
+//! tokio::time::pause() and tokio::time::advance(), instead of sleeping for real.
+
+use crate::Instant;
+use std::sync::Arc;
+use std::time::Duration;
+
+/*
+ * A source of time and of sleeping.  sleep() is only called from chex's own threads; blocking
+ * waits with a timeout (ie block_until_exit_timeout()) only read now(), and recheck it every few
+ * milliseconds of real time when a clock other than SystemClock is configured.
+ */
+pub trait Clock: Send + Sync + 'static {
+    /// The current time on this clock.
+    fn now(&self) -> Instant;
+
+    /// Block the calling thread until duration has passed on this clock.
+    fn sleep(&self, duration: Duration);
+}
+
+/*
+ * Real time: Instant::now() and std::thread::sleep().
+ */
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/*
+ * The clock held by a ChexConfig.  Configs compare equal iff they hold the same clock.
+ */
+#[derive(Clone)]
+pub(crate) struct SharedClock(pub(crate) Arc<dyn Clock>);
+
+impl PartialEq for SharedClock {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for SharedClock {}
+
+impl std::fmt::Debug for SharedClock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Clock")
+    }
+}
+
+/*
+ * A clock which only moves when advance() is called.  Clones share the same time.
+ */
+#[cfg(feature = "test-util")]
+#[derive(Clone)]
+pub struct MockClock {
+    state: Arc<MockState>,
+}
+
+#[cfg(feature = "test-util")]
+struct MockState {
+    start: Instant,
+    elapsed: std::sync::Mutex<Duration>,
+    advanced: std::sync::Condvar,
+}
+
+#[cfg(feature = "test-util")]
+impl MockClock {
+    /// A clock standing at the current real time.
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(MockState {
+                start: Instant::now(),
+                elapsed: std::sync::Mutex::new(Duration::ZERO),
+                advanced: std::sync::Condvar::new(),
+            }),
+        }
+    }
+
+    /// Move the clock forward by duration, waking the sleepers whose time has come.
+    pub fn advance(&self, duration: Duration) {
+        *self.state.elapsed.lock().unwrap_or_else(|e| e.into_inner()) += duration;
+        self.state.advanced.notify_all();
+    }
+
+    /// How far the clock was advanced in total.
+    pub fn elapsed(&self) -> Duration {
+        *self.state.elapsed.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.state.start + self.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        let mut elapsed = self.state.elapsed.lock().unwrap_or_else(|e| e.into_inner());
+        let until = *elapsed + duration;
+        while *elapsed < until {
+            elapsed = self.state.advanced.wait(elapsed).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+}
+
+/*
+ * tokio's time on the runtime of handle, so a runtime with paused time (tokio::time::pause(), or
+ * #[tokio::test(start_paused = true)]) drives chex's timers as well.  Needs a runtime with the
+ * time driver enabled.
+ */
+#[cfg(all(feature = "tokio", not(all(target_arch = "wasm32", target_os = "unknown"))))]
+#[derive(Clone, Debug)]
+pub struct TokioClock {
+    handle: tokio::runtime::Handle,
+}
+
+#[cfg(all(feature = "tokio", not(all(target_arch = "wasm32", target_os = "unknown"))))]
+impl TokioClock {
+    /// The clock of the runtime of handle, ie TokioClock::new(Handle::current()).
+    pub fn new(handle: tokio::runtime::Handle) -> Self {
+        Self { handle }
+    }
+}
+
+#[cfg(all(feature = "tokio", not(all(target_arch = "wasm32", target_os = "unknown"))))]
+impl Clock for TokioClock {
+    fn now(&self) -> Instant {
+        let _enter = self.handle.enter();
+        tokio::time::Instant::now().into_std()
+    }
+
+    /// Sleeps on the runtime, falling back to real time once the runtime is shut down.
+    fn sleep(&self, duration: Duration) {
+        let (done, slept) = std::sync::mpsc::sync_channel(1);
+        self.handle.spawn(async move {
+            tokio::time::sleep(duration).await;
+            let _ = done.send(());
+        });
+        if slept.recv().is_err() {
+            std::thread::sleep(duration);
+        }
+    }
+}
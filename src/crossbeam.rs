@@ -0,0 +1,73 @@
+//! Exit-aware receives on crossbeam channels, for sync code.
+//!
+//! Enabled with the "crossbeam" feature.  Every coordinator owns a crossbeam channel which is
+//! disconnected once exit is signalled, so waiting on data and on exit together takes a plain
+//! crossbeam select, without polling.
+
+use crate::{ChexExited,ChexInstance};
+use crossbeam_channel::{Receiver,Sender};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/*
+ * Channel of one ChexShared whose sender is dropped on exit, after which its receiver is
+ * always ready.
+ */
+pub(crate) struct ExitChannel {
+    sender: Mutex<Option<Sender<()>>>,
+    receiver: Receiver<()>,
+}
+
+impl ExitChannel {
+    pub(crate) fn new() -> Self {
+        let (sender, receiver) = crossbeam_channel::bounded(0);
+        Self {
+            sender: Mutex::new(Some(sender)),
+            receiver,
+        }
+    }
+
+    pub(crate) fn receiver(&self) -> &Receiver<()> {
+        &self.receiver
+    }
+
+    /// Disconnect the channel, waking every select waiting on it.
+    pub(crate) fn close(&self) {
+        drop(self.sender.lock().unwrap_or_else(|e| e.into_inner()).take());
+    }
+
+    /// Like close(), but skipped if the lock is contended, as from the minimal panic path.
+    pub(crate) fn try_close(&self) {
+        match self.sender.try_lock() {
+            Ok(mut sender) => drop(sender.take()),
+            Err(std::sync::TryLockError::Poisoned(e)) => drop(e.into_inner().take()),
+            Err(std::sync::TryLockError::WouldBlock) => {}
+        }
+    }
+}
+
+/// Receive the next message from rx, unless exit is signalled on ci first.  Returns
+/// Ok(Some(message)), Ok(None) once every sender is gone, or Err(ChexExited).  Exit wins over
+/// messages still queued.
+pub fn recv_or_exit<T, U>(rx: &Receiver<T>, ci: &ChexInstance<U>) -> Result<Option<T>, ChexExited> {
+    recv_inner(rx, ci, None)
+}
+
+/// Like recv_or_exit(), giving up after timeout.  Returns Ok(None) on timeout as well as once
+/// every sender is gone.
+pub fn recv_or_exit_timeout<T, U>(rx: &Receiver<T>, ci: &ChexInstance<U>, timeout: Duration) -> Result<Option<T>, ChexExited> {
+    recv_inner(rx, ci, Some(timeout))
+}
+
+fn recv_inner<T, U>(rx: &Receiver<T>, ci: &ChexInstance<U>, timeout: Option<Duration>) -> Result<Option<T>, ChexExited> {
+    let exit = ci.shared.exit_channel.receiver();
+    if ci.shared.poll_exit() {
+        return Err(ChexExited);
+    }
+    let timeout = timeout.map_or_else(crossbeam_channel::never, crossbeam_channel::after);
+    crossbeam_channel::select_biased! {
+        recv(exit) -> _ => Err(ChexExited),
+        recv(rx) -> message => Ok(message.ok()),
+        recv(timeout) -> _ => Ok(None),
+    }
+}
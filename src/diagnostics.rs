@@ -0,0 +1,216 @@
+//! Point-in-time dump of the chex state, for debug endpoints and troubleshooting shutdown.
+
+use crate::{ExitOrigin,ExitReason,Instant};
+use crate::listener::ListenerInfo;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::Relaxed;
+use std::time::Duration;
+
+/*
+ * Signal attempts kept per coordinator.  Later ones are counted but dropped.
+ */
+const SIGNAL_ATTEMPTS: usize = 64;
+
+/*
+ * One call which signalled, or tried to signal, exit.  Only the first sets the exit reason and
+ * origin; the later ones often trace a cascading failure.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignalAttempt {
+    reason: ExitReason,
+    origin: ExitOrigin,
+    at: Instant,
+    first: bool,
+}
+
+impl SignalAttempt {
+    pub fn reason(&self) -> &ExitReason {
+        &self.reason
+    }
+
+    pub fn origin(&self) -> &ExitOrigin {
+        &self.origin
+    }
+
+    pub fn at(&self) -> Instant {
+        self.at
+    }
+
+    /// Whether this attempt was the one which signalled exit.
+    pub fn first(&self) -> bool {
+        self.first
+    }
+}
+
+/*
+ * Signal attempts on one ChexShared, in order.
+ */
+pub(crate) struct SignalLog {
+    attempts: Mutex<Vec<SignalAttempt>>,
+    dropped: AtomicUsize,
+}
+
+impl SignalLog {
+    pub(crate) fn new() -> Self {
+        Self {
+            attempts: Mutex::new(Vec::new()),
+            dropped: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn record(&self, reason: ExitReason, origin: ExitOrigin, first: bool) {
+        let mut attempts = self.attempts.lock().unwrap_or_else(|e| e.into_inner());
+        if attempts.len() >= SIGNAL_ATTEMPTS {
+            self.dropped.fetch_add(1, Relaxed);
+            return;
+        }
+        attempts.push(SignalAttempt {
+            reason,
+            origin,
+            at: Instant::now(),
+            first,
+        });
+    }
+
+    /// The kept attempts, and the number dropped because the log was full.
+    pub(crate) fn attempts(&self) -> (Vec<SignalAttempt>, usize) {
+        let attempts = self.attempts.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        (attempts, self.dropped.load(Relaxed))
+    }
+}
+
+/*
+ * Returned by Chex::dump_state().
+ */
+#[derive(Clone, Debug, Default)]
+pub struct ChexStateSnapshot {
+    pub(crate) initialized: bool,
+    pub(crate) exit_signalled: bool,
+    pub(crate) draining: bool,
+    pub(crate) origin: Option<ExitOrigin>,
+    pub(crate) reason: Option<ExitReason>,
+    pub(crate) panic_message: Option<String>,
+    pub(crate) exit_elapsed: Option<Duration>,
+    pub(crate) receiver_count: usize,
+    pub(crate) waiter_count: usize,
+    pub(crate) exit_on_panic: bool,
+    pub(crate) panic_hooks: usize,
+    pub(crate) watchdogs: usize,
+    pub(crate) pending_acks: Vec<ListenerInfo>,
+    pub(crate) signal_attempts: Vec<SignalAttempt>,
+    pub(crate) dropped_signal_attempts: usize,
+}
+
+impl ChexStateSnapshot {
+    pub fn initialized(&self) -> bool {
+        self.initialized
+    }
+
+    pub fn exit_signalled(&self) -> bool {
+        self.exit_signalled
+    }
+
+    /// Draining has begun (ie with start_draining()), or exit has been signalled.
+    pub fn draining(&self) -> bool {
+        self.draining
+    }
+
+    /// Where exit was first signalled, if it has been.
+    pub fn origin(&self) -> Option<&ExitOrigin> {
+        self.origin.as_ref()
+    }
+
+    /// Why exit was signalled, if it has been.
+    pub fn reason(&self) -> Option<&ExitReason> {
+        self.reason.as_ref()
+    }
+
+    /// Message of the panic that signalled exit, if any.
+    pub fn panic_message(&self) -> Option<&str> {
+        self.panic_message.as_deref()
+    }
+
+    /// Time since exit was first signalled.
+    pub fn exit_elapsed(&self) -> Option<Duration> {
+        self.exit_elapsed
+    }
+
+    /// Live ChexInstances, including the global one.
+    pub fn receiver_count(&self) -> usize {
+        self.receiver_count
+    }
+
+    /// Tasks currently waiting for exit asynchronously, see ChexInstance::waiter_count().
+    pub fn waiter_count(&self) -> usize {
+        self.waiter_count
+    }
+
+    /// Whether the exit-on-panic hook is installed.
+    pub fn exit_on_panic(&self) -> bool {
+        self.exit_on_panic
+    }
+
+    /// Number of hooks chained after the exit-on-panic hook.
+    pub fn panic_hooks(&self) -> usize {
+        self.panic_hooks
+    }
+
+    /// Number of live heartbeat watchdogs.
+    pub fn watchdogs(&self) -> usize {
+        self.watchdogs
+    }
+
+    /// Registered listeners that have not acked yet.
+    pub fn pending_acks(&self) -> &[ListenerInfo] {
+        &self.pending_acks
+    }
+
+    /// Every call which signalled or tried to signal exit, in order, up to a bound.
+    pub fn signal_attempts(&self) -> &[SignalAttempt] {
+        &self.signal_attempts
+    }
+
+    /// Signal attempts not kept in signal_attempts() because the log was full.
+    pub fn dropped_signal_attempts(&self) -> usize {
+        self.dropped_signal_attempts
+    }
+}
+
+impl std::fmt::Display for ChexStateSnapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.initialized {
+            return writeln!(f, "chex: not initialized");
+        }
+
+        writeln!(f, "chex:")?;
+        writeln!(f, "  exit signalled: {}", self.exit_signalled)?;
+        writeln!(f, "  draining: {}", self.draining)?;
+        if let Some(origin) = &self.origin {
+            writeln!(f, "  origin: {origin}")?;
+        }
+        if let Some(reason) = &self.reason {
+            writeln!(f, "  reason: {reason}")?;
+        }
+        if let Some(message) = &self.panic_message {
+            writeln!(f, "  panic: {message}")?;
+        }
+        if let Some(elapsed) = self.exit_elapsed {
+            writeln!(f, "  exit elapsed: {elapsed:?}")?;
+        }
+        writeln!(f, "  receivers: {}, async waiters: {}", self.receiver_count, self.waiter_count)?;
+        writeln!(f, "  exit on panic: {}, chained panic hooks: {}", self.exit_on_panic, self.panic_hooks)?;
+        writeln!(f, "  watchdogs: {}", self.watchdogs)?;
+        writeln!(f, "  pending acks: {}", self.pending_acks.len())?;
+        for l in &self.pending_acks {
+            writeln!(f, "    {} on thread {}", l.name(), l.thread_name().unwrap_or("<unnamed>"))?;
+        }
+        if !self.signal_attempts.is_empty() {
+            writeln!(f, "  signal attempts: {}", self.signal_attempts.len() + self.dropped_signal_attempts)?;
+            for a in &self.signal_attempts {
+                writeln!(f, "    {} at {}{}", a.reason, a.origin, if a.first { " (first)" } else { "" })?;
+            }
+        }
+        Ok(())
+    }
+}
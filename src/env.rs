@@ -0,0 +1,51 @@
+//! Defaults for ChexConfig from CHEX_* environment variables, so operators can tune shutdown
+//! per deployment.  Settings made explicitly on the ChexConfig always win.
+
+use crate::ChexConfig;
+use crate::sink::{self,ChexEvent,LogLevel};
+use std::time::Duration;
+
+pub(crate) const GRACE_SECS: &str = "CHEX_GRACE_SECS";
+pub(crate) const EXIT_ON_PANIC: &str = "CHEX_EXIT_ON_PANIC";
+pub(crate) const LOG_LEVEL: &str = "CHEX_LOG_LEVEL";
+
+pub(crate) fn apply(mut config: ChexConfig) -> ChexConfig {
+    if config.grace.is_none() {
+        if let Some(grace) = var(GRACE_SECS, parse_secs) {
+            config.grace = Some(Some(grace));
+        }
+    }
+    if config.exit_on_panic.is_none() {
+        config.exit_on_panic = var(EXIT_ON_PANIC, parse_bool);
+    }
+    if config.log_level.is_none() {
+        config.log_level = var(LOG_LEVEL, LogLevel::parse);
+    }
+    config
+}
+
+/// The parsed value of the variable name, or None if it is unset or empty.  Invalid values are
+/// reported and ignored.
+fn var<T>(name: &'static str, parse: fn(&str) -> Option<T>) -> Option<T> {
+    let value = std::env::var(name).ok()?;
+    if value.trim().is_empty() {
+        return None;
+    }
+    let parsed = parse(&value);
+    if parsed.is_none() {
+        sink::emit(ChexEvent::InvalidEnvVar { name, value: &value });
+    }
+    parsed
+}
+
+fn parse_secs(value: &str) -> Option<Duration> {
+    Duration::try_from_secs_f64(value.trim().parse().ok()?).ok()
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
@@ -0,0 +1,87 @@
+//! Every failure reported to a coordinator, not just the one which signalled exit.
+
+use crate::{ExitOrigin,ExitReasonKind,Instant};
+use std::sync::Mutex;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::Relaxed;
+
+/*
+ * Failures kept per coordinator.  Later ones are counted but dropped.
+ */
+const CAPACITY: usize = 64;
+
+/*
+ * One failure (panic, error or missed watchdog) reported to a coordinator.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CollectedError {
+    reason: ExitReasonKind,
+    message: String,
+    origin: ExitOrigin,
+    at: Instant,
+}
+
+impl CollectedError {
+    pub fn reason(&self) -> ExitReasonKind {
+        self.reason
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Where the failure was reported.
+    pub fn origin(&self) -> &ExitOrigin {
+        &self.origin
+    }
+
+    pub fn at(&self) -> Instant {
+        self.at
+    }
+}
+
+impl std::fmt::Display for CollectedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} (at {})", self.reason, self.message, self.origin)
+    }
+}
+
+/*
+ * Failures reported to one ChexShared, in the order they were reported.
+ */
+pub(crate) struct ErrorLog {
+    entries: Mutex<Vec<CollectedError>>,
+    dropped: AtomicUsize,
+}
+
+impl ErrorLog {
+    pub(crate) fn new() -> Self {
+        Self {
+            entries: Mutex::new(Vec::new()),
+            dropped: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn record(&self, reason: ExitReasonKind, message: String, origin: ExitOrigin) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() >= CAPACITY {
+            self.dropped.fetch_add(1, Relaxed);
+            return;
+        }
+        entries.push(CollectedError {
+            reason,
+            message,
+            origin,
+            at: Instant::now(),
+        });
+    }
+
+    pub(crate) fn entries(&self) -> Vec<CollectedError> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Failures not kept because the log was full.
+    pub(crate) fn dropped(&self) -> usize {
+        self.dropped.load(Relaxed)
+    }
+}
@@ -0,0 +1,91 @@
+//! C ABI for C/C++ code sharing a process with the global Chex.
+//!
+//! Enabled with the "ffi" feature.  Build your Rust binary (or a staticlib/cdylib wrapping this
+//! crate) with it and declare the functions from include/chex.h on the C side.  All functions
+//! operate on the same global Chex as the Rust API, and none of them panic or exit the process.
+#![allow(unsafe_code)]
+/*
+ * The only unsafe code here is #[no_mangle]: every exported name is prefixed with chex_, so none
+ * collides with another symbol.
+ */
+
+use crate::GLOBAL_CHECK_EXIT;
+use std::ffi::c_int;
+
+/// Initialize the global Chex, as Chex::init().  Returns 0.  Calling it when Chex is already
+/// initialized is harmless.
+#[no_mangle]
+pub extern "C" fn chex_init(exit_on_panic: bool) -> c_int {
+    crate::Chex::init(exit_on_panic);
+    0
+}
+
+/// Signal exit to every listener.  Returns 1 if this call was the first to signal exit, 0 if
+/// exit had already been signalled, and -1 if Chex is not initialized or signalling failed.
+///
+/// The recorded ExitOrigin points at this function, not at the C caller.
+#[no_mangle]
+pub extern "C" fn chex_signal_exit() -> c_int {
+    match GLOBAL_CHECK_EXIT.try_signal_exit() {
+        Ok(true) => 1,
+        Ok(false) => 0,
+        Err(_) => -1,
+    }
+}
+
+/// Returns 1 if exit has been signalled, 0 if not, and -1 if Chex is not initialized.
+#[no_mangle]
+pub extern "C" fn chex_poll_exit() -> c_int {
+    match GLOBAL_CHECK_EXIT.try_poll_exit() {
+        Ok(exited) => exited as c_int,
+        Err(_) => -1,
+    }
+}
+
+/// A file descriptor which becomes readable once exit has been signalled, for use with
+/// poll()/select()/epoll in C event loops.  The fd is owned by chex and must not be closed or
+/// read from; every call returns the same fd.  Returns -1 if Chex is not initialized or the fd
+/// could not be created.
+#[cfg(unix)]
+#[no_mangle]
+pub extern "C" fn chex_exit_fd() -> c_int {
+    use std::os::fd::AsRawFd;
+
+    if GLOBAL_CHECK_EXIT.try_poll_exit().is_err() {
+        return -1;
+    }
+    match exit_fd::get() {
+        Some(reader) => reader.as_raw_fd(),
+        None => -1,
+    }
+}
+
+#[cfg(unix)]
+mod exit_fd {
+    use crate::GLOBAL_CHECK_EXIT;
+    use crate::sink::{self,ChexEvent};
+    use std::io::{PipeReader,Write};
+    use std::sync::OnceLock;
+
+    static EXIT_FD: OnceLock<Option<PipeReader>> = OnceLock::new();
+
+    /// Create the pipe on first use, with a thread that writes to it once exit is signalled.
+    pub(super) fn get() -> Option<&'static PipeReader> {
+        EXIT_FD.get_or_init(|| {
+            let (reader, mut writer) = std::io::pipe().ok()?;
+            let spawned = std::thread::Builder::new()
+                .name("chex-ffi-fd".to_string())
+                .spawn(move || {
+                    GLOBAL_CHECK_EXIT.block_until_exit();
+                    let _ = writer.write_all(&[1]);
+                });
+            match spawned {
+                Ok(_) => Some(reader),
+                Err(e) => {
+                    sink::emit(ChexEvent::ThreadSpawnFailed { thread: "chex-ffi-fd", error: &e });
+                    None
+                }
+            }
+        }).as_ref()
+    }
+}
@@ -0,0 +1,168 @@
+//! A bare exit signal which only needs core and alloc, for no_std targets (build with
+//! `default-features = false`) where everything else in this crate, which relies on threads,
+//! panic hooks and OS services, is unavailable.
+//!
+//! The flag is a portable-atomic AtomicBool, so it also works on targets without native atomic
+//! CAS (enable portable-atomic's "critical-section" or "unsafe-assume-single-core" there).
+//! Wakers of async waiters live behind a spin lock.
+//!
+//! ```
+//! use chex::flag::ExitFlag;
+//!
+//! static EXIT: ExitFlag = ExitFlag::new();
+//!
+//! assert!(EXIT.signal_exit());
+//! assert!(EXIT.poll_exit());
+//! ```
+
+use alloc::vec::Vec;
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{fence,Ordering::{Acquire,Release,SeqCst}};
+use core::task::{Context,Poll,Waker};
+use portable_atomic::AtomicBool;
+
+/*
+ * An exit signal: raised once, observed by polling or awaiting wait().  Usually a static, since
+ * new() is const.
+ */
+pub struct ExitFlag {
+    exit: AtomicBool,
+    waiters: spin::Mutex<Waiters>,
+}
+
+/*
+ * Wakers by slot, as in the std coordinator.  Slots are reused, so waiting in a select loop
+ * doesn't grow this.
+ */
+struct Waiters {
+    slots: Vec<Option<Waker>>,
+    free: Vec<usize>,
+}
+
+impl ExitFlag {
+    pub const fn new() -> Self {
+        Self {
+            exit: AtomicBool::new(false),
+            waiters: spin::Mutex::new(Waiters { slots: Vec::new(), free: Vec::new() }),
+        }
+    }
+
+    /// Returns true iff exit has been signalled.  A true result makes the signaller's earlier
+    /// writes visible.
+    pub fn poll_exit(&self) -> bool {
+        self.exit.load(Acquire)
+    }
+
+    /// Signal exit and wake every task awaiting wait().  Never blocks, so it may be called from
+    /// an interrupt handler (provided the executor's wakers may be woken there).
+    ///
+    /// Returns true iff this call was the first to signal exit.
+    pub fn signal_exit(&self) -> bool {
+        let first = !self.exit.swap(true, Release);
+        self.wake_if_exited();
+        first
+    }
+
+    /// A future which resolves once exit is signalled.
+    pub fn wait(&self) -> ExitFlagWait<'_> {
+        ExitFlagWait { flag: self, slot: None }
+    }
+
+    /// Wake every waiter if exit was signalled.  signal_exit() only try-locks, so whoever holds
+    /// the lock calls this after releasing it, and one of the two sees the other.
+    fn wake_if_exited(&self) {
+        fence(SeqCst);
+        if !self.exit.load(SeqCst) {
+            return;
+        }
+        let Some(mut waiters) = self.waiters.try_lock() else {
+            return;
+        };
+        waiters.free.clear();
+        let slots = core::mem::take(&mut waiters.slots);
+        drop(waiters);
+        for waker in slots.into_iter().flatten() {
+            waker.wake();
+        }
+    }
+}
+
+impl Default for ExitFlag {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl core::fmt::Debug for ExitFlag {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ExitFlag").field("exit", &self.poll_exit()).finish()
+    }
+}
+
+/*
+ * Future returned by ExitFlag::wait().  Releases its slot when dropped.
+ */
+pub struct ExitFlagWait<'a> {
+    flag: &'a ExitFlag,
+    slot: Option<usize>,
+}
+
+impl Future for ExitFlagWait<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.flag.poll_exit() {
+            return Poll::Ready(());
+        }
+
+        let flag = self.flag;
+        let mut waiters = flag.waiters.lock();
+        let slot = match self.slot {
+            Some(slot) if slot < waiters.slots.len() => {
+                match &mut waiters.slots[slot] {
+                    Some(waker) if waker.will_wake(cx.waker()) => {}
+                    waker => *waker = Some(cx.waker().clone()),
+                }
+                slot
+            }
+            _ => {
+                let waker = Some(cx.waker().clone());
+                match waiters.free.pop() {
+                    Some(slot) => {
+                        waiters.slots[slot] = waker;
+                        slot
+                    }
+                    None => {
+                        waiters.slots.push(waker);
+                        waiters.slots.len() - 1
+                    }
+                }
+            }
+        };
+        drop(waiters);
+        self.slot = Some(slot);
+
+        flag.wake_if_exited();
+        if flag.poll_exit() {
+            return Poll::Ready(());
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for ExitFlagWait<'_> {
+    fn drop(&mut self) {
+        let Some(slot) = self.slot else { return };
+        let mut waiters = self.flag.waiters.lock();
+        /*
+         * wake_if_exited() already emptied the slots if exit was signalled.
+         */
+        if let Some(waker) = waiters.slots.get_mut(slot) {
+            *waker = None;
+            waiters.free.push(slot);
+        }
+        drop(waiters);
+        self.flag.wake_if_exited();
+    }
+}
@@ -0,0 +1,49 @@
+//! Giving the global Chex a fresh coordinator in a child created with fork(), see
+//! Chex::reinit_after_fork().
+//!
+//! Enabled with the "fork" feature, on unix only.  The child's copy of the coordinator is left
+//! behind rather than freed: other code may still hold references into it, and its threads
+//! (timers, watchdogs, the SIGTERM reader) only ever existed in the parent.
+#![allow(unsafe_code)]
+
+use crate::ChexInstance;
+use std::sync::atomic::{AtomicPtr,AtomicU32};
+use std::sync::atomic::Ordering::{Acquire,Relaxed,Release};
+
+/*
+ * The coordinator installed by the latest reinit(), or null if this process never reinitialized.
+ * Only ever points to a leaked Box, so it stays valid for the rest of the process.
+ */
+static CURRENT: AtomicPtr<ChexInstance> = AtomicPtr::new(std::ptr::null_mut());
+
+/*
+ * The process which created the current coordinator, 0 until Chex is initialized.
+ */
+static OWNER: AtomicU32 = AtomicU32::new(0);
+
+pub(crate) fn current() -> Option<&'static ChexInstance> {
+    let ptr = CURRENT.load(Acquire);
+    if ptr.is_null() {
+        return None;
+    }
+    // SAFETY: never freed, see CURRENT.
+    Some(unsafe { &*ptr })
+}
+
+/// Record this process as the owner of the coordinator created by Chex::init().
+pub(crate) fn set_owner() {
+    OWNER.store(std::process::id(), Relaxed);
+}
+
+/// True iff the current coordinator was created by another process, ie this is a forked child
+/// which has not reinitialized yet.
+pub(crate) fn is_stale() -> bool {
+    let owner = OWNER.load(Relaxed);
+    owner != 0 && owner != std::process::id()
+}
+
+/// Make instance the current coordinator, owned by this process.
+pub(crate) fn reinit(instance: ChexInstance) {
+    CURRENT.store(Box::into_raw(Box::new(instance)), Release);
+    set_owner();
+}
@@ -0,0 +1,211 @@
+//! Signalling exit when a watched path appears, disappears or changes, ie a stop file touched
+//! by an operator or a pidfile being removed.
+//!
+//! Enabled with the "fswatch" feature.  On Linux a "chex-fswatch" thread sleeps on inotify
+//! events for the parent directory; elsewhere it polls the path's metadata.
+#![allow(unsafe_code)]
+
+use crate::{ChexShared,ExitOrigin,ExitReason};
+use crate::sink::{self,ChexEvent};
+use std::path::{Path,PathBuf};
+use std::sync::Weak;
+use std::time::{Duration,SystemTime};
+
+/*
+ * What makes a PathWatch fire.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PathTrigger {
+    /// The path exists, including when watching starts.
+    Appears,
+    /// The path does not exist, including when watching starts.
+    Disappears,
+    /// The path was created, removed, or its size or modification time changed since watching
+    /// started.
+    Changes,
+}
+
+/*
+ * A path to watch with ChexInstance::exit_on_path().
+ */
+#[derive(Clone, Debug)]
+pub struct PathWatch {
+    path: PathBuf,
+    trigger: PathTrigger,
+    poll_interval: Duration,
+}
+
+impl PathWatch {
+    pub fn new(path: impl Into<PathBuf>, trigger: PathTrigger) -> Self {
+        Self {
+            path: path.into(),
+            trigger,
+            poll_interval: Duration::from_millis(500),
+        }
+    }
+
+    /// Signal exit once path exists, ie a stop file.
+    pub fn appears(path: impl Into<PathBuf>) -> Self {
+        Self::new(path, PathTrigger::Appears)
+    }
+
+    /// Signal exit once path is gone, ie a pidfile.
+    pub fn disappears(path: impl Into<PathBuf>) -> Self {
+        Self::new(path, PathTrigger::Disappears)
+    }
+
+    /// Signal exit once path is created, removed or modified.
+    pub fn changes(path: impl Into<PathBuf>) -> Self {
+        Self::new(path, PathTrigger::Changes)
+    }
+
+    /// How often to check the path where inotify is unavailable; with inotify, how often to
+    /// check whether the coordinator is gone.  Defaults to 500ms.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+}
+
+/*
+ * The parts of a path's metadata which Changes compares.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct State {
+    exists: bool,
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl State {
+    fn of(path: &Path) -> Self {
+        match std::fs::metadata(path) {
+            Ok(m) => Self { exists: true, len: m.len(), modified: m.modified().ok() },
+            Err(_) => Self { exists: false, len: 0, modified: None },
+        }
+    }
+}
+
+pub(crate) fn watch(shared: Weak<ChexShared>, origin: ExitOrigin, watch: PathWatch) {
+    let initial = State::of(&watch.path);
+    let spawned = std::thread::Builder::new()
+        .name("chex-fswatch".to_string())
+        .spawn(move || {
+            let mut wakeup = Wakeup::new(&watch.path, watch.poll_interval);
+            loop {
+                let Some(shared) = shared.upgrade() else { return };
+                if shared.poll_exit() {
+                    return;
+                }
+                let state = State::of(&watch.path);
+                let fired = match watch.trigger {
+                    PathTrigger::Appears => state.exists,
+                    PathTrigger::Disappears => !state.exists,
+                    PathTrigger::Changes => state != initial,
+                };
+                if fired {
+                    shared.signal_exit_from(ExitReason::Manual, origin);
+                    return;
+                }
+                drop(shared);
+                wakeup.wait();
+            }
+        });
+    if let Err(e) = spawned {
+        sink::emit(ChexEvent::ThreadSpawnFailed { thread: "chex-fswatch", error: &e });
+    }
+}
+
+#[cfg(target_os = "linux")]
+use inotify::Wakeup;
+
+#[cfg(target_os = "linux")]
+mod inotify {
+    use libc::c_int;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+    use std::time::Duration;
+
+    const MASK: u32 = libc::IN_MODIFY | libc::IN_ATTRIB | libc::IN_CLOSE_WRITE | libc::IN_MOVED_FROM |
+        libc::IN_MOVED_TO | libc::IN_CREATE | libc::IN_DELETE | libc::IN_DELETE_SELF | libc::IN_MOVE_SELF;
+
+    /*
+     * Wakes up on any event in the watched path's directory, or after the poll interval.
+     * Falls back to sleeping if inotify is unavailable.
+     */
+    pub(super) struct Wakeup {
+        fd: c_int,
+        interval: Duration,
+    }
+
+    impl Drop for Wakeup {
+        fn drop(&mut self) {
+            if self.fd >= 0 {
+                // SAFETY: fd is the inotify instance owned by this Wakeup.
+                unsafe {
+                    libc::close(self.fd);
+                }
+            }
+        }
+    }
+
+    impl Wakeup {
+        pub(super) fn new(path: &Path, interval: Duration) -> Self {
+            let dir = match path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir,
+                _ => Path::new("."),
+            };
+            let Ok(dir) = std::ffi::CString::new(dir.as_os_str().as_bytes()) else {
+                return Self { fd: -1, interval };
+            };
+            // SAFETY: inotify_init1(2) takes no pointers.
+            let fd = unsafe { libc::inotify_init1(libc::IN_CLOEXEC) };
+            // SAFETY: dir is a NUL-terminated CString which outlives the call.
+            if fd >= 0 && unsafe { libc::inotify_add_watch(fd, dir.as_ptr(), MASK) } < 0 {
+                // SAFETY: fd was just created above and nothing else refers to it.
+                unsafe {
+                    libc::close(fd);
+                }
+                return Self { fd: -1, interval };
+            }
+            Self { fd, interval }
+        }
+
+        pub(super) fn wait(&mut self) {
+            if self.fd < 0 {
+                std::thread::sleep(self.interval);
+                return;
+            }
+            let mut pfd = libc::pollfd { fd: self.fd, events: libc::POLLIN, revents: 0 };
+            let timeout = c_int::try_from(self.interval.as_millis()).unwrap_or(c_int::MAX);
+            // SAFETY: pfd is a live local array of one pollfd.
+            if unsafe { libc::poll(&mut pfd, 1, timeout) } > 0 {
+                /*
+                 * Drain the events; the caller rechecks the path itself.
+                 */
+                let mut buf = [0u8; 4096];
+                // SAFETY: buf is a live local of buf.len() writable bytes.
+                unsafe {
+                    libc::read(self.fd, buf.as_mut_ptr().cast(), buf.len());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+struct Wakeup {
+    interval: Duration,
+}
+
+#[cfg(not(target_os = "linux"))]
+impl Wakeup {
+    fn new(_path: &Path, interval: Duration) -> Self {
+        Self { interval }
+    }
+
+    fn wait(&mut self) {
+        std::thread::sleep(self.interval);
+    }
+}
@@ -0,0 +1,93 @@
+//! RAII guards which signal exit when dropped.
+
+use crate::{Chex,ChexShared,ExitOrigin,ExitReason};
+use std::panic::Location;
+use std::sync::Arc;
+
+/*
+ * Returned by Chex::init_guarded() and ChexBuilder::init_guarded().  Hold it in main(): once it
+ * is dropped, because main returned (ie early with `?`) or is unwinding, exit is signalled with
+ * ExitReason::Manual and the init_guarded() call as origin, so background threads don't keep
+ * running unnoticed until process teardown.  Derefs to the global Chex.
+ */
+#[must_use = "exit is signalled as soon as the MainGuard is dropped"]
+#[derive(Debug)]
+pub struct MainGuard {
+    chex: &'static Chex,
+    origin: ExitOrigin,
+}
+
+impl MainGuard {
+    pub(crate) fn new(chex: &'static Chex, location: &'static Location<'static>) -> Self {
+        Self { chex, origin: ExitOrigin::from_location(location) }
+    }
+
+    pub fn chex(&self) -> &'static Chex {
+        self.chex
+    }
+}
+
+impl std::ops::Deref for MainGuard {
+    type Target = Chex;
+
+    fn deref(&self) -> &Chex {
+        self.chex
+    }
+}
+
+impl Drop for MainGuard {
+    fn drop(&mut self) {
+        if let Some(c) = self.chex.instance() {
+            c.shared.signal_exit_from(ExitReason::Manual, self.origin.clone());
+        }
+    }
+}
+
+/*
+ * Returned by ChexInstance::exit_on_drop().  Held by a critical worker (ie a consensus loop)
+ * which must never stop on its own: when it is dropped exit is signalled with the
+ * exit_on_drop() call as origin, with ExitReason::Panic if the worker is unwinding and
+ * ExitReason::Error otherwise.  Has no effect if exit was already signalled, ie when the worker
+ * stops because of it.
+ */
+#[must_use = "exit is signalled as soon as the ChexGuard is dropped"]
+pub struct ChexGuard {
+    shared: Option<Arc<ChexShared>>,
+    origin: ExitOrigin,
+}
+
+impl ChexGuard {
+    pub(crate) fn new(shared: Arc<ChexShared>, location: &'static Location<'static>) -> Self {
+        Self { shared: Some(shared), origin: ExitOrigin::from_location(location) }
+    }
+
+    /// Drop the guard without signalling exit, ie for a deliberate, orderly stop.
+    pub fn disarm(mut self) {
+        self.shared = None;
+    }
+}
+
+impl std::fmt::Debug for ChexGuard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChexGuard")
+            .field("armed", &self.shared.is_some())
+            .field("origin", &self.origin)
+            .finish()
+    }
+}
+
+impl Drop for ChexGuard {
+    fn drop(&mut self) {
+        let Some(shared) = self.shared.take() else { return };
+        if shared.poll_exit() {
+            return;
+        }
+        let worker = std::thread::current().name().unwrap_or("<unnamed>").to_string();
+        let reason = if std::thread::panicking() {
+            ExitReason::Panic { message: format!("critical worker on thread {worker} unwound") }
+        } else {
+            ExitReason::Error { message: format!("critical worker on thread {worker} stopped") }
+        };
+        shared.signal_exit_from(reason, self.origin.clone());
+    }
+}
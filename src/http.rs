@@ -0,0 +1,104 @@
+//! Graceful-shutdown futures for HTTP servers, ie hyper and axum.
+//!
+//! Enabled with the "http" feature.  These only await the exit signal, so they work with any
+//! async runtime.
+
+use crate::ChexInstance;
+use std::future::Future;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::sync::{Arc,Mutex};
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::task::{Context,Poll,Waker};
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::Duration;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use crate::clock::Clock;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use crate::sink::{self,ChexEvent};
+
+/// Resolves once exit is signalled on the global Chex, for
+/// `axum::serve(listener, app).with_graceful_shutdown(chex::http::shutdown_signal())` or
+/// hyper's `GracefulShutdown`.
+///
+/// Panics if Chex is not initialized.
+pub fn shutdown_signal() -> impl Future<Output = ()> + Send + 'static {
+    shutdown_signal_for(crate::Chex::get_chex_instance())
+}
+
+/// Like shutdown_signal(), for the coordinator of ci.
+pub async fn shutdown_signal_for(mut ci: ChexInstance) {
+    ci.check_exit_async().await
+}
+
+/// Drive serve (ie the graceful-shutdown server future) to completion, but give up once
+/// deadline has elapsed (on ci's configured clock) after exit was signalled on ci.  Returns
+/// None if the deadline hit, in which case serve is dropped with whatever connections it still
+/// had open.
+///
+/// Connections spawned onto the runtime by the server are not owned by serve; they are
+/// aborted when the runtime shuts down, ie when main() returns.
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub async fn serve_with_deadline<F: Future>(serve: F, ci: ChexInstance, deadline: Duration) -> Option<F::Output> {
+    let clock = ci.shared.config.timer_clock();
+    let mut serve = std::pin::pin!(serve);
+    let mut exit = std::pin::pin!(shutdown_signal_for(ci));
+    let mut timer: Option<Timer> = None;
+
+    std::future::poll_fn(|cx| {
+        if let Poll::Ready(output) = serve.as_mut().poll(cx) {
+            return Poll::Ready(Some(output));
+        }
+        if timer.is_none() && exit.as_mut().poll(cx).is_ready() {
+            timer = Some(Timer::start(clock.clone(), deadline));
+        }
+        match &timer {
+            Some(timer) if timer.poll(cx) => Poll::Ready(None),
+            _ => Poll::Pending,
+        }
+    }).await
+}
+
+/*
+ * Runtime-agnostic one-shot timer, backed by a "chex-http-deadline" thread.
+ */
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+struct Timer {
+    state: Arc<Mutex<(bool, Option<Waker>)>>,
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+impl Timer {
+    fn start(clock: Arc<dyn Clock>, after: Duration) -> Self {
+        let state = Arc::new(Mutex::new((false, None::<Waker>)));
+        let fire = state.clone();
+        let spawned = std::thread::Builder::new()
+            .name("chex-http-deadline".to_string())
+            .spawn(move || {
+                clock.sleep(after);
+                let waker = {
+                    let mut state = fire.lock().unwrap_or_else(|e| e.into_inner());
+                    state.0 = true;
+                    state.1.take()
+                };
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+            });
+        /*
+         * Without the timer thread the deadline never hits, and shutdown stays graceful.
+         */
+        if let Err(e) = spawned {
+            sink::emit(ChexEvent::ThreadSpawnFailed { thread: "chex-http-deadline", error: &e });
+        }
+        Self { state }
+    }
+
+    /// Whether the timer has fired; otherwise register cx to be woken when it does.
+    fn poll(&self, cx: &mut Context<'_>) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if !state.0 {
+            state.1 = Some(cx.waker().clone());
+        }
+        state.0
+    }
+}
@@ -0,0 +1,125 @@
+//! Shutdown interceptors, which hold off the exit broadcast for a bounded time.
+
+use crate::ChexConfig;
+use crate::sink::{self,ChexEvent};
+use std::sync::{Arc,Condvar,Mutex};
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::Relaxed;
+use std::time::Duration;
+
+/*
+ * Interceptors registered with one ChexShared.
+ */
+pub(crate) struct InterceptorRegistry {
+    interceptors: Mutex<Vec<Arc<InterceptorState>>>,
+    released_cv: Condvar,
+    pending: AtomicBool,
+}
+
+struct InterceptorState {
+    name: String,
+    max_delay: Duration,
+    released: AtomicBool,
+}
+
+/*
+ * Handle returned by ChexInstance::intercept().  Once exit_pending() returns true, finish the
+ * in-flight work and call release() (or drop the handle) to let the exit signal fan out.
+ */
+pub struct Interceptor {
+    state: Arc<InterceptorState>,
+    registry: Arc<InterceptorRegistry>,
+}
+
+impl Interceptor {
+    /// Whether exit has been signalled and is being held off for this interceptor.
+    pub fn exit_pending(&self) -> bool {
+        self.registry.pending.load(Relaxed)
+    }
+
+    /// Stop holding off exit.
+    pub fn release(self) {}
+
+    pub fn name(&self) -> &str {
+        &self.state.name
+    }
+
+    /// Longest this interceptor may hold off exit.
+    pub fn max_delay(&self) -> Duration {
+        self.state.max_delay
+    }
+}
+
+impl Drop for Interceptor {
+    fn drop(&mut self) {
+        self.state.released.store(true, Relaxed);
+        let mut interceptors = self.registry.interceptors.lock().unwrap_or_else(|e| e.into_inner());
+        interceptors.retain(|i| !Arc::ptr_eq(i, &self.state));
+        self.registry.released_cv.notify_all();
+    }
+}
+
+impl InterceptorRegistry {
+    pub(crate) fn new() -> Self {
+        Self {
+            interceptors: Mutex::new(Vec::new()),
+            released_cv: Condvar::new(),
+            pending: AtomicBool::new(false),
+        }
+    }
+
+    /// Whether exit has been signalled and is being held off.
+    pub(crate) fn pending(&self) -> bool {
+        self.pending.load(Relaxed)
+    }
+
+    pub(crate) fn register(self: &Arc<Self>, name: &str, max_delay: Duration) -> Interceptor {
+        let state = Arc::new(InterceptorState {
+            name: name.to_string(),
+            max_delay,
+            released: AtomicBool::new(false),
+        });
+        self.interceptors.lock().unwrap_or_else(|e| e.into_inner()).push(state.clone());
+
+        Interceptor {
+            state,
+            registry: self.clone(),
+        }
+    }
+
+    /// Called by the first signaller before exit fans out: mark exit pending, then wait until
+    /// every interceptor has released or used up its max_delay, on config's clock.  Later
+    /// signallers wait for the
+    /// flag the first one raises after this, so the hold gates them too.
+    pub(crate) fn hold(&self, config: &ChexConfig) {
+        self.pending.store(true, Relaxed);
+
+        let start = config.now();
+        let mut interceptors = self.interceptors.lock().unwrap_or_else(|e| e.into_inner());
+        let mut expired: Vec<Arc<InterceptorState>> = Vec::new();
+        loop {
+            let now = config.now();
+            let mut wait = None;
+            for i in interceptors.iter().filter(|i| !i.released.load(Relaxed)) {
+                let deadline = start + i.max_delay;
+                if deadline <= now {
+                    if !expired.iter().any(|e| Arc::ptr_eq(e, i)) {
+                        expired.push(i.clone());
+                    }
+                } else {
+                    wait = Some(wait.map_or(deadline - now, |w: Duration| w.min(deadline - now)));
+                }
+            }
+
+            let Some(wait) = wait else { break };
+            interceptors = self.released_cv.wait_timeout(interceptors, config.wait_slice(wait))
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+        drop(interceptors);
+
+        for i in expired {
+            sink::emit(ChexEvent::InterceptorExpired { name: &i.name, max_delay: i.max_delay });
+        }
+    }
+}
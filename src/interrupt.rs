@@ -0,0 +1,108 @@
+//! Interrupting threads blocked outside chex once exit is signalled, ie parked with
+//! std::thread::park() or stuck in a blocking read() on a socket.
+
+use std::sync::{Arc,Mutex};
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering::Relaxed;
+
+/// Something which can break a thread out of a blocking call.  Called once exit is signalled,
+/// from the signalling thread, so it must not block.
+pub trait Interrupt: Send + Sync {
+    fn interrupt(&self);
+}
+
+/*
+ * Unparks the thread.
+ */
+impl Interrupt for std::thread::Thread {
+    fn interrupt(&self) {
+        self.unpark();
+    }
+}
+
+/*
+ * Shuts down both directions, so a blocking read() returns 0 and a write() fails.
+ */
+impl Interrupt for std::net::TcpStream {
+    fn interrupt(&self) {
+        let _ = self.shutdown(std::net::Shutdown::Both);
+    }
+}
+
+#[cfg(unix)]
+impl Interrupt for std::os::unix::net::UnixStream {
+    fn interrupt(&self) {
+        let _ = self.shutdown(std::net::Shutdown::Both);
+    }
+}
+
+impl<T: Interrupt + ?Sized> Interrupt for Arc<T> {
+    fn interrupt(&self) {
+        (**self).interrupt();
+    }
+}
+
+/*
+ * Targets to interrupt when one ChexShared exits.
+ */
+pub(crate) struct InterruptRegistry {
+    targets: Mutex<Vec<(u64, Box<dyn Interrupt>)>>,
+    next_id: AtomicU64,
+}
+
+/*
+ * Returned by unpark_on_exit() and interrupt_on_exit().  The target stays registered until this
+ * is dropped.
+ */
+pub struct InterruptGuard {
+    registry: Arc<InterruptRegistry>,
+    id: u64,
+}
+
+impl Drop for InterruptGuard {
+    fn drop(&mut self) {
+        self.registry.targets.lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|(id, _)| *id != self.id);
+    }
+}
+
+impl InterruptRegistry {
+    pub(crate) fn new() -> Self {
+        Self {
+            targets: Mutex::new(Vec::new()),
+            next_id: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn register(self: &Arc<Self>, target: Box<dyn Interrupt>) -> InterruptGuard {
+        let id = self.next_id.fetch_add(1, Relaxed);
+        self.targets.lock().unwrap_or_else(|e| e.into_inner()).push((id, target));
+        InterruptGuard {
+            registry: self.clone(),
+            id,
+        }
+    }
+
+    /// Interrupt every registered target.
+    pub(crate) fn interrupt_all(&self) {
+        let targets = self.targets.lock().unwrap_or_else(|e| e.into_inner());
+        for (_, target) in targets.iter() {
+            target.interrupt();
+        }
+    }
+
+    /// Like interrupt_all(), but skipped if the lock is contended, as from the minimal panic
+    /// path which must not block.  A thread registering concurrently rechecks the exit flag
+    /// once it has registered.
+    pub(crate) fn try_interrupt_all(&self) {
+        let targets = match self.targets.try_lock() {
+            Ok(targets) => targets,
+            Err(std::sync::TryLockError::Poisoned(e)) => e.into_inner(),
+            Err(std::sync::TryLockError::WouldBlock) => return,
+        };
+        for (_, target) in targets.iter() {
+            target.interrupt();
+        }
+    }
+}
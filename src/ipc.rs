@@ -0,0 +1,110 @@
+//! Exit propagation between cooperating processes over a Unix domain socket.
+//!
+//! Enabled with the "ipc" feature, on unix only.  One process calls coordinate() to listen on a
+//! socket path, the others join() it.  Exit signalled in any of them is signalled in all of
+//! them, with ExitReason::Manual and the coordinate() / join() call as origin.  A participant
+//! whose coordinator goes away (ie was killed) signals exit too.
+
+use crate::{ChexInstance,ChexShared,ExitOrigin,ExitReason};
+use crate::interrupt::{Interrupt,InterruptGuard};
+use crate::sink::{self,ChexEvent};
+use std::io::{Read,Write};
+use std::os::unix::net::{UnixListener,UnixStream};
+use std::panic::Location;
+use std::path::Path;
+use std::sync::{Arc,Mutex,Weak};
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::Relaxed;
+
+/*
+ * The only message on the wire.
+ */
+const EXIT: u8 = b'X';
+
+/*
+ * Returned by coordinate() and join().  Local exits are sent to the other processes for as long
+ * as it is kept; exits from the other processes are received regardless.
+ */
+pub struct IpcLink {
+    _guard: InterruptGuard,
+}
+
+/*
+ * Sends EXIT to every peer, once.  Runs on the thread which signals exit locally.
+ */
+struct Notify {
+    peers: Arc<Mutex<Vec<UnixStream>>>,
+    sent: AtomicBool,
+}
+
+impl Interrupt for Notify {
+    fn interrupt(&self) {
+        if self.sent.swap(true, Relaxed) {
+            return;
+        }
+        for mut peer in self.peers.lock().unwrap_or_else(|e| e.into_inner()).drain(..) {
+            let _ = peer.write_all(&[EXIT]);
+        }
+    }
+}
+
+/// Listen on path for participants.  Fails if path exists, ie was left behind by a previous
+/// coordinator; remove it first.
+#[track_caller]
+pub fn coordinate<U>(ci: &ChexInstance<U>, path: impl AsRef<Path>) -> std::io::Result<IpcLink> {
+    let origin = ExitOrigin::from_location(Location::caller());
+    let listener = UnixListener::bind(path)?;
+    let peers = Arc::new(Mutex::new(Vec::new()));
+    let guard = ci.interrupt_on_exit(Notify { peers: peers.clone(), sent: AtomicBool::new(false) });
+
+    let shared = Arc::downgrade(&ci.shared);
+    spawn("chex-ipc-accept", move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let Some(exited) = shared.upgrade().map(|s| s.poll_exit()) else { return };
+            if exited {
+                let _ = stream.write_all(&[EXIT]);
+                continue;
+            }
+            let Ok(reader) = stream.try_clone() else { continue };
+            peers.lock().unwrap_or_else(|e| e.into_inner()).push(stream);
+            let (shared, origin) = (shared.clone(), origin.clone());
+            spawn("chex-ipc-peer", move || receive(reader, shared, origin, false));
+        }
+    });
+    Ok(IpcLink { _guard: guard })
+}
+
+/// Connect to the coordinator listening on path.
+#[track_caller]
+pub fn join<U>(ci: &ChexInstance<U>, path: impl AsRef<Path>) -> std::io::Result<IpcLink> {
+    let origin = ExitOrigin::from_location(Location::caller());
+    let stream = UnixStream::connect(path)?;
+    let reader = stream.try_clone()?;
+    let guard = ci.interrupt_on_exit(Notify {
+        peers: Arc::new(Mutex::new(vec![stream])),
+        sent: AtomicBool::new(false),
+    });
+
+    let shared = Arc::downgrade(&ci.shared);
+    spawn("chex-ipc", move || receive(reader, shared, origin, true));
+    Ok(IpcLink { _guard: guard })
+}
+
+/// Signal exit once EXIT arrives, or (if exit_on_eof) once the other side hangs up.
+fn receive(mut stream: UnixStream, shared: Weak<ChexShared>, origin: ExitOrigin, exit_on_eof: bool) {
+    let mut buf = [0u8; 1];
+    let exit = match stream.read(&mut buf) {
+        Ok(1) => buf[0] == EXIT,
+        _ => exit_on_eof,
+    };
+    if let (true, Some(shared)) = (exit, shared.upgrade()) {
+        shared.signal_exit_from(ExitReason::Manual, origin);
+    }
+}
+
+fn spawn(name: &'static str, f: impl FnOnce() + Send + 'static) {
+    if let Err(e) = std::thread::Builder::new().name(name.to_string()).spawn(f) {
+        sink::emit(ChexEvent::ThreadSpawnFailed { thread: name, error: &e });
+    }
+}
@@ -0,0 +1,154 @@
+//! Threads and tasks adopted by a coordinator, joined together during teardown.
+
+use crate::Instant;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/*
+ * How often join_all() checks whether adopted handles have finished.
+ */
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+trait Adopted: Send {
+    fn name(&self) -> String;
+    fn is_finished(&self) -> bool;
+    /// Collect a finished handle.  Returns false if it panicked, or its result is unavailable.
+    fn join(self: Box<Self>) -> bool;
+}
+
+impl<T: Send + 'static> Adopted for std::thread::JoinHandle<T> {
+    fn name(&self) -> String {
+        self.thread().name().unwrap_or("<unnamed>").to_string()
+    }
+
+    fn is_finished(&self) -> bool {
+        std::thread::JoinHandle::is_finished(self)
+    }
+
+    fn join(self: Box<Self>) -> bool {
+        std::thread::JoinHandle::join(*self).is_ok()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T: Send + 'static> Adopted for tokio::task::JoinHandle<T> {
+    fn name(&self) -> String {
+        "<tokio task>".to_string()
+    }
+
+    fn is_finished(&self) -> bool {
+        tokio::task::JoinHandle::is_finished(self)
+    }
+
+    fn join(mut self: Box<Self>) -> bool {
+        use std::future::Future;
+
+        /*
+         * The task has finished, so its output is ready without a runtime.  Should it still be
+         * pending, its result is unknown and must not be reported as a clean join.
+         */
+        let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+        match std::pin::Pin::new(&mut *self).poll(&mut cx) {
+            std::task::Poll::Ready(result) => result.is_ok(),
+            std::task::Poll::Pending => false,
+        }
+    }
+}
+
+/*
+ * Returned by join_all(): the name of every adopted thread or task, by outcome.
+ */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct JoinReport {
+    joined: Vec<String>,
+    panicked: Vec<String>,
+    timed_out: Vec<String>,
+}
+
+impl JoinReport {
+    /// Finished normally.
+    pub fn joined(&self) -> &[String] {
+        &self.joined
+    }
+
+    /// Finished by panicking (or, for tokio tasks, by being cancelled).
+    pub fn panicked(&self) -> &[String] {
+        &self.panicked
+    }
+
+    /// Still running at the timeout.  They stay adopted, so a later join_all() retries them.
+    pub fn timed_out(&self) -> &[String] {
+        &self.timed_out
+    }
+
+    /// True iff every adopted handle finished without panicking.
+    pub fn is_complete(&self) -> bool {
+        self.panicked.is_empty() && self.timed_out.is_empty()
+    }
+}
+
+impl std::fmt::Display for JoinReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "joined: [{}]; panicked: [{}]; timed out: [{}]",
+            self.joined.join(", "),
+            self.panicked.join(", "),
+            self.timed_out.join(", "),
+        )
+    }
+}
+
+/*
+ * Handles adopted by one ChexShared.
+ */
+pub(crate) struct JoinRegistry {
+    handles: Mutex<Vec<Box<dyn Adopted>>>,
+}
+
+impl JoinRegistry {
+    pub(crate) fn new() -> Self {
+        Self {
+            handles: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub(crate) fn adopt_thread<T: Send + 'static>(&self, handle: std::thread::JoinHandle<T>) {
+        self.handles.lock().unwrap_or_else(|e| e.into_inner()).push(Box::new(handle));
+    }
+
+    #[cfg(feature = "tokio")]
+    pub(crate) fn adopt_task<T: Send + 'static>(&self, handle: tokio::task::JoinHandle<T>) {
+        self.handles.lock().unwrap_or_else(|e| e.into_inner()).push(Box::new(handle));
+    }
+
+    /// Join every adopted handle which finishes before timeout.
+    pub(crate) fn join_all(&self, timeout: Duration) -> JoinReport {
+        let deadline = Instant::now() + timeout;
+        let mut pending = std::mem::take(&mut *self.handles.lock().unwrap_or_else(|e| e.into_inner()));
+        let mut report = JoinReport::default();
+
+        loop {
+            let (finished, running): (Vec<_>, Vec<_>) = pending.into_iter().partition(|h| h.is_finished());
+            pending = running;
+            for handle in finished {
+                let name = handle.name();
+                if handle.join() {
+                    report.joined.push(name);
+                } else {
+                    report.panicked.push(name);
+                }
+            }
+
+            let now = Instant::now();
+            if pending.is_empty() || now >= deadline {
+                break;
+            }
+            std::thread::sleep(POLL_INTERVAL.min(deadline - now));
+        }
+
+        report.timed_out = pending.iter().map(|h| h.name()).collect();
+        self.handles.lock().unwrap_or_else(|e| e.into_inner()).extend(pending);
+        report
+    }
+}
@@ -0,0 +1,172 @@
+//! How long listeners took to observe the exit signal.
+
+use std::sync::Mutex;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::Relaxed;
+use std::time::Duration;
+
+/*
+ * Samples kept per coordinator.  Later ones are counted but dropped.
+ */
+const SAMPLES: usize = 1024;
+
+/*
+ * How a listener observed exit.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum WakeupKind {
+    /// A registered listener acked (or dropped its guard).
+    Ack,
+    /// check_exit_async() resolved.
+    Async,
+    /// block_until_exit() returned.
+    Blocking,
+    /// poll_exit() first returned true on an instance.
+    Poll,
+}
+
+/*
+ * One listener observing exit, latency after it was first signalled.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WakeupSample {
+    kind: WakeupKind,
+    latency: Duration,
+    listener: Option<String>,
+    thread_name: Option<String>,
+}
+
+impl WakeupSample {
+    pub fn kind(&self) -> WakeupKind {
+        self.kind
+    }
+
+    pub fn latency(&self) -> Duration {
+        self.latency
+    }
+
+    /// Name of the registered listener, for WakeupKind::Ack.
+    pub fn listener(&self) -> Option<&str> {
+        self.listener.as_deref()
+    }
+
+    /// Name of the observing thread (the registering thread for WakeupKind::Ack), if named.
+    pub fn thread_name(&self) -> Option<&str> {
+        self.thread_name.as_deref()
+    }
+}
+
+/*
+ * Summary statistics over wakeup samples, see LatencySummary::of().
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LatencySummary {
+    count: usize,
+    min: Duration,
+    max: Duration,
+    mean: Duration,
+    p50: Duration,
+    p99: Duration,
+}
+
+impl LatencySummary {
+    /// Summarize samples, ie all of wakeup_latencies() or only those of one WakeupKind.
+    pub fn of<'a>(samples: impl IntoIterator<Item = &'a WakeupSample>) -> Self {
+        let mut latencies: Vec<Duration> = samples.into_iter().map(|s| s.latency).collect();
+        if latencies.is_empty() {
+            return Self::default();
+        }
+        latencies.sort();
+
+        let count = latencies.len();
+        let percentile = |p: usize| latencies[((count - 1) * p).div_ceil(100)];
+        Self {
+            count,
+            min: latencies[0],
+            max: latencies[count - 1],
+            mean: latencies.iter().sum::<Duration>() / count as u32,
+            p50: percentile(50),
+            p99: percentile(99),
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn min(&self) -> Duration {
+        self.min
+    }
+
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    pub fn mean(&self) -> Duration {
+        self.mean
+    }
+
+    pub fn p50(&self) -> Duration {
+        self.p50
+    }
+
+    pub fn p99(&self) -> Duration {
+        self.p99
+    }
+}
+
+impl std::fmt::Display for LatencySummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "n={} min={:?} p50={:?} p99={:?} max={:?} mean={:?}",
+            self.count, self.min, self.p50, self.p99, self.max, self.mean,
+        )
+    }
+}
+
+/*
+ * Wakeups observed on one ChexShared.  Acks are not recorded here, they are derived from the
+ * listener registry.
+ */
+pub(crate) struct WakeupRecorder {
+    samples: Mutex<Vec<WakeupSample>>,
+    dropped: AtomicUsize,
+}
+
+impl WakeupRecorder {
+    pub(crate) fn new() -> Self {
+        Self {
+            samples: Mutex::new(Vec::new()),
+            dropped: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn record(&self, kind: WakeupKind, latency: Duration) {
+        let mut samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        if samples.len() >= SAMPLES {
+            self.dropped.fetch_add(1, Relaxed);
+            return;
+        }
+        samples.push(WakeupSample {
+            kind,
+            latency,
+            listener: None,
+            thread_name: std::thread::current().name().map(|n| n.to_string()),
+        });
+    }
+
+    pub(crate) fn samples(&self) -> Vec<WakeupSample> {
+        self.samples.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+pub(crate) fn ack_sample(listener: &str, thread_name: Option<&str>, latency: Duration) -> WakeupSample {
+    WakeupSample {
+        kind: WakeupKind::Ack,
+        latency,
+        listener: Some(listener.to_string()),
+        thread_name: thread_name.map(|n| n.to_string()),
+    }
+}
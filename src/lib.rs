@@ -9,14 +9,18 @@
 //! 2. Acquired from anywhere with an associated function of the global Chex::get_chex_instance()
 //!
 //! ## Usage guidelines:
-//! 1. Very early in the main task/thread call Chex::init(set_exit_on_panic: bool).  After that a ChexInstance can be obtained immediately with .get_instance() and cloned as needed, or acquired at any other point in the program without holding a reference to the original &Chex returned from init, with the associated function Chex::get_chex_instance()
-//! 2. All threads and tasks which run for a significant amount of time should periodically check whether exit has been signalled, ie as a match within a tokio::select!() block or as a poll-check within non-async forever-loops.
+//! 1. Very early in the main task/thread call Chex::init(set_exit_on_panic: bool).  After that a ChexInstance can be obtained immediately with .get_instance() and cloned as needed, or acquired at any other point in the program without holding a reference to the original &Chex returned from init, with the associated function Chex::get_chex_instance().  Use Chex::builder() instead of init() for further settings, ie a grace period or SIGTERM handling.
+//! 2. All threads and tasks which run for a significant amount of time should periodically check whether exit has been signalled, ie as a match within a tokio::select!() block or as a poll-check within non-async forever-loops.  Threads with nothing else to do (ie main, once all workers are started) can park with .block_until_exit().
 //! 3. If panic!() on one thread should be caught to send the exit signal to all other ChexInstance listeners, initialize the library with Chex::init(true).  This behavior can also be enabled after the fact with Chex.set_exit_on_panic().
 //!
 //! See the examples/ folder for usage with a mix of independent tokio runtimes and non-async worker threads.
 //!
+//! Without the default "std" feature the crate is no_std, and only the bare exit signal of
+//! flag::ExitFlag remains.
+//!
 //! ## Basic usage example
 //! ```
+//! # #[cfg(feature = "std")] {
 //! use chex::{Chex,ChexInstance};
 //!
 //! let chex: &Chex = Chex::init(true);
@@ -28,171 +32,3035 @@
 //! assert!(ci_b.poll_exit());
 //! let ci_c = chex.get_instance();
 //! assert!(ci_c.poll_exit());
+//! # }
 //! ```
-#![forbid(unsafe_code)]
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unsafe_code)]
+#![deny(clippy::undocumented_unsafe_blocks)]
+
+extern crate alloc;
+
+#[cfg(feature = "actix")]
+pub mod actix;
+#[cfg(feature = "admin")]
+pub mod admin;
+#[cfg(feature = "std")]
+pub mod builder;
+#[cfg(all(feature = "chaos", not(all(target_arch = "wasm32", target_os = "unknown"))))]
+pub mod chaos;
+#[cfg(feature = "tokio")]
+pub mod channel;
+#[cfg(feature = "std")]
+pub mod clock;
+#[cfg(feature = "crossbeam")]
+pub mod crossbeam;
+#[cfg(feature = "std")]
+pub mod diagnostics;
+#[cfg(feature = "std")]
+mod env;
+#[cfg(feature = "std")]
+pub mod errors;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod flag;
+#[cfg(all(feature = "fork", unix))]
+mod fork;
+#[cfg(all(feature = "fswatch", not(all(target_arch = "wasm32", target_os = "unknown"))))]
+pub mod fswatch;
+#[cfg(feature = "std")]
+pub mod guard;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(feature = "std")]
+pub mod intercept;
+#[cfg(feature = "std")]
+pub mod interrupt;
+#[cfg(all(feature = "ipc", unix))]
+pub mod ipc;
+#[cfg(feature = "std")]
+pub mod join;
+#[cfg(feature = "std")]
+pub mod latency;
+#[cfg(feature = "std")]
+pub mod listener;
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub mod memory;
+#[cfg(feature = "std")]
+mod notify;
+#[cfg(feature = "std")]
+pub mod panic;
+#[cfg(all(feature = "std", unix))]
+pub mod parent;
+#[cfg(all(feature = "pid1", unix))]
+pub mod pid1;
+#[cfg(feature = "process")]
+pub mod process;
+#[cfg(feature = "std")]
+pub mod quiesce;
+#[cfg(feature = "rayon")]
+pub mod rayon;
+#[cfg(feature = "std")]
+pub mod readiness;
+#[cfg(feature = "std")]
+pub mod scope;
+#[cfg(feature = "std")]
+pub mod signal;
+#[cfg(all(feature = "sigterm", unix))]
+pub mod sigterm;
+#[cfg(all(feature = "shm", unix, target_pointer_width = "64"))]
+pub mod shm;
+#[cfg(feature = "std")]
+pub mod sink;
+#[cfg(feature = "std")]
+pub mod summary;
+#[cfg(feature = "std")]
+mod sync;
+#[cfg(all(feature = "systemd", target_os = "linux"))]
+pub mod systemd;
+#[cfg(feature = "std")]
+pub mod teardown;
+#[cfg(feature = "test-util")]
+pub mod testing;
+#[cfg(feature = "std")]
+pub mod trigger;
+#[cfg(feature = "tonic")]
+pub mod tonic;
+#[cfg(feature = "tower")]
+pub mod tower;
+#[cfg(feature = "std")]
+mod telemetry;
+#[cfg(feature = "std")]
+pub mod warning;
+#[cfg(feature = "std")]
+pub mod watchdog;
+
+#[cfg(feature = "std")]
+use diagnostics::ChexStateSnapshot;
+#[cfg(feature = "std")]
+use listener::{ListenerGuard,ListenerInfo,SubsystemHandle};
+#[cfg(feature = "std")]
+use panic::PanicReport;
+#[cfg(feature = "std")]
+use readiness::NotReady;
+#[cfg(feature = "std")]
+use sink::ChexEvent;
+#[cfg(feature = "std")]
+use summary::ExitSummary;
+#[cfg(feature = "std")]
+use warning::WarningSubscription;
+#[cfg(feature = "std")]
+use watchdog::Watchdog;
+#[cfg(feature = "std")]
+use std::panic::Location;
+#[cfg(feature = "std")]
+use std::sync::{Arc,Mutex,OnceLock,RwLock};
+#[cfg(feature = "std")]
+use std::sync::atomic::{AtomicBool,AtomicUsize};
+#[cfg(feature = "std")]
+use std::sync::atomic::Ordering::{Acquire,Relaxed,Release};
 
-use log::error;
-use std::sync::{Arc,OnceLock};
-use std::sync::atomic::AtomicBool;
-use std::sync::atomic::Ordering::Relaxed;
+/*
+ * std::time::Instant::now() and SystemTime::now() panic on wasm32-unknown-unknown, so
+ * timestamps come from web-time there.  Everywhere else these are the std::time types.
+ */
+#[cfg(all(feature = "std", not(all(target_arch = "wasm32", target_os = "unknown"))))]
+pub use std::time::{Instant,SystemTime};
+#[cfg(all(feature = "std", target_arch = "wasm32", target_os = "unknown"))]
+pub use web_time::{Instant,SystemTime};
 
+#[cfg(feature = "std")]
 static GLOBAL_CHECK_EXIT: Chex = Chex::const_default();
 
+/*
+ * Mirrors the exit flag of the global coordinator for exiting(), so the hot path doesn't go
+ * through the OnceLock.  The lowest bit is the flag, the bits above it the generation of the
+ * global coordinator, which Chex::reinit_after_fork() and test_lock() resets move on: a
+ * coordinator left behind by them only raises the flag while its generation is current, so it
+ * can't mark its successor as exiting.
+ */
+#[cfg(feature = "std")]
+static EXITING: AtomicUsize = AtomicUsize::new(0);
+
+/// Start a new generation of the global coordinator, with the mirrored flag lowered.  Call
+/// before creating its coordinator with ChexInstance::new_global().
+#[cfg(all(feature = "std", any(feature = "test-util", all(feature = "fork", unix))))]
+fn next_global_generation() {
+    let _ = EXITING.fetch_update(Relaxed, Relaxed, |s| Some(((s >> 1) + 1) << 1));
+}
+
+#[cfg(feature = "std")]
+thread_local! {
+    /*
+     * Instances injected with Chex::with_scoped_instance() on this thread, innermost last.
+     */
+    static SCOPED: std::cell::RefCell<Vec<ChexInstance>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/*
+ * Pops the instance pushed by Chex::with_scoped_instance(), even if the closure unwinds.
+ */
+#[cfg(feature = "std")]
+struct ScopedInstanceGuard;
+
+#[cfg(feature = "std")]
+impl Drop for ScopedInstanceGuard {
+    fn drop(&mut self) {
+        SCOPED.with(|s| s.borrow_mut().pop());
+    }
+}
+
+/*
+ * Coordinators created by Chex::namespace(), by name.
+ */
+#[cfg(feature = "std")]
+static NAMESPACES: OnceLock<Mutex<std::collections::HashMap<String, ChexInstance>>> = OnceLock::new();
+
+/*
+ * Mapping installed with Chex::set_exit_code_policy(), if any.
+ */
+#[cfg(feature = "std")]
+static EXIT_CODE_POLICY: RwLock<Option<ExitCodePolicy>> = RwLock::new(None);
+
+/*
+ * Maps the recorded ExitReason to the process exit status, see Chex::set_exit_code_policy().
+ */
+#[cfg(feature = "std")]
+pub type ExitCodePolicy = fn(&ExitReason) -> i32;
+
+#[cfg(feature = "std")]
 type ChexPanicHandler = Box<dyn Fn(&std::panic::PanicHookInfo<'_>) + Sync + Send + 'static>;
 
+#[cfg(feature = "std")]
+type AtExitHook = Box<dyn FnOnce() + Send + 'static>;
+
+#[cfg(feature = "std")]
+type ExitPayload = Box<dyn std::any::Any + Send + Sync>;
+
+/*
+ * What a signaller hands over besides reason and origin, kept only if it is the first.
+ */
+#[cfg(feature = "std")]
+#[derive(Default)]
+struct ExitAttachments {
+    payload: Option<ExitPayload>,
+    #[cfg(feature = "anyhow")]
+    error: Option<anyhow::Error>,
+}
+
+#[cfg(feature = "std")]
+impl ExitAttachments {
+    #[cfg_attr(not(feature = "anyhow"), allow(clippy::needless_update))]
+    fn payload(payload: ExitPayload) -> Self {
+        Self { payload: Some(payload), ..Self::default() }
+    }
+}
+
 /*
  * Global handle to wrap ChexInstance.
+ *
+ * Used before init(), get_instance(), get_chex_instance() and poll_exit() panic (see their try_*
+ * variants) and signal_exit() and its variants exit the process.  Methods returning a handle fail
+ * with ChexError::NotInitialized, and the rest report ChexEvent::NotInitialized and do nothing,
+ * returning a neutral value: not exited, not ready, nothing to wait for.
  */
+#[cfg(feature = "std")]
 pub struct Chex {
     cell: OnceLock<ChexInstance>,
+    config: OnceLock<ChexConfig>,
     default_panic_handler: OnceLock<ChexPanicHandler>,
+    panic_policy: panic::PanicPolicy,
+    panic_hooks: panic::PanicHooks,
+}
+
+/*
+ * Settings applied by the first call to Chex::init / Chex::try_init.  Settings left unset are
+ * read from the environment during global initialization, see ChexConfig::from_env().
+ */
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChexConfig {
+    exit_on_panic: Option<bool>,
+    exit_process_on_error: bool,
+    forced_exit: ForcedExit,
+    grace: Option<Option<std::time::Duration>>,
+    log_level: Option<sink::LogLevel>,
+    clock: Option<clock::SharedClock>,
+}
+
+/*
+ * How a grace-period watchdog terminates a process that did not exit in time.
+ */
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ForcedExit {
+    /// std::process::exit(code)
+    Exit(i32),
+    /// std::process::abort()
+    Abort,
+}
+
+/*
+ * Reasons Chex::try_init did not perform initialization itself.
+ */
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub enum InitError {
+    /// Chex was already initialized earlier with an identical config.
+    AlreadyInitialized(&'static Chex),
+    /// Chex was already initialized earlier with a different config.
+    ConflictingConfig {
+        existing: ChexConfig,
+        requested: ChexConfig,
+    },
+}
+
+/*
+ * Errors returned by the fallible try_* variants of the Chex API.
+ */
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChexError {
+    /// Chex::init / Chex::try_init has not been called yet.
+    NotInitialized,
+    /// The exit flag was set, but the wakeup could not be broadcast to async listeners.  No
+    /// longer returned, since async listeners are woken without a channel.
+    #[deprecated(note = "never returned: signalling exit cannot fail once Chex is initialized")]
+    BroadcastFailed(String),
+}
+
+/*
+ * Returned by checkpoint() once exit has been signalled, to unwind a call chain with `?`.
+ * Converts into std::io::Error with ErrorKind::Interrupted.
+ */
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChexExited;
+
+/*
+ * Handle for exit notifications.  T is the type of payload which may accompany the exit signal
+ * (see signal_exit_with()); the global Chex and plain local coordinators use ().
+ *
+ * Cloning is an Arc bump plus a listener count: nothing is registered with the coordinator
+ * until check_exit_async() actually has to wait, so clones which only poll_exit() stay cheap.
+ */
+#[cfg(feature = "std")]
+pub struct ChexInstance<T = ()> {
+    shared: Arc<ChexShared>,
+    observed: AtomicBool,
+    payload: std::marker::PhantomData<fn() -> T>,
 }
 
 /*
- * Channel wrapper for exit notifications.
+ * Handle which can signal exit (or start draining) and check whether exit was signalled, but
+ * never waits for it, ie for an ExitTrigger or a component which only ever triggers exit.
+ * Cloning it is an Arc bump, and it doesn't count towards listener_count().
  */
+#[cfg(feature = "std")]
 #[derive(Clone)]
-pub struct ChexInstance {
-    exit: Arc<AtomicBool>,
-    chs_bcast: async_broadcast::Sender::<()>,
-    chr_bcast: async_broadcast::Receiver::<()>,
+pub struct ChexSignaler {
+    shared: Arc<ChexShared>,
 }
 
-impl Chex {
-    const fn const_default() -> Self {
+/*
+ * State shared by every clone of a ChexInstance.
+ *
+ * The exit flag is raised with Release and read with Acquire, so that a signaller's earlier
+ * writes happen before anything done by a thread which observes the flag.
+ */
+#[cfg(feature = "std")]
+struct ChexShared {
+    exit: sync::AtomicBool,
+    /* The generation of the global coordinator this is, None for any other coordinator. */
+    global: Option<usize>,
+    drain: quiesce::DrainFlag,
+    serving: AtomicBool,
+    exit_lock: sync::Mutex<()>,
+    exit_cv: sync::Condvar,
+    notify: notify::ExitNotify,
+    origin: sync::OnceLock<ExitOrigin>,
+    /* The thread of the first signaller, which may signal again from its at-exit hooks. */
+    first_signaller: sync::OnceLock<std::thread::ThreadId>,
+    reason: sync::OnceLock<ExitReason>,
+    exited_at: sync::OnceLock<Instant>,
+    exited_at_system: sync::OnceLock<SystemTime>,
+    panic: sync::OnceLock<PanicReport>,
+    payload: sync::OnceLock<ExitPayload>,
+    warnings: warning::WarningChannel,
+    links: Mutex<Vec<std::sync::Weak<ChexShared>>>,
+    readiness: readiness::ReadinessRegistry,
+    teardown: Arc<teardown::TeardownRegistry>,
+    at_exit: Mutex<Option<Vec<AtExitHook>>>,
+    interceptors: Arc<intercept::InterceptorRegistry>,
+    permits: Arc<quiesce::PermitRegistry>,
+    interrupts: Arc<interrupt::InterruptRegistry>,
+    #[cfg(feature = "crossbeam")]
+    exit_channel: crossbeam::ExitChannel,
+    joins: join::JoinRegistry,
+    #[cfg(feature = "process")]
+    children: Arc<process::ChildRegistry>,
+    errors: errors::ErrorLog,
+    signal_attempts: diagnostics::SignalLog,
+    wakeups: latency::WakeupRecorder,
+    #[cfg(feature = "anyhow")]
+    error: Mutex<Option<anyhow::Error>>,
+    watchdogs: watchdog::WatchdogRegistry,
+    forced_exit_at: Arc<Mutex<Option<(Instant, std::time::Duration)>>>,
+    listeners: Arc<listener::ListenerRegistry>,
+    config: ChexConfig,
+}
+
+/*
+ * Why exit was signalled, recorded by the first signaller.
+ */
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ExitReason {
+    /// signal_exit() or one of its variants was called.
+    Manual,
+    /// A panic was caught by the exit-on-panic hook.  message is empty if the panic could not
+    /// be captured, ie when exit was signalled from the minimal (allocation-free) path.
+    Panic { message: String },
+    /// An OS signal was forwarded with signal_exit_on_os_signal().
+    OsSignal(i32),
+    /// A timer from signal_exit_after() / signal_exit_at() expired.
+    Deadline,
+    /// A watchdog missed its heartbeat.
+    Watchdog { name: String },
+    /// Components did not become ready in time, see wait_all_ready_or_exit().
+    NotReady,
+    /// A task failed, see signal_exit_with_error().
+    Error { message: String },
+    /// The parent process went away, see exit_on_parent_death().
+    ParentExited,
+    /// A resource watermark was crossed, ie memory usage with exit_on_memory().
+    Resource { message: String },
+    /// Injected by a chaos::ChaosTrigger, whose delay can be reproduced from seed.
+    Chaos { seed: u64 },
+}
+
+/*
+ * The variant of an ExitReason without its data, for filtering with poll_exit_for() and
+ * check_exit_async_for().
+ */
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ExitReasonKind {
+    Manual,
+    Panic,
+    OsSignal,
+    Deadline,
+    Watchdog,
+    NotReady,
+    Error,
+    ParentExited,
+    Resource,
+    Chaos,
+}
+
+#[cfg(feature = "std")]
+impl ExitReason {
+    pub fn kind(&self) -> ExitReasonKind {
+        match self {
+            ExitReason::Manual => ExitReasonKind::Manual,
+            ExitReason::Panic { .. } => ExitReasonKind::Panic,
+            ExitReason::OsSignal(_) => ExitReasonKind::OsSignal,
+            ExitReason::Deadline => ExitReasonKind::Deadline,
+            ExitReason::Watchdog { .. } => ExitReasonKind::Watchdog,
+            ExitReason::NotReady => ExitReasonKind::NotReady,
+            ExitReason::Error { .. } => ExitReasonKind::Error,
+            ExitReason::ParentExited => ExitReasonKind::ParentExited,
+            ExitReason::Resource { .. } => ExitReasonKind::Resource,
+            ExitReason::Chaos { .. } => ExitReasonKind::Chaos,
+        }
+    }
+
+    /// Process exit status for this reason: 0 for orderly shutdowns (Manual, Deadline), 101 for
+    /// Panic (as for an uncaught panic), 128 + the signal number for OsSignal (as a shell
+    /// reports it), and 1 otherwise.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            ExitReason::Manual | ExitReason::Deadline => 0,
+            ExitReason::Panic { .. } => 101,
+            ExitReason::OsSignal(signo) => 128i32.saturating_add(*signo).clamp(1, 255) as u8,
+            ExitReason::Watchdog { .. } | ExitReason::NotReady | ExitReason::Error { .. } => 1,
+            ExitReason::ParentExited | ExitReason::Resource { .. } | ExitReason::Chaos { .. } => 1,
+        }
+    }
+
+    /// Short lowercase name of the kind of reason, ie for metric labels.
+    pub fn as_str(&self) -> &'static str {
+        self.kind().as_str()
+    }
+}
+
+#[cfg(feature = "std")]
+impl ExitReasonKind {
+    /// Short lowercase name, ie for metric labels.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExitReasonKind::Manual => "manual",
+            ExitReasonKind::Panic => "panic",
+            ExitReasonKind::OsSignal => "os_signal",
+            ExitReasonKind::Deadline => "deadline",
+            ExitReasonKind::Watchdog => "watchdog",
+            ExitReasonKind::NotReady => "not_ready",
+            ExitReasonKind::Error => "error",
+            ExitReasonKind::ParentExited => "parent_exited",
+            ExitReasonKind::Resource => "resource",
+            ExitReasonKind::Chaos => "chaos",
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for ExitReasonKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/*
+ * Returned from main() to exit with the status for the recorded ExitReason, ie
+ * `fn main() -> ChexTermination { ...; chex.termination() }`.
+ */
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChexTermination {
+    reason: Option<ExitReason>,
+}
+
+#[cfg(feature = "std")]
+impl ChexTermination {
+    /// Why exit was signalled, if it was.
+    pub fn reason(&self) -> Option<&ExitReason> {
+        self.reason.as_ref()
+    }
+
+    /// Process exit status: 0 if exit was not signalled, otherwise the status for the reason
+    /// from the policy installed with Chex::set_exit_code_policy(), or ExitReason::exit_code()
+    /// without one.
+    pub fn exit_code(&self) -> i32 {
+        let Some(reason) = &self.reason else {
+            return 0;
+        };
+        match *EXIT_CODE_POLICY.read().unwrap_or_else(|e| e.into_inner()) {
+            Some(policy) => policy(reason),
+            None => i32::from(reason.exit_code()),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::process::Termination for ChexTermination {
+    /// Reports the low 8 bits of exit_code(), which is all most platforms keep.
+    fn report(self) -> std::process::ExitCode {
+        std::process::ExitCode::from(self.exit_code() as u8)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for ExitReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExitReason::Panic { message } if !message.is_empty() => write!(f, "panic '{message}'"),
+            ExitReason::OsSignal(signo) => write!(f, "os_signal {signo}"),
+            ExitReason::Watchdog { name } => write!(f, "watchdog '{name}'"),
+            ExitReason::Error { message } => write!(f, "error '{message}'"),
+            ExitReason::Chaos { seed } => write!(f, "chaos seed {seed}"),
+            _ => f.write_str(self.as_str()),
+        }
+    }
+}
+
+/*
+ * Code location and thread of the first signal_exit() caller.
+ */
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExitOrigin {
+    file: String,
+    line: u32,
+    column: u32,
+    thread_name: Option<String>,
+    thread_id: std::thread::ThreadId,
+}
+
+#[cfg(feature = "std")]
+impl ExitOrigin {
+    /// Capture the given location on the current thread.
+    fn new(file: &str, line: u32, column: u32) -> Self {
+        let thread = std::thread::current();
         Self {
-            default_panic_handler: OnceLock::new(),
-            cell: OnceLock::new(),
+            file: file.to_string(),
+            line,
+            column,
+            thread_name: thread.name().map(str::to_string),
+            thread_id: thread.id(),
         }
     }
 
-    /// Initialize global exit-signal state.
-    /// Must be called before any other crate functions.
-    ///
-    /// If set_exit_on_panic is true, we will immediately register a panic hook
-    /// to signal exit to all other Chex/ChexInstance listeners.  This can be enabled later with
-    /// .set_exit_on_panic()
-    pub fn init(set_exit_on_panic: bool) -> &'static Chex {
-        let _inst = GLOBAL_CHECK_EXIT.cell.get_or_init(ChexInstance::new);
+    fn from_location(location: &Location<'_>) -> Self {
+        Self::new(location.file(), location.line(), location.column())
+    }
 
-        GLOBAL_CHECK_EXIT.default_panic_handler.get_or_init(|| std::panic::take_hook());
+    /// Source file of the signal_exit() call (or of the panic, for exit-on-panic).
+    pub fn file(&self) -> &str {
+        &self.file
+    }
 
-        if set_exit_on_panic {
-            GLOBAL_CHECK_EXIT.set_exit_on_panic();
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    pub fn column(&self) -> u32 {
+        self.column
+    }
+
+    /// Name of the signalling thread, if it was named.
+    pub fn thread_name(&self) -> Option<&str> {
+        self.thread_name.as_deref()
+    }
+
+    pub fn thread_id(&self) -> std::thread::ThreadId {
+        self.thread_id
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for ExitOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{} on thread ", self.file, self.line, self.column)?;
+        match &self.thread_name {
+            Some(name) => write!(f, "'{name}'"),
+            None => write!(f, "{:?}", self.thread_id),
         }
+    }
+}
 
-        &GLOBAL_CHECK_EXIT
+#[cfg(feature = "std")]
+impl Default for ChexConfig {
+    fn default() -> Self {
+        Self {
+            exit_on_panic: None,
+            exit_process_on_error: true,
+            forced_exit: ForcedExit::Exit(1),
+            grace: None,
+            log_level: None,
+            clock: None,
+        }
     }
+}
 
-    /// Setup a panic hook to signal exit to other threads.
-    /// This is called automatically if initialized with init(set_exit_on_panic = true)
-    pub fn set_exit_on_panic(&self) {
-        std::panic::set_hook(Box::new(|info| {
-            error!("PANIC: {info}");
-            error!("PANIC: signal exit to all Chex listeners");
+#[cfg(feature = "std")]
+impl ChexConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-            GLOBAL_CHECK_EXIT.signal_exit();
+    /// Register the exit-on-panic hook during initialization.  Defaults to CHEX_EXIT_ON_PANIC
+    /// for the global Chex, else false.
+    pub fn exit_on_panic(mut self, exit_on_panic: bool) -> Self {
+        self.exit_on_panic = Some(exit_on_panic);
+        self
+    }
 
-            /*
-             * TODO: Store a list of threads that have cloned the ChexInstance and not yet
-             *       dropped it, and spin here until timeout or the list length hits 1
-             *       (which probably means this Panicking thread is the last holdout)
-             *       and then std::process::exit(1) / abort() or just call default_handler to
-             *       trigger nested panic
-             *
-             *       ... async-broadcast also has .sender_count / .receiver_count()
-             */
+    /// Whether signal_exit() may terminate the process with std::process::exit(1) when it
+    /// cannot notify listeners.  Has no effect: listeners are woken without anything that can
+    /// fail, so there is no such error.
+    #[deprecated(note = "has no effect: signalling exit cannot fail once Chex is initialized")]
+    pub fn exit_process_on_error(mut self, exit_process_on_error: bool) -> Self {
+        self.exit_process_on_error = exit_process_on_error;
+        self
+    }
 
-            /*
-             * Invoke the default panic handler.
-             */
-            let default_handler = GLOBAL_CHECK_EXIT.default_panic_handler.get()
-                .expect("PANIC (nested): Failed to initialize Chex before Panic encountered");
-            error!("PANIC: calling default panic handler");
-            default_handler(info);
-        }));
+    /// How signal_exit_with_deadline() terminates the process once the grace period expires.
+    /// Defaults to ForcedExit::Exit(1).
+    pub fn forced_exit(mut self, forced_exit: ForcedExit) -> Self {
+        self.forced_exit = forced_exit;
+        self
     }
 
-    /// Returns an instance of the underlying ChexInstance that can be used to asynchronously check
-    /// exit.
-    pub fn get_instance(&self) -> ChexInstance {
-        self.cell.get()
-            .expect("Failed to initialize Chex before .get_instance()")
-            .clone()
+    /// Once exit is signalled, terminate the process (see forced_exit()) if it is still alive
+    /// after grace, as if every exit was signalled with signal_exit_with_deadline(grace).  None
+    /// disables this.  Defaults to CHEX_GRACE_SECS for the global Chex, else None.
+    pub fn grace(mut self, grace: Option<std::time::Duration>) -> Self {
+        self.grace = Some(grace);
+        self
     }
 
-    /// Returns an instance of the underlying ChexInstance that can be used to asynchronously check
-    /// exit.
-    pub fn get_chex_instance() -> ChexInstance {
-        GLOBAL_CHECK_EXIT.cell.get()
-            .expect("Failed to initialize Chex before .get_instance()")
-            .clone()
+    /// The most verbose events the default sink reports.  Defaults to CHEX_LOG_LEVEL ("off",
+    /// "error" or "warn") for the global Chex, else LogLevel::Warn.
+    pub fn log_level(mut self, level: sink::LogLevel) -> Self {
+        self.log_level = Some(level);
+        self
     }
 
-    /// Returns true iff exit has been signalled.
-    pub fn poll_exit(&self) -> bool {
-        let c: &ChexInstance = self.cell.get().expect("Failed to initialize Chex before .poll_exit()");
-        c.exit.load(Relaxed)
+    /// The clock behind deadlines, the grace period, watchdogs and the other timeouts listed in
+    /// the clock module.
+    /// Defaults to clock::SystemClock.
+    pub fn clock<C: clock::Clock>(mut self, clock: C) -> Self {
+        self.clock = Some(clock::SharedClock(Arc::new(clock)));
+        self
     }
 
-    /// Signal all listeners to exit, then return to allow the caller to do their own cleanup.
+    /// Fill the settings which were not set explicitly from the environment: CHEX_GRACE_SECS
+    /// (seconds, ie "30" or "2.5"), CHEX_EXIT_ON_PANIC ("1"/"true" or "0"/"false") and
+    /// CHEX_LOG_LEVEL.  Chex::init() and Chex::try_init() do this for the global Chex; call it
+    /// to have a local coordinator follow the environment too.
     ///
-    /// Exits the process with a failure code if we were unable to signal exit.
-    pub fn signal_exit(&self) {
-        let c: Option<&ChexInstance> = self.cell.get();
-        match c {
-            None => {
-                error!("Failed to initialize Chex before .signal_exit()");
-                std::process::exit(1);
-            }
-            Some(c) => {
-                c.signal_exit();
+    /// Invalid values are ignored, and reported with ChexEvent::InvalidEnvVar.
+    pub fn from_env(self) -> Self {
+        env::apply(self)
+    }
+
+    fn timer_clock(&self) -> Arc<dyn clock::Clock> {
+        match &self.clock {
+            Some(clock) => clock.0.clone(),
+            None => Arc::new(clock::SystemClock),
+        }
+    }
+
+    /// The current time on the configured clock.
+    fn now(&self) -> Instant {
+        match &self.clock {
+            Some(clock) => clock.0.now(),
+            None => Instant::now(),
+        }
+    }
+
+    /// How long a thread may block on a condvar (or sleep) before it checks the configured
+    /// clock again, with remaining left until its deadline: all of it on the system clock, a
+    /// short slice on any other, which moves without waking anyone.
+    fn wait_slice(&self, remaining: std::time::Duration) -> std::time::Duration {
+        match &self.clock {
+            Some(_) => remaining.min(std::time::Duration::from_millis(10)),
+            None => remaining,
+        }
+    }
+
+    fn exit_on_panic_enabled(&self) -> bool {
+        self.exit_on_panic.unwrap_or(false)
+    }
+
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    fn grace_period(&self) -> Option<std::time::Duration> {
+        self.grace.flatten()
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for InitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InitError::AlreadyInitialized(_) => write!(f, "Chex was already initialized"),
+            InitError::ConflictingConfig { existing, requested } => {
+                write!(f, "Chex was already initialized with {existing:?}, conflicting with {requested:?}")
             }
         }
     }
 }
 
-impl ChexInstance {
-    /// Initialize the channels and exit flag.
-    ///
-    /// Should not be called directly by library users.
-    fn new() -> Self {
-        let (mut chs_bcast, chr_bcast) = async_broadcast::broadcast::<()>(1);
-        chs_bcast.set_overflow(true);
+#[cfg(feature = "std")]
+impl std::error::Error for InitError {}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for ChexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChexError::NotInitialized => write!(f, "Chex has not been initialized"),
+            #[allow(deprecated)]
+            ChexError::BroadcastFailed(e) => write!(f, "Failed to broadcast exit signal: {e}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ChexError {}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for ChexExited {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "exit has been signalled")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ChexExited {}
+
+/*
+ * Lets exit flow through io::Result code with `?`.  The original ChexExited stays available
+ * through io::Error::get_ref().  ChexExited is Send + Sync + 'static, so it also converts into
+ * anyhow::Error and Box<dyn Error> without further impls.
+ */
+#[cfg(feature = "std")]
+impl From<ChexExited> for std::io::Error {
+    fn from(e: ChexExited) -> Self {
+        std::io::Error::new(std::io::ErrorKind::Interrupted, e)
+    }
+}
+
+#[cfg(feature = "std")]
+/// Return `Err(ChexExited.into())` from the current function if exit has been signalled, on the
+/// global Chex or on the given ChexInstance.
+///
+/// ```
+/// fn step(ci: &chex::ChexInstance) -> Result<(), Box<dyn std::error::Error>> {
+///     chex::bail_if_exiting!();
+///     chex::bail_if_exiting!(ci);
+///     Ok(())
+/// }
+///
+/// let chex = chex::Chex::init(false);
+/// let ci = chex.get_instance();
+/// assert!(step(&ci).is_ok());
+/// chex.signal_exit();
+/// assert!(step(&ci).is_err());
+/// ```
+#[macro_export]
+macro_rules! bail_if_exiting {
+    () => {
+        if let ::std::result::Result::Err(e) = $crate::checkpoint() {
+            return ::std::result::Result::Err(::std::convert::From::from(e));
+        }
+    };
+    ($ci:expr) => {
+        if let ::std::result::Result::Err(e) = $ci.checkpoint() {
+            return ::std::result::Result::Err(::std::convert::From::from(e));
+        }
+    };
+}
+
+#[cfg(feature = "std")]
+/// Returns true once exit has been signalled on the global Chex, ie in tight loops which check
+/// for exit every iteration.  A single relaxed load, without the lookup done by
+/// Chex::poll_exit(), so it doesn't make the signaller's earlier writes visible; call
+/// poll_exit() once this returns true if that is needed.
+///
+/// Always false if Chex has not been initialized.
+#[inline]
+pub fn exiting() -> bool {
+    EXITING.load(Relaxed) & 1 != 0
+}
+
+#[cfg(feature = "std")]
+/// Returns Err(ChexExited) once exit has been signalled on the global Chex, so deep synchronous
+/// call chains can cancel cooperatively with `chex::checkpoint()?;` without a ChexInstance.
+///
+/// Always Ok if Chex has not been initialized.
+pub fn checkpoint() -> Result<(), ChexExited> {
+    match GLOBAL_CHECK_EXIT.try_poll_exit() {
+        Ok(true) => Err(ChexExited),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Debug for Chex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut d = f.debug_struct("Chex");
+        match self.instance() {
+            None => d.field("initialized", &false),
+            Some(c) => d
+                .field("initialized", &true)
+                .field("exit", &c.poll_exit())
+                .field("receiver_count", &c.shared.receiver_count())
+                .field("exit_on_panic", &self.panic_hooks.is_installed()),
+        };
+        d.finish()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::fmt::Debug for ChexInstance<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChexInstance")
+            .field("exit", &self.poll_exit())
+            .field("receiver_count", &self.shared.receiver_count())
+            .field("origin", &self.exit_origin())
+            .finish()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Chex {
+    const fn const_default() -> Self {
         Self {
-            exit: Arc::new(AtomicBool::new(false)),
-            chs_bcast,
-            chr_bcast,
+            default_panic_handler: OnceLock::new(),
+            config: OnceLock::new(),
+            cell: OnceLock::new(),
+            panic_policy: panic::PanicPolicy::new(),
+            panic_hooks: panic::PanicHooks::new(),
         }
     }
 
-    /// Signal all listeners to exit, then return to allow the caller to do their own cleanup.
-    ///
-    /// Exits the process with a failure code if we were unable to signal exit.
-    pub fn signal_exit(&self) {
-        self.exit.store(true, Relaxed);
+    /// The global coordinator, or None if Chex is not initialized.  In a forked child which
+    /// called reinit_after_fork(), the child's own coordinator.
+    fn instance(&self) -> Option<&ChexInstance> {
+        GLOBAL_CHECK_EXIT.panic_hooks.ensure_if_replaced(&GLOBAL_CHECK_EXIT);
+        #[cfg(all(feature = "fork", unix))]
+        if let Some(c) = fork::current() {
+            return Some(c);
+        }
+        #[cfg(feature = "test-util")]
+        if let Some(c) = testing::current() {
+            return Some(c);
+        }
+        self.cell.get()
+    }
 
-        if let Err(e) = self.chs_bcast.try_broadcast(()) {
-            /*
-             * This can only happen if the channel is closed or full.  Let's just exit.
-             */
-            error!("signal_exit failed to send broadcast: {e:?}");
-            std::process::exit(1);
+    /// The global coordinator for operation, or None after reporting ChexEvent::NotInitialized.
+    fn instance_for(&self, operation: &'static str) -> Option<&ChexInstance> {
+        let c = self.instance();
+        if c.is_none() {
+            sink::emit(ChexEvent::NotInitialized { operation });
         }
+        c
     }
 
-    /// Returns true iff exit has already been signalled
-    pub fn poll_exit(&self) -> bool {
-        self.exit.load(Relaxed)
+    /// Initialize the global state with config, unless that already happened.
+    ///
+    /// Returns the config in effect and whether initialization happened during this call.
+    fn init_config(config: ChexConfig) -> (&'static ChexConfig, bool) {
+        let mut initialized_here = false;
+        let existing = GLOBAL_CHECK_EXIT.config.get_or_init(|| {
+            let config = config.from_env();
+            sink::set_level(config.log_level.unwrap_or(sink::LogLevel::Warn));
+            GLOBAL_CHECK_EXIT.cell.get_or_init(|| ChexInstance::new_global(config.clone()));
+            GLOBAL_CHECK_EXIT.panic_hooks.install_once(&GLOBAL_CHECK_EXIT);
+            #[cfg(all(feature = "fork", unix))]
+            fork::set_owner();
+            initialized_here = true;
+            config
+        });
+
+        (existing, initialized_here)
     }
 
-    /// Returns when exit has been signalled, or the exit-signal channel is closed.
-    pub async fn check_exit_async(&mut self) {
-        let ex = self.exit.load(Relaxed);
-        if ex {
-            return;
+    /// Initialize global exit-signal state.
+    /// Must be called before any other crate functions.
+    ///
+    /// If set_exit_on_panic is true, we will immediately register a panic hook
+    /// to signal exit to all other Chex/ChexInstance listeners.  This can be enabled later with
+    /// .set_exit_on_panic()
+    pub fn init(set_exit_on_panic: bool) -> &'static Chex {
+        Self::builder().exit_on_panic(set_exit_on_panic).init()
+    }
+
+    /// Like init(), but exit is also signalled once the returned MainGuard is dropped, ie when
+    /// main() returns early or unwinds: `let chex = Chex::init_guarded(true);`
+    #[track_caller]
+    pub fn init_guarded(set_exit_on_panic: bool) -> guard::MainGuard {
+        Self::builder().exit_on_panic(set_exit_on_panic).init_guarded()
+    }
+
+    /// Configure the global state beyond exit-on-panic, ie
+    /// `Chex::builder().exit_on_panic(true).grace(Duration::from_secs(30)).init()`.
+    pub fn builder() -> builder::ChexBuilder {
+        builder::ChexBuilder::new()
+    }
+
+    /// Create an independent coordinator, unrelated to the global Chex, ie for a library or a
+    /// test which must not share the process-wide exit signal.  Clone the returned ChexInstance
+    /// to hand it out; it works without Chex::init().
+    ///
+    /// If config has exit_on_panic set, a panic on any thread also signals exit to this
+    /// coordinator, for as long as any of its instances are alive.  The global panic policy
+    /// (filters, thresholds, hook mode) does not apply, but suppress_exit() does.
+    /// All local coordinators share one hook, chained in front of the process panic hook at the
+    /// first such call; replacing the process hook afterwards with std::panic::set_hook()
+    /// removes it.
+    pub fn new_local(config: ChexConfig) -> ChexInstance {
+        Self::new_local_with_payload(config)
+    }
+
+    /// Returns the coordinator for namespace name, creating it on first use.  Every call with
+    /// the same name returns an instance of the same coordinator.
+    ///
+    /// Namespaces let a library coordinate its own threads without calling Chex::init(), so it
+    /// never races the application over the global config or panic hook.  A namespace is
+    /// independent of the global Chex unless linked to it with Chex::link().
+    pub fn namespace(name: &str) -> ChexInstance {
+        let mut namespaces = NAMESPACES.get_or_init(Default::default)
+            .lock().unwrap_or_else(|e| e.into_inner());
+        namespaces.entry(name.to_string())
+            .or_insert_with(|| ChexInstance::new(ChexConfig::new()))
+            .clone()
+    }
+
+    /// Like new_local(), but exit may be signalled with a payload of type T (ie a shutdown
+    /// command with a reason and deadline) using ChexInstance::signal_exit_with().
+    pub fn new_local_with_payload<T: Clone + Send + 'static>(config: ChexConfig) -> ChexInstance<T> {
+        let exit_on_panic = config.exit_on_panic_enabled();
+        let instance = ChexInstance::new(config);
+        if exit_on_panic {
+            panic::bridge_local(&instance.shared);
         }
+        instance
+    }
+
+    /// Initialize global exit-signal state, reporting whether it was already initialized.
+    ///
+    /// Unlike init(), this never silently accepts a different configuration than the one
+    /// in effect: libraries can use it to detect that the application (or another library)
+    /// initialized Chex first, and whether the settings agree.
+    pub fn try_init(config: ChexConfig) -> Result<&'static Chex, InitError> {
+        let (existing, initialized_here) = Self::init_config(config.clone());
+
+        if !initialized_here {
+            let config = config.from_env();
+            if *existing == config {
+                return Err(InitError::AlreadyInitialized(&GLOBAL_CHECK_EXIT));
+            }
+            return Err(InitError::ConflictingConfig {
+                existing: existing.clone(),
+                requested: config,
+            });
+        }
+
+        if existing.exit_on_panic_enabled() {
+            GLOBAL_CHECK_EXIT.set_exit_on_panic();
+        }
+
+        Ok(&GLOBAL_CHECK_EXIT)
+    }
+
+    /// Call in the child right after fork(), ie when daemonizing, to give it a coordinator of
+    /// its own.  Without this the child shares a copy of the parent's state: exit signalled
+    /// before the fork stays signalled, threads such as timers, watchdogs and the SIGTERM
+    /// reader only exist in the parent, and SIGTERM in the child wakes up the parent.
+    ///
+    /// Afterwards the global Chex, get_instance() and get_chex_instance() refer to a fresh,
+    /// unexited coordinator with the same config, which the exit-on-panic hook (if enabled)
+    /// signals from then on.  Namespaces are forgotten, SIGTERM handling is reset to the default
+    /// action, and the panic threshold count starts over.  ChexInstances obtained before the
+    /// fork keep referring to the old coordinator, which nothing in the child signals anymore;
+    /// everything registered on it (handle_sigterm(), signal_exit_after(), watchdogs, teardown
+    /// hooks, adopted children...) has to be registered again.
+    ///
+    /// As with any code run after fork() in a multi-threaded process, this may deadlock if
+    /// another thread held one of chex's locks at the time of the fork, so fork before
+    /// starting threads where possible.
+    ///
+    /// Returns false (and does nothing) if Chex is not initialized, or if this process already
+    /// owns the current coordinator, ie is not a forked child or already reinitialized.
+    #[cfg(all(feature = "fork", unix))]
+    pub fn reinit_after_fork() -> bool {
+        let Some(config) = GLOBAL_CHECK_EXIT.config.get() else {
+            return false;
+        };
+        if !fork::is_stale() {
+            return false;
+        }
+
+        next_global_generation();
+        fork::reinit(ChexInstance::new_global(config.clone()));
+        if let Some(namespaces) = NAMESPACES.get() {
+            namespaces.lock().unwrap_or_else(|e| e.into_inner()).clear();
+        }
+        #[cfg(feature = "sigterm")]
+        sigterm::reinit_after_fork();
+        GLOBAL_CHECK_EXIT.panic_policy.reset_threshold();
+        true
+    }
+
+    /// Serialize a test which uses the global Chex against every other test holding the lock, and
+    /// reset the global Chex once the returned guard is dropped, so `cargo test` can run such
+    /// tests with the default parallel runner: `let _lock = Chex::test_lock();`
+    ///
+    /// The reset starts a new generation: a fresh, unexited coordinator with the config from the
+    /// first init() (so tests sharing a binary should agree on it, or use Chex::new_local() for
+    /// anything else), which the exit-on-panic hook (if installed) signals from then on.
+    /// Namespaces are forgotten and the panic threshold count starts over.  ChexInstances obtained earlier keep referring to the
+    /// previous coordinator.  Needs the "test-util" feature.
+    #[cfg(feature = "test-util")]
+    pub fn test_lock() -> testing::TestLock {
+        testing::lock()
+    }
+
+    #[cfg(feature = "test-util")]
+    fn reset_generation() {
+        let Some(config) = GLOBAL_CHECK_EXIT.config.get() else {
+            return;
+        };
+
+        next_global_generation();
+        testing::start_generation(ChexInstance::new_global(config.clone()));
+        if let Some(namespaces) = NAMESPACES.get() {
+            namespaces.lock().unwrap_or_else(|e| e.into_inner()).clear();
+        }
+        GLOBAL_CHECK_EXIT.panic_policy.reset_threshold();
+    }
+
+    /// Setup a panic hook to signal exit to other threads.
+    /// This is called automatically if initialized with init(set_exit_on_panic = true)
+    pub fn set_exit_on_panic(&self) {
+        GLOBAL_CHECK_EXIT.panic_hooks.enable(&GLOBAL_CHECK_EXIT);
+    }
+
+    /// Select how much work (and output) the exit-on-panic hook does while panicking.  Defaults to
+    /// PanicHookMode::Verbose.
+    pub fn set_panic_hook_mode(&self, mode: panic::PanicHookMode) {
+        self.panic_policy.set_mode(mode);
+    }
+
+    /// After the exit-on-panic hook signals exit, block the panicking thread for up to grace
+    /// (or until all ChexInstances besides the global one have been dropped) before returning
+    /// from the hook.  An instance still held by the panicking thread itself means waiting out
+    /// the full grace period.
+    ///
+    /// Intended for panic = "abort" builds, where the process aborts as soon as the hook
+    /// returns and other threads would otherwise never get to run their teardown.  None (the
+    /// default) disables the wait.
+    pub fn set_panic_grace(&self, grace: Option<std::time::Duration>) {
+        self.panic_policy.set_grace(grace);
+    }
+
+    /// Stop signalling exit on panic, restoring the panic hook that was in effect at init.
+    ///
+    /// Hooks registered with add_panic_hook() keep running before the restored hook.  Exit on
+    /// panic can be enabled again with set_exit_on_panic().
+    pub fn unset_exit_on_panic(&self) {
+        GLOBAL_CHECK_EXIT.panic_hooks.disable();
+    }
+
+    /// Register an additional panic hook, run by the exit-on-panic hook after exit has been
+    /// signalled and before the default panic handler.
+    ///
+    /// Crates that want their own panic reporting should use this instead of
+    /// std::panic::set_hook(), which would replace the exit-on-panic hook.
+    pub fn add_panic_hook<F>(&self, hook: F)
+    where
+        F: Fn(&std::panic::PanicHookInfo<'_>) + Sync + Send + 'static,
+    {
+        self.panic_hooks.add(Box::new(hook));
+    }
+
+    /// If another crate has replaced chex's panic hook with std::panic::set_hook() since init(),
+    /// reinstall it and chain the replacement after it (as if it were registered with
+    /// add_panic_hook()).
+    ///
+    /// A replacement which drops chex's hook is also noticed by every later call on the global
+    /// Chex (and poll_exit() on its instances), which reinstalls the hook the same way.  Call
+    /// this explicitly for a replacement which keeps chex's hook without ever calling it, once
+    /// the other crate has installed its hook, ie after setting up logging.  Reinstalling swaps
+    /// the process panic hook, so a panic on another thread at that very moment may miss it.
+    ///
+    /// Returns true iff the hook had to be reinstalled.
+    pub fn ensure_panic_hook(&self) -> bool {
+        GLOBAL_CHECK_EXIT.panic_hooks.ensure(&GLOBAL_CHECK_EXIT)
+    }
+
+    /// Only signal exit for panics where filter returns true.  Other panics are passed straight
+    /// to the default panic handler.
+    ///
+    /// Useful for threads that intentionally catch_unwind() recoverable panics.  Replaces any
+    /// previously set filter.
+    pub fn set_panic_filter<F>(&self, filter: F)
+    where
+        F: Fn(&std::panic::PanicHookInfo<'_>) -> bool + Sync + Send + 'static,
+    {
+        self.panic_policy.set_filter(Box::new(filter));
+    }
+
+    /// Remove the filter set with set_panic_filter().
+    pub fn clear_panic_filter(&self) {
+        self.panic_policy.clear_filter();
+    }
+
+    /// Never signal exit for panics on threads named thread_name.
+    pub fn ignore_panics_on_thread(&self, thread_name: &str) {
+        self.panic_policy.ignore_thread(thread_name);
+    }
+
+    /// Only signal exit once count panics have been seen, or count panics within window if
+    /// given.  Panics excluded by filters or suppress_exit() are not counted.
+    ///
+    /// The default threshold is a single panic.  Resets the count of panics seen so far.
+    pub fn set_panic_threshold(&self, count: usize, window: Option<std::time::Duration>) {
+        self.panic_policy.set_threshold(count, window);
+    }
+
+    /// Returns an instance of the underlying ChexInstance that can be used to asynchronously check
+    /// exit.
+    pub fn get_instance(&self) -> ChexInstance {
+        self.try_get_instance()
+            .expect("Failed to initialize Chex before .get_instance()")
+    }
+
+    /// Like get_instance(), but returns an error instead of panicking if Chex is not initialized.
+    pub fn try_get_instance(&self) -> Result<ChexInstance, ChexError> {
+        self.instance()
+            .cloned()
+            .ok_or(ChexError::NotInitialized)
+    }
+
+    /// Returns an instance of the underlying ChexInstance that can be used to asynchronously check
+    /// exit.
+    pub fn get_chex_instance() -> ChexInstance {
+        Self::try_get_chex_instance()
+            .expect("Failed to initialize Chex before .get_instance()")
+    }
+
+    /// Like get_chex_instance(), but returns an error instead of panicking if Chex is not
+    /// initialized.
+    pub fn try_get_chex_instance() -> Result<ChexInstance, ChexError> {
+        if let Some(scoped) = Self::scoped_instance() {
+            return Ok(scoped);
+        }
+        GLOBAL_CHECK_EXIT.try_get_instance()
+    }
+
+    /// Run f with instance standing in for the global Chex on this thread: within f,
+    /// get_chex_instance(), try_get_chex_instance() and get_or_init_instance() return clones of
+    /// instance, ie a Chex::new_local() coordinator, so code which looks up the global instance
+    /// deep inside can be tested in isolation.  Scopes nest, and the innermost wins.
+    ///
+    /// Only the calling thread is affected, so threads spawned within f, and async tasks which
+    /// move to another worker thread, still see the global Chex.  Methods on &Chex, and
+    /// exiting(), always refer to the global Chex.
+    pub fn with_scoped_instance<R>(instance: ChexInstance, f: impl FnOnce() -> R) -> R {
+        SCOPED.with(|s| s.borrow_mut().push(instance));
+        let _guard = ScopedInstanceGuard;
+        f()
+    }
+
+    fn scoped_instance() -> Option<ChexInstance> {
+        SCOPED.with(|s| s.borrow().last().cloned())
+    }
+
+    /// Returns true iff the global Chex has been initialized.
+    pub fn is_initialized() -> bool {
+        GLOBAL_CHECK_EXIT.instance().is_some()
+    }
+
+    /// Like get_chex_instance(), but initializes the global Chex with the default config (and
+    /// the environment, see ChexConfig::from_env()) first if needed, ie for a library which
+    /// only checks for exit and must not panic if the application never calls init().
+    ///
+    /// If this initializes Chex, a later Chex::try_init() from the application fails with
+    /// InitError::AlreadyInitialized or ConflictingConfig; Chex::init(true) still enables exit
+    /// on panic.
+    pub fn get_or_init_instance() -> ChexInstance {
+        if let Some(scoped) = Self::scoped_instance() {
+            return scoped;
+        }
+        if !Self::is_initialized() {
+            Self::builder().init();
+        }
+        GLOBAL_CHECK_EXIT.get_instance()
+    }
+
+    /// Returns true iff exit has been signalled.  Like ChexInstance::poll_exit(), a true
+    /// result makes the signaller's earlier writes visible.
+    pub fn poll_exit(&self) -> bool {
+        self.try_poll_exit().expect("Failed to initialize Chex before .poll_exit()")
+    }
+
+    /// Like poll_exit(), but returns an error instead of panicking if Chex is not initialized.
+    pub fn try_poll_exit(&self) -> Result<bool, ChexError> {
+        let c: &ChexInstance = self.instance().ok_or(ChexError::NotInitialized)?;
+        Ok(c.poll_exit())
+    }
+
+    /// Signal all listeners to exit, then return to allow the caller to do their own cleanup.
+    ///
+    /// Returns true iff this call was the first to signal exit.
+    ///
+    /// Exits the process with a failure code if Chex is not initialized.
+    #[track_caller]
+    pub fn signal_exit(&self) -> bool {
+        let c: Option<&ChexInstance> = self.instance();
+        match c {
+            None => {
+                sink::emit(ChexEvent::NotInitialized { operation: ".signal_exit()" });
+                exit_process(1);
+            }
+            Some(c) => {
+                c.signal_exit()
+            }
+        }
+    }
+
+    /// Park the current thread until exit is signalled, ie at the end of main() once all the
+    /// workers have been started.
+    pub fn block_until_exit(&self) {
+        if let Some(c) = self.instance_for(".block_until_exit()") {
+            c.block_until_exit();
+        }
+    }
+
+    /// Park the current thread until exit is signalled or timeout elapses on the configured
+    /// clock (ChexConfig::clock()).
+    ///
+    /// Returns true iff exit has been signalled.
+    pub fn block_until_exit_timeout(&self, timeout: std::time::Duration) -> bool {
+        match self.instance_for(".block_until_exit_timeout()") {
+            Some(c) => c.block_until_exit_timeout(timeout),
+            None => false,
+        }
+    }
+
+    /// Like signal_exit(), but returns ChexError::NotInitialized instead of exiting the process
+    /// if Chex is not initialized.
+    #[track_caller]
+    pub fn try_signal_exit(&self) -> Result<bool, ChexError> {
+        self.instance()
+            .ok_or(ChexError::NotInitialized)?
+            .try_signal_exit()
+    }
+
+    /// Broadcast a non-fatal warning (ie degraded mode, low disk) to every warning subscriber.
+    /// poll_exit() and exit listeners are unaffected.
+    #[track_caller]
+    pub fn signal_warning(&self, message: impl Into<String>) {
+        if let Some(c) = self.instance_for(".signal_warning()") {
+            c.signal_warning(message);
+        }
+    }
+
+    /// Subscribe to warnings signalled from now on.
+    pub fn subscribe_warnings(&self) -> Result<WarningSubscription, ChexError> {
+        Ok(self.instance().ok_or(ChexError::NotInitialized)?.subscribe_warnings())
+    }
+
+    /// Report that component name has finished starting up.
+    pub fn ready(&self, name: &str) {
+        if let Some(c) = self.instance_for(".ready()") {
+            c.ready(name);
+        }
+    }
+
+    /// Park the current thread until every one of names has reported ready.  See
+    /// ChexInstance::wait_all_ready().
+    pub fn wait_all_ready(&self, names: &[&str], timeout: std::time::Duration) -> Result<(), NotReady> {
+        match self.instance_for(".wait_all_ready()") {
+            Some(c) => c.wait_all_ready(names, timeout),
+            None => Err(NotReady::uninitialized(names)),
+        }
+    }
+
+    /// Like wait_all_ready(), but signal exit if the components don't become ready in time.
+    #[track_caller]
+    pub fn wait_all_ready_or_exit(&self, names: &[&str], timeout: std::time::Duration) -> Result<(), NotReady> {
+        match self.instance_for(".wait_all_ready_or_exit()") {
+            Some(c) => c.wait_all_ready_or_exit(names, timeout),
+            None => Err(NotReady::uninitialized(names)),
+        }
+    }
+
+    /// Signal exit like signal_exit(), and terminate the process if it is still alive once grace
+    /// has elapsed, so a hung listener can't keep a shutting-down process alive forever.
+    ///
+    /// See ChexInstance::signal_exit_with_deadline().
+    #[track_caller]
+    pub fn signal_exit_with_deadline(&self, grace: std::time::Duration) -> bool {
+        match self.instance() {
+            None => {
+                sink::emit(ChexEvent::NotInitialized { operation: ".signal_exit_with_deadline()" });
+                exit_process(1);
+            }
+            Some(c) => c.signal_exit_with_deadline(grace),
+        }
+    }
+
+    /// Signal exit with ExitReason::OsSignal(signo).  See ChexInstance::signal_exit_on_os_signal().
+    #[track_caller]
+    pub fn signal_exit_on_os_signal(&self, signo: i32) -> bool {
+        match self.instance() {
+            None => {
+                sink::emit(ChexEvent::NotInitialized { operation: ".signal_exit_on_os_signal()" });
+                exit_process(1);
+            }
+            Some(c) => c.signal_exit_on_os_signal(signo),
+        }
+    }
+
+    /// Schedule exit to be signalled once delay has elapsed, ie to bound the runtime of a batch
+    /// job or soak test.
+    #[track_caller]
+    pub fn signal_exit_after(&self, delay: std::time::Duration) {
+        if let Some(c) = self.instance_for(".signal_exit_after()") {
+            c.signal_exit_after(delay);
+        }
+    }
+
+    /// Schedule exit to be signalled at deadline.
+    #[track_caller]
+    pub fn signal_exit_at(&self, deadline: Instant) {
+        if let Some(c) = self.instance_for(".signal_exit_at()") {
+            c.signal_exit_at(deadline);
+        }
+    }
+
+    /// Returns a guard which signals exit on the global Chex when dropped.  See
+    /// ChexInstance::exit_on_drop().
+    #[track_caller]
+    pub fn exit_on_drop(&self) -> Result<guard::ChexGuard, ChexError> {
+        Ok(self.instance().ok_or(ChexError::NotInitialized)?.exit_on_drop())
+    }
+
+    /// A sender-only handle for the global Chex.  See ChexInstance::signaler().
+    pub fn signaler(&self) -> Result<ChexSignaler, ChexError> {
+        Ok(self.instance().ok_or(ChexError::NotInitialized)?.signaler())
+    }
+
+    /// Run trigger for the global Chex.  See ChexInstance::install_trigger().
+    pub fn install_trigger(&self, trigger: impl trigger::ExitTrigger) {
+        if let Some(c) = self.instance_for(".install_trigger()") {
+            c.install_trigger(trigger);
+        }
+    }
+
+    /// Register a heartbeat watchdog: if the returned handle's beat() is not called at least
+    /// once per interval, the miss is logged and exit is signalled (or the policy set with
+    /// set_watchdog_policy() is invoked instead).
+    ///
+    /// This catches silently hung threads, not just panicking ones.
+    #[track_caller]
+    pub fn watchdog(&self, name: &str, interval: std::time::Duration) -> Result<Watchdog, ChexError> {
+        Ok(self.instance().ok_or(ChexError::NotInitialized)?.watchdog(name, interval))
+    }
+
+    /// Call policy(name, interval) when a watchdog misses its heartbeat, instead of signalling
+    /// exit.
+    pub fn set_watchdog_policy<F>(&self, policy: F)
+    where
+        F: Fn(&str, std::time::Duration) + Sync + Send + 'static,
+    {
+        if let Some(c) = self.instance_for(".set_watchdog_policy()") {
+            c.shared.watchdogs.set_policy(Box::new(policy));
+        }
+    }
+
+    /// Route internal diagnostics (panics caught, exit signalled, failures) to sink instead of
+    /// the default `defmt` or `log` sink (or dropping them, without either feature).  The sink
+    /// is process-wide and may be installed before init().
+    pub fn set_sink<S: sink::ChexSink>(&self, sink: S) {
+        sink::set(Box::new(sink));
+    }
+
+    /// Once exit is signalled, wait timeout and then report every listener registered with
+    /// ChexInstance::register() that has not acked yet.  Stragglers are logged, or passed to the
+    /// callback set with on_stragglers().  None (the default) disables the check.
+    pub fn set_straggler_timeout(&self, timeout: Option<std::time::Duration>) {
+        if let Some(c) = self.instance_for(".set_straggler_timeout()") {
+            c.shared.listeners.set_straggler_timeout(timeout);
+        }
+    }
+
+    /// Pass stragglers to callback instead of logging them.  See set_straggler_timeout().
+    pub fn on_stragglers<F>(&self, callback: F)
+    where
+        F: Fn(&[ListenerInfo]) + Sync + Send + 'static,
+    {
+        if let Some(c) = self.instance_for(".on_stragglers()") {
+            c.shared.listeners.set_straggler_callback(Box::new(callback));
+        }
+    }
+
+    /// Once exit has been signalled, the registered listeners that have not acked yet.  Empty
+    /// before exit is signalled.
+    pub fn stragglers(&self) -> Vec<ListenerInfo> {
+        match self.instance() {
+            Some(c) if c.poll_exit() => c.shared.listeners.live(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Listeners registered with ChexInstance::register() that are still live, ie have not
+    /// acked or dropped their guard, with the thread each registered from.
+    pub fn listeners(&self) -> Vec<ListenerInfo> {
+        self.instance()
+            .map(|c| c.shared.listeners.live())
+            .unwrap_or_default()
+    }
+
+    /// Number of live ChexInstances, including the one held by the global Chex itself.  Zero if
+    /// Chex is not initialized.
+    pub fn listener_count(&self) -> usize {
+        self.instance()
+            .map(|c| c.listener_count())
+            .unwrap_or(0)
+    }
+
+    /// Snapshot of the current state, with a Display impl suitable for a debug endpoint.
+    pub fn dump_state(&self) -> ChexStateSnapshot {
+        let Some(c) = self.instance() else {
+            return ChexStateSnapshot::default();
+        };
+        let shared = &c.shared;
+        let (signal_attempts, dropped_signal_attempts) = shared.signal_attempts.attempts();
+
+        ChexStateSnapshot {
+            initialized: true,
+            exit_signalled: shared.poll_exit(),
+            draining: shared.is_draining(),
+            origin: shared.origin.get().cloned(),
+            reason: shared.reason.get().cloned(),
+            panic_message: shared.panic.get().map(|p| p.message().to_string()),
+            exit_elapsed: shared.exit_elapsed(),
+            receiver_count: shared.receiver_count(),
+            waiter_count: shared.notify.waiters(),
+            exit_on_panic: self.panic_hooks.is_installed(),
+            panic_hooks: self.panic_hooks.chained_count(),
+            watchdogs: shared.watchdogs.count(),
+            pending_acks: shared.listeners.live(),
+            signal_attempts,
+            dropped_signal_attempts,
+        }
+    }
+
+    /// Block until exit has been signalled and every listener registered with
+    /// ChexInstance::register() has acked, or until timeout.  Returns one structured record of
+    /// the shutdown: origin, panic message, per-listener ack latencies and stragglers.  timeout
+    /// runs on the configured clock (ChexConfig::clock()).
+    pub fn wait_for_shutdown(&self, timeout: std::time::Duration) -> Result<ExitSummary, ChexError> {
+        Ok(self.instance().ok_or(ChexError::NotInitialized)?.shared.wait_for_shutdown(timeout))
+    }
+
+    /// Register hook to run at the given priority once exit is signalled.  See
+    /// ChexInstance::on_teardown().
+    pub fn on_teardown<F>(&self, priority: i32, hook: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        if let Some(c) = self.instance_for(".on_teardown()") {
+            c.on_teardown(priority, hook);
+        }
+    }
+
+    /// Run hook on the thread which first signals exit, before anyone else observes the exit.
+    /// See ChexInstance::at_exit().
+    pub fn at_exit<F>(&self, hook: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        if let Some(c) = self.instance_for(".at_exit()") {
+            c.at_exit(hook);
+        }
+    }
+
+    /// Whether shutdown has begun: exit was signalled, or a SIGTERM pre-stop delay is running.
+    /// False if Chex is not initialized.
+    pub fn is_draining(&self) -> bool {
+        self.instance().is_some_and(|c| c.is_draining())
+    }
+
+    /// Set whether the process is ready to serve traffic, ie once startup has finished.  See
+    /// is_ready().
+    pub fn set_ready(&self, ready: bool) {
+        if let Some(c) = self.instance_for(".set_ready()") {
+            c.set_ready(ready);
+        }
+    }
+
+    /// For readiness probes: true iff set_ready(true) was called and the process is not
+    /// draining.  Turns false as soon as shutdown begins.  False if Chex is not initialized.
+    ///
+    /// Not to be confused with ChexInstance::is_ready(name), for per-component startup.
+    pub fn is_ready(&self) -> bool {
+        self.instance().is_some_and(|c| c.is_ready_for_traffic())
+    }
+
+    /// For liveness probes: true unless exit was signalled for a failure (a panic, missed
+    /// watchdog, startup timeout or error).  Stays true through an orderly shutdown so the
+    /// process is not killed while draining.  False if Chex is not initialized.
+    pub fn is_live(&self) -> bool {
+        self.instance().is_some_and(|c| c.is_live())
+    }
+
+    /// Begin draining without signalling exit yet.  See ChexInstance::start_draining().
+    pub fn start_draining(&self) -> bool {
+        match self.instance_for(".start_draining()") {
+            Some(c) => c.start_draining(),
+            None => false,
+        }
+    }
+
+    /// Handle SIGTERM on the global Chex.  See ChexInstance::handle_sigterm().
+    #[cfg(all(feature = "sigterm", unix))]
+    #[track_caller]
+    pub fn handle_sigterm(&self, options: sigterm::SigtermOptions) {
+        if let Some(c) = self.instance_for(".handle_sigterm()") {
+            c.handle_sigterm(options);
+        }
+    }
+
+    /// Signal exit on the global Chex when a path appears, disappears or changes.  See
+    /// ChexInstance::exit_on_path().
+    #[cfg(all(feature = "fswatch", not(all(target_arch = "wasm32", target_os = "unknown"))))]
+    #[track_caller]
+    pub fn exit_on_path(&self, watch: fswatch::PathWatch) {
+        if let Some(c) = self.instance_for(".exit_on_path()") {
+            c.exit_on_path(watch);
+        }
+    }
+
+    /// Act as a minimal init for the global Chex when running as PID 1.  See
+    /// ChexInstance::act_as_pid1().
+    #[cfg(all(feature = "pid1", unix))]
+    #[track_caller]
+    pub fn act_as_pid1(&self, options: pid1::Pid1Options) -> bool {
+        match self.instance_for(".act_as_pid1()") {
+            Some(c) => c.act_as_pid1(options),
+            None => false,
+        }
+    }
+
+    /// Watch memory usage of the global Chex's process.  See ChexInstance::exit_on_memory().
+    #[cfg(target_os = "linux")]
+    #[track_caller]
+    pub fn exit_on_memory(&self, watermark: memory::MemoryWatermark) -> std::io::Result<()> {
+        match self.instance_for(".exit_on_memory()") {
+            Some(c) => c.exit_on_memory(watermark),
+            None => Err(std::io::Error::other(ChexError::NotInitialized)),
+        }
+    }
+
+    /// Signal exit on the global Chex when the parent process dies.  See
+    /// ChexInstance::exit_on_parent_death().
+    #[cfg(unix)]
+    #[track_caller]
+    pub fn exit_on_parent_death(&self, options: parent::ParentDeathOptions) {
+        if let Some(c) = self.instance_for(".exit_on_parent_death()") {
+            c.exit_on_parent_death(options);
+        }
+    }
+
+    /// Tie the global Chex to systemd.  See ChexInstance::enable_systemd().
+    #[cfg(all(feature = "systemd", target_os = "linux"))]
+    #[track_caller]
+    pub fn enable_systemd(&self, options: systemd::SystemdOptions) {
+        if let Some(c) = self.instance_for(".enable_systemd()") {
+            c.enable_systemd(options);
+        }
+    }
+
+    /// Register an interceptor which may hold off the exit signal for up to max_delay.  See
+    /// ChexInstance::intercept().
+    pub fn intercept(&self, name: &str, max_delay: std::time::Duration) -> Result<intercept::Interceptor, ChexError> {
+        Ok(self.instance().ok_or(ChexError::NotInitialized)?.intercept(name, max_delay))
+    }
+
+    /// Acquire a permit for one unit of work, or None once shutdown has begun.  See
+    /// ChexInstance::acquire_work_permit().
+    pub fn acquire_work_permit(&self) -> Option<quiesce::WorkPermit> {
+        match self.instance_for(".acquire_work_permit()") {
+            Some(c) => c.acquire_work_permit(),
+            None => None,
+        }
+    }
+
+    /// Park the current thread until every work permit has been dropped, or until timeout.
+    /// Returns true iff no permits are outstanding.
+    pub fn wait_for_work_permits(&self, timeout: std::time::Duration) -> bool {
+        match self.instance_for(".wait_for_work_permits()") {
+            Some(c) => c.wait_for_work_permits(timeout),
+            None => true,
+        }
+    }
+
+    /// Returns a crossbeam receiver which becomes ready once exit is signalled.  See
+    /// ChexInstance::crossbeam_receiver().
+    #[cfg(feature = "crossbeam")]
+    pub fn crossbeam_receiver(&self) -> Result<crossbeam_channel::Receiver<()>, ChexError> {
+        Ok(self.instance().ok_or(ChexError::NotInitialized)?.crossbeam_receiver())
+    }
+
+    /// Unpark thread when exit is signalled.  See ChexInstance::unpark_on_exit().
+    pub fn unpark_on_exit(&self, thread: std::thread::Thread) -> Result<interrupt::InterruptGuard, ChexError> {
+        Ok(self.instance().ok_or(ChexError::NotInitialized)?.unpark_on_exit(thread))
+    }
+
+    /// Interrupt target when exit is signalled.  See ChexInstance::interrupt_on_exit().
+    pub fn interrupt_on_exit(&self, target: impl interrupt::Interrupt + 'static) -> Result<interrupt::InterruptGuard, ChexError> {
+        Ok(self.instance().ok_or(ChexError::NotInitialized)?.interrupt_on_exit(target))
+    }
+
+    /// Run callback exactly once when exit is signalled.  See ChexInstance::on_exit().
+    pub fn on_exit<F>(&self, callback: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        if let Some(c) = self.instance_for(".on_exit()") {
+            c.on_exit(callback);
+        }
+    }
+
+    /// Run an async hook once exit is signalled.  See ChexInstance::on_exit_async().
+    #[cfg(feature = "tokio")]
+    pub fn on_exit_async<F, Fut>(&self, executor: teardown::AsyncExecutor, factory: F)
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = ()> + 'static,
+    {
+        if let Some(c) = self.instance_for(".on_exit_async()") {
+            c.on_exit_async(executor, factory);
+        }
+    }
+
+    /// Hand runtime over to be shut down on exit.  See ChexInstance::adopt_runtime().
+    ///
+    /// If Chex is not initialized, runtime is dropped (shutting it down) and
+    /// ChexError::NotInitialized is returned.
+    #[cfg(feature = "tokio")]
+    pub fn adopt_runtime(
+        &self,
+        runtime: tokio::runtime::Runtime,
+        grace: std::time::Duration,
+        timeout: std::time::Duration,
+    ) -> Result<tokio::runtime::Handle, ChexError> {
+        Ok(self.instance().ok_or(ChexError::NotInitialized)?.adopt_runtime(runtime, grace, timeout))
+    }
+
+    /// Adopt a thread, to be joined by join_all().  See ChexInstance::adopt().
+    pub fn adopt<R: Send + 'static>(&self, handle: std::thread::JoinHandle<R>) {
+        if let Some(c) = self.instance_for(".adopt()") {
+            c.adopt(handle);
+        }
+    }
+
+    /// Adopt a tokio task, to be joined by join_all().  See ChexInstance::adopt_task().
+    #[cfg(feature = "tokio")]
+    pub fn adopt_task<R: Send + 'static>(&self, handle: tokio::task::JoinHandle<R>) {
+        if let Some(c) = self.instance_for(".adopt_task()") {
+            c.adopt_task(handle);
+        }
+    }
+
+    /// Terminate child once exit is signalled.  See ChexInstance::adopt_child().
+    #[cfg(feature = "process")]
+    pub fn adopt_child(&self, child: std::process::Child, options: process::ChildOptions) {
+        if let Some(c) = self.instance_for(".adopt_child()") {
+            c.adopt_child(child, options);
+        }
+    }
+
+    /// Terminate a tokio child once exit is signalled.  See ChexInstance::adopt_tokio_child().
+    #[cfg(all(feature = "process", feature = "tokio"))]
+    pub fn adopt_tokio_child(&self, child: tokio::process::Child, options: process::ChildOptions) {
+        if let Some(c) = self.instance_for(".adopt_tokio_child()") {
+            c.adopt_tokio_child(child, options);
+        }
+    }
+
+    /// Park the current thread until every adopted thread and task has finished, or until
+    /// timeout.  See ChexInstance::join_all().
+    pub fn join_all(&self, timeout: std::time::Duration) -> join::JoinReport {
+        match self.instance_for(".join_all()") {
+            Some(c) => c.join_all(timeout),
+            None => join::JoinReport::default(),
+        }
+    }
+
+    /// Park the current thread until exit has been signalled and every teardown hook has run,
+    /// or until timeout.  Returns true iff teardown completed.
+    pub fn wait_for_teardown(&self, timeout: std::time::Duration) -> bool {
+        match self.instance_for(".wait_for_teardown()") {
+            Some(c) => c.wait_for_teardown(timeout),
+            None => false,
+        }
+    }
+
+    /// Register a subsystem whose shutdown should be accounted for.  See
+    /// ChexInstance::register_subsystem().
+    pub fn register_subsystem(&self, name: &str) -> Result<SubsystemHandle, ChexError> {
+        Ok(self.instance().ok_or(ChexError::NotInitialized)?.register_subsystem(name))
+    }
+
+    /// Park the current thread until every registered subsystem has called done() or been
+    /// dropped, ie after signalling exit.  Fails with the subsystems still running if timeout
+    /// elapses first.
+    pub fn wait_for_subsystems(&self, timeout: std::time::Duration) -> Result<(), Vec<ListenerInfo>> {
+        match self.instance_for(".wait_for_subsystems()") {
+            Some(c) => c.wait_for_subsystems(timeout),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the code location and thread of the first signal_exit() call, or None if exit has
+    /// not been signalled (or Chex is not initialized).
+    pub fn exit_origin(&self) -> Option<&ExitOrigin> {
+        self.instance()?.exit_origin()
+    }
+
+    /// Returns why exit was signalled, or None if exit has not been signalled (or Chex is not
+    /// initialized).
+    pub fn exit_reason(&self) -> Option<ExitReason> {
+        self.instance()?.exit_reason()
+    }
+
+    /// How long listeners took to observe exit, see ChexInstance::wakeup_latencies().  Empty if
+    /// Chex is not initialized.
+    pub fn wakeup_latencies(&self) -> Vec<latency::WakeupSample> {
+        self.instance().map(|c| c.wakeup_latencies()).unwrap_or_default()
+    }
+
+    /// When exit was first signalled, or None if it has not been (or Chex is not initialized).
+    /// The time is taken from the configured clock (ChexConfig::clock()).
+    pub fn exited_at(&self) -> Option<Instant> {
+        self.instance()?.exited_at()
+    }
+
+    /// Wall-clock time exit was first signalled, ie for logs and reports.
+    pub fn exited_at_system(&self) -> Option<SystemTime> {
+        self.instance()?.exited_at_system()
+    }
+
+    /// How long we have been shutting down, or None if exit has not been signalled (or Chex is
+    /// not initialized).
+    pub fn exit_elapsed(&self) -> Option<std::time::Duration> {
+        self.instance()?.exit_elapsed()
+    }
+
+    /// Signal exit because of err.  See ChexInstance::signal_exit_with_error().
+    #[cfg(feature = "anyhow")]
+    #[track_caller]
+    pub fn signal_exit_with_error(&self, err: anyhow::Error) -> bool {
+        match self.instance() {
+            None => {
+                sink::emit(ChexEvent::NotInitialized { operation: ".signal_exit_with_error()" });
+                exit_process(1);
+            }
+            Some(c) => c.signal_exit_with_error(err),
+        }
+    }
+
+    /// Take the error exit was signalled with, ie to return it from main().  Returns None if
+    /// exit was not signalled with an error, Chex is not initialized, or the error was already
+    /// taken.
+    #[cfg(feature = "anyhow")]
+    pub fn take_exit_error(&self) -> Option<anyhow::Error> {
+        self.instance()?.take_exit_error()
+    }
+
+    /// Every panic, error and missed watchdog reported so far, including those after the first
+    /// exit signal, for post-mortems.  Empty if Chex is not initialized.
+    pub fn collected_errors(&self) -> Vec<errors::CollectedError> {
+        self.instance().map(|c| c.collected_errors()).unwrap_or_default()
+    }
+
+    /// The value to return from main(), with the exit status derived from the recorded
+    /// ExitReason.
+    pub fn termination(&self) -> ChexTermination {
+        ChexTermination {
+            reason: self.exit_reason(),
+        }
+    }
+
+    /// Map the recorded ExitReason to the process exit status used by termination() and
+    /// exit_process(), ie to give SIGTERM, panic and deadline exits distinct statuses for
+    /// monitoring.  The policy is process-wide and may be installed before init().
+    pub fn set_exit_code_policy(&self, policy: ExitCodePolicy) {
+        *EXIT_CODE_POLICY.write().unwrap_or_else(|e| e.into_inner()) = Some(policy);
+    }
+
+    /// Exit the process with the status for the recorded ExitReason, as termination() would
+    /// report it, ie at the end of the application's own cleanup.  Exits with 0 if exit was
+    /// not signalled.
+    pub fn exit_process(&self) -> ! {
+        exit_process(self.termination().exit_code())
+    }
+
+    /// Link the global Chex with another (ie local) coordinator, so that exit signalled on
+    /// either side propagates to the other.  See ChexInstance::link().
+    #[track_caller]
+    pub fn link<U>(&self, other: &ChexInstance<U>) {
+        if let Some(c) = self.instance_for(".link()") {
+            c.link(other);
+        }
+    }
+
+    /// Returns details of the first panic caught by the exit-on-panic hook, if any.
+    pub fn panic_info(&self) -> Option<&PanicReport> {
+        self.instance()?.panic_info()
+    }
+}
+
+#[cfg(feature = "std")]
+/// Terminate the process after a fatal misuse.  wasm32-unknown-unknown has no process to exit,
+/// so panic there instead.
+fn exit_process(code: i32) -> ! {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    std::process::exit(code);
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    panic!("chex: fatal error (exit code {code}), and there is no process to exit");
+}
+
+#[cfg(feature = "std")]
+impl ChexShared {
+    fn poll_exit(&self) -> bool {
+        self.exit.load(Acquire)
+    }
+
+    fn raise_exit_flag(&self) {
+        self.exit.store(true, Release);
+        if let Some(generation) = self.global {
+            let _ = EXITING.fetch_update(Relaxed, Relaxed, |s| (s >> 1 == generation).then_some(s | 1));
+        }
+    }
+
+    /// Mark the coordinator as draining ahead of exit.  Returns true if it already was.
+    fn start_draining(&self) -> bool {
+        self.drain.raise()
+    }
+
+    fn is_draining(&self) -> bool {
+        self.drain.is_raised() || self.poll_exit()
+    }
+
+    /// Whether exit was signalled for a failure (rather than an orderly shutdown), after which
+    /// the process should be reported as not live.
+    fn failed(&self) -> bool {
+        matches!(
+            self.reason.get(),
+            Some(ExitReason::Panic { .. } | ExitReason::Watchdog { .. } | ExitReason::NotReady | ExitReason::Error { .. })
+        )
+    }
+
+    fn signal_exit_from(&self, reason: ExitReason, origin: ExitOrigin) -> bool {
+        self.signal_exit_carrying(reason, origin, ExitAttachments::default())
+    }
+
+    /// Signal exit, keeping attachments if this call is the first to signal.
+    fn signal_exit_carrying(&self, reason: ExitReason, origin: ExitOrigin, attachments: ExitAttachments) -> bool {
+        /*
+         * Whoever records the origin is the first signaller.  Record it before raising the flag
+         * so that anyone observing the flag can also find the origin.  Likewise only the first
+         * signaller's payload and error are kept, so they always match the recorded reason and
+         * origin.
+         *
+         * The minimal panic hook raises the flag without an origin or any hooks.  The next caller
+         * then records its origin and runs the hooks, but the panic stays the first signal.
+         */
+        let raised = self.poll_exit();
+        let first = self.origin.set(origin.clone()).is_ok();
+        self.signal_attempts.record(reason.clone(), origin, first && !raised);
+        if !first {
+            /*
+             * The first signaller raises the flag once its at-exit hooks and interceptors are
+             * done, so anyone else only waits for that rather than letting listeners react
+             * early.  Its own hooks signalling again must not wait for themselves.
+             */
+            if self.first_signaller.get() != Some(&std::thread::current().id()) {
+                self.wait_for_first_signaller();
+            }
+            return false;
+        }
+        let _ = self.first_signaller.set(std::thread::current().id());
+        if raised {
+            self.run_at_exit();
+        } else {
+            let _ = self.reason.set(reason);
+            if let Some(payload) = attachments.payload {
+                let _ = self.payload.set(payload);
+            }
+            #[cfg(feature = "anyhow")]
+            if let Some(error) = attachments.error {
+                *self.error.lock().unwrap_or_else(|e| e.into_inner()) = Some(error);
+            }
+            self.run_at_exit();
+            self.interceptors.hold(&self.config);
+
+            self.mark_exited_at();
+            self.raise_exit_flag();
+            self.wake_blocked();
+            self.drain.wake();
+            self.interrupts.interrupt_all();
+            #[cfg(feature = "crossbeam")]
+            self.exit_channel.close();
+        }
+
+        if let Some(reason) = self.reason.get() {
+            telemetry::exit_signalled(reason);
+            sink::emit(ChexEvent::ExitSignalled { reason, origin: self.origin.get() });
+            self.listeners.on_exit(reason, self.origin.get());
+        }
+        self.teardown.start();
+        #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+        if let Some(grace) = self.config.grace_period() {
+            self.spawn_deadline(grace);
+        }
+
+        self.notify.notify_all();
+        self.signal_links();
+        !raised
+    }
+
+    /// Park until the first signaller has raised the exit flag.
+    fn wait_for_first_signaller(&self) {
+        let mut guard = self.exit_lock.lock().unwrap_or_else(|e| e.into_inner());
+        while !self.poll_exit() {
+            guard = self.exit_cv.wait(guard).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// Terminate the process as configured once grace has elapsed.  One "chex-deadline" thread
+    /// serves every call: a later call only moves the deadline if it is earlier.
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    fn spawn_deadline(&self, grace: std::time::Duration) {
+        let clock = self.config.timer_clock();
+        let at = clock.now() + grace;
+        {
+            let mut forced_exit_at = self.forced_exit_at.lock().unwrap_or_else(|e| e.into_inner());
+            match *forced_exit_at {
+                Some((current, _)) if current <= at => return,
+                Some(_) => {
+                    *forced_exit_at = Some((at, grace));
+                    return;
+                }
+                None => *forced_exit_at = Some((at, grace)),
+            }
+        }
+
+        let forced_exit = self.config.forced_exit;
+        let forced_exit_at = self.forced_exit_at.clone();
+        let spawned = std::thread::Builder::new()
+            .name("chex-deadline".to_string())
+            .spawn(move || {
+                loop {
+                    let Some((at, grace)) = *forced_exit_at.lock().unwrap_or_else(|e| e.into_inner()) else { return };
+                    let now = clock.now();
+                    if now >= at {
+                        sink::emit(ChexEvent::DeadlineExpired { grace, forced_exit });
+                        match forced_exit {
+                            ForcedExit::Exit(code) => std::process::exit(code),
+                            ForcedExit::Abort => std::process::abort(),
+                        }
+                    }
+                    clock.sleep((at - now).min(std::time::Duration::from_millis(100)));
+                }
+            });
+        if let Err(e) = spawned {
+            *self.forced_exit_at.lock().unwrap_or_else(|e| e.into_inner()) = None;
+            sink::emit(ChexEvent::ThreadSpawnFailed { thread: "chex-deadline", error: &e });
+        }
+    }
+
+    /// Link self and other so that exit signalled on either side is signalled on the other.  If
+    /// either side has already exited, the other is signalled now.
+    fn link(self: &Arc<Self>, other: &Arc<ChexShared>, location: &'static Location<'static>) {
+        if Arc::ptr_eq(self, other) {
+            return;
+        }
+        self.link_child(other, location);
+        other.link_child(self, location);
+    }
+
+    /// Link self to child in one direction only: exit signalled on self is signalled on child.
+    /// If self has already exited, child is signalled now.  Links to dropped coordinators (ie
+    /// finished scopes) are pruned here, so repeated links don't accumulate.
+    fn link_child(&self, child: &Arc<ChexShared>, location: &'static Location<'static>) {
+        {
+            let mut links = self.links.lock().unwrap_or_else(|e| e.into_inner());
+            links.retain(|l| l.strong_count() > 0);
+            links.push(Arc::downgrade(child));
+        }
+        if self.poll_exit() {
+            let origin = self.origin.get().cloned()
+                .unwrap_or_else(|| ExitOrigin::from_location(location));
+            child.signal_exit_from(self.reason.get().cloned().unwrap_or(ExitReason::Manual), origin);
+        }
+    }
+
+    /// Run the at_exit hooks, most recently registered first.  Later registrations run
+    /// immediately.
+    fn run_at_exit(&self) {
+        let hooks = self.at_exit.lock().unwrap_or_else(|e| e.into_inner()).take();
+        for hook in hooks.into_iter().flatten().rev() {
+            let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(hook));
+        }
+    }
+
+    fn add_at_exit(&self, hook: AtExitHook) {
+        let mut hooks = self.at_exit.lock().unwrap_or_else(|e| e.into_inner());
+        match hooks.as_mut() {
+            Some(hooks) => hooks.push(hook),
+            None => {
+                drop(hooks);
+                hook();
+            }
+        }
+    }
+
+    /// Propagate a first exit signal to every linked coordinator, keeping its reason and origin.
+    fn signal_links(&self) {
+        let (Some(origin), Some(reason)) = (self.origin.get(), self.reason.get()) else {
+            return;
+        };
+        let links: Vec<Arc<ChexShared>> = self.links.lock().unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter_map(std::sync::Weak::upgrade)
+            .collect();
+        for link in links {
+            link.signal_exit_from(reason.clone(), origin.clone());
+        }
+    }
+
+    /// Spawn a timer thread that signals exit at deadline, unless exit happens first.
+    fn schedule_exit(self: &Arc<Self>, deadline: Instant, location: &'static Location<'static>) {
+        let shared = Arc::downgrade(self);
+        let clock = self.config.timer_clock();
+        let spawned = std::thread::Builder::new()
+            .name("chex-timer".to_string())
+            .spawn(move || {
+                loop {
+                    let Some(shared) = shared.upgrade() else { return };
+                    if shared.poll_exit() {
+                        return;
+                    }
+
+                    let now = clock.now();
+                    if now >= deadline {
+                        shared.signal_exit_from(ExitReason::Deadline, ExitOrigin::from_location(location));
+                        return;
+                    }
+                    drop(shared);
+                    clock.sleep((deadline - now).min(std::time::Duration::from_millis(100)));
+                }
+            });
+        if let Err(e) = spawned {
+            sink::emit(ChexEvent::ThreadSpawnFailed { thread: "chex-timer", error: &e });
+        }
+    }
+
+    /// Describe the shutdown as it stands now.
+    fn summary(&self) -> ExitSummary {
+        let exited_at = self.exited_at.get().copied();
+        let (completed, stragglers): (Vec<ListenerInfo>, Vec<ListenerInfo>) = self.listeners.listeners()
+            .into_iter()
+            .partition(|l| l.acked_at().is_some());
+
+        ExitSummary {
+            exited: self.poll_exit(),
+            origin: self.origin.get().cloned(),
+            reason: self.reason.get().cloned(),
+            panic_message: self.panic.get().map(|p| p.message().to_string()),
+            completions: completed.into_iter()
+                .map(|l| {
+                    let latency = match (l.acked_at(), exited_at) {
+                        (Some(acked), Some(exited)) => acked.saturating_duration_since(exited),
+                        _ => std::time::Duration::ZERO,
+                    };
+                    (l, latency)
+                })
+                .collect(),
+            stragglers,
+            elapsed: self.exit_elapsed(),
+        }
+    }
+
+    /// Wait until exit has been signalled and every registered listener has acked, or until
+    /// timeout, then summarize.
+    fn wait_for_shutdown(&self, timeout: std::time::Duration) -> ExitSummary {
+        let clock = self.config.timer_clock();
+        let deadline = clock.now() + timeout;
+        loop {
+            let summary = self.summary();
+            if summary.is_complete() || clock.now() >= deadline {
+                return summary;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+    }
+
+    /// Wait until every registered listener has acked, or until timeout.
+    fn wait_for_subsystems(&self, timeout: std::time::Duration) -> Result<(), Vec<ListenerInfo>> {
+        let clock = self.config.timer_clock();
+        let deadline = clock.now() + timeout;
+        loop {
+            let live = self.listeners.live();
+            if live.is_empty() {
+                return Ok(());
+            }
+            if clock.now() >= deadline {
+                return Err(live);
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+    }
+
+    /// Number of live ChexInstances, including the one held by the global Chex.
+    fn receiver_count(&self) -> usize {
+        self.notify.instances()
+    }
+
+    /// Raise the exit flag and wake async listeners without allocating or logging.
+    fn signal_exit_minimal(&self) {
+        let _ = self.reason.set(ExitReason::Panic { message: String::new() });
+        self.mark_exited_at();
+        self.raise_exit_flag();
+        self.wake_blocked();
+        self.drain.wake();
+        self.interrupts.try_interrupt_all();
+        #[cfg(feature = "crossbeam")]
+        self.exit_channel.try_close();
+        self.notify.notify_all();
+    }
+
+    /// How long ago exit was first signalled, on the configured clock.
+    fn exit_elapsed(&self) -> Option<std::time::Duration> {
+        let exited_at = self.exited_at.get()?;
+        Some(self.config.now().saturating_duration_since(*exited_at))
+    }
+
+    /// Record when exit was first signalled, on the configured clock.
+    fn mark_exited_at(&self) {
+        if self.exited_at.set(self.config.now()).is_ok() {
+            let _ = self.exited_at_system.set(SystemTime::now());
+        }
+    }
+
+    /// Wake threads parked in block_until_exit().
+    fn wake_blocked(&self) {
+        /*
+         * Take the lock so a waiter can't miss the wakeup between checking the flag and waiting.
+         */
+        let _guard = self.exit_lock.lock().unwrap_or_else(|e| e.into_inner());
+        self.exit_cv.notify_all();
+    }
+
+    /// Park the current thread until exit is signalled or timeout elapses.
+    ///
+    /// Returns true iff exit has been signalled.
+    fn block_until_exit(&self, timeout: Option<std::time::Duration>) -> bool {
+        let deadline = timeout.map(|t| self.config.now() + t);
+        let mut guard = self.exit_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let waited = !self.poll_exit();
+        while !self.poll_exit() {
+            guard = match deadline {
+                None => self.exit_cv.wait(guard).unwrap_or_else(|e| e.into_inner()),
+                Some(deadline) => {
+                    let now = self.config.now();
+                    if now >= deadline {
+                        return false;
+                    }
+                    self.exit_cv.wait_timeout(guard, self.config.wait_slice(deadline - now))
+                        .unwrap_or_else(|e| e.into_inner())
+                        .0
+                }
+            };
+        }
+        if waited {
+            self.record_wakeup(latency::WakeupKind::Blocking);
+        }
+        true
+    }
+
+    fn record_wakeup(&self, kind: latency::WakeupKind) {
+        if let Some(latency) = self.exit_elapsed() {
+            telemetry::listener_woke(latency);
+            self.wakeups.record(kind, latency);
+        }
+    }
+
+    /// Wakeups recorded so far, plus the acks of registered listeners.
+    fn wakeup_latencies(&self) -> Vec<latency::WakeupSample> {
+        let mut samples = self.wakeups.samples();
+        if let Some(exited_at) = self.exited_at.get() {
+            for l in self.listeners.listeners() {
+                if let Some(acked) = l.acked_at() {
+                    let latency = acked.saturating_duration_since(*exited_at);
+                    samples.push(latency::ack_sample(l.name(), l.thread_name(), latency));
+                }
+            }
+        }
+        samples
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> ChexInstance<T> {
+    /// Initialize the channels and exit flag.
+    ///
+    /// Should not be called directly by library users.
+    fn new(config: ChexConfig) -> Self {
+        Self::create(config, None)
+    }
+
+    /// Like new(), for the coordinator behind the global Chex, whose exit flag is mirrored for
+    /// exiting().
+    fn new_global(config: ChexConfig) -> Self {
+        Self::create(config, Some(EXITING.load(Relaxed) >> 1))
+    }
+
+    fn create(config: ChexConfig, global: Option<usize>) -> Self {
+        telemetry::instance_created();
+        let instance = Self {
+            shared: Arc::new(ChexShared {
+                exit: sync::AtomicBool::new(false),
+                global,
+                drain: quiesce::DrainFlag::new(),
+                serving: AtomicBool::new(false),
+                exit_lock: sync::Mutex::new(()),
+                exit_cv: sync::Condvar::new(),
+                notify: notify::ExitNotify::new(),
+                origin: sync::OnceLock::new(),
+                first_signaller: sync::OnceLock::new(),
+                reason: sync::OnceLock::new(),
+                exited_at: sync::OnceLock::new(),
+                exited_at_system: sync::OnceLock::new(),
+                panic: sync::OnceLock::new(),
+                payload: sync::OnceLock::new(),
+                warnings: warning::WarningChannel::new(),
+                links: Mutex::new(Vec::new()),
+                readiness: readiness::ReadinessRegistry::new(),
+                teardown: Arc::new(teardown::TeardownRegistry::new()),
+                at_exit: Mutex::new(Some(Vec::new())),
+                interceptors: Arc::new(intercept::InterceptorRegistry::new()),
+                permits: Arc::new(quiesce::PermitRegistry::new()),
+                interrupts: Arc::new(interrupt::InterruptRegistry::new()),
+                #[cfg(feature = "crossbeam")]
+                exit_channel: crossbeam::ExitChannel::new(),
+                joins: join::JoinRegistry::new(),
+                #[cfg(feature = "process")]
+                children: Arc::new(process::ChildRegistry::new()),
+                errors: errors::ErrorLog::new(),
+                signal_attempts: diagnostics::SignalLog::new(),
+                wakeups: latency::WakeupRecorder::new(),
+                #[cfg(feature = "anyhow")]
+                error: Mutex::new(None),
+                watchdogs: watchdog::WatchdogRegistry::new(),
+                forced_exit_at: Arc::new(Mutex::new(None)),
+                listeners: Arc::new(listener::ListenerRegistry::new(config.timer_clock())),
+                config,
+            }),
+            observed: AtomicBool::new(false),
+            payload: std::marker::PhantomData,
+        };
+        instance.shared.notify.instance_created();
+        instance
+    }
+
+    /// Signal all listeners to exit, then return to allow the caller to do their own cleanup.
+    ///
+    /// Returns true iff this call was the first to signal exit, so callers can log or tear down
+    /// only once.
+    ///
+    /// Everything this thread wrote before signal_exit() raised the flag is visible to any
+    /// thread which then sees poll_exit() return true (or wakes from a wait for exit), so data
+    /// describing why we are exiting can be published with plain writes ahead of the signal.
+    #[track_caller]
+    pub fn signal_exit(&self) -> bool {
+        self.shared.signal_exit_from(ExitReason::Manual, ExitOrigin::from_location(Location::caller()))
+    }
+
+    /// Signal exit like signal_exit(), recording ExitReason::OsSignal(signo).  Chex installs no
+    /// OS signal handlers itself; call this from the application's own (ie a signal-hook or
+    /// tokio::signal loop).
+    ///
+    /// Returns true iff this call was the first to signal exit.
+    #[track_caller]
+    pub fn signal_exit_on_os_signal(&self, signo: i32) -> bool {
+        self.shared.signal_exit_from(ExitReason::OsSignal(signo), ExitOrigin::from_location(Location::caller()))
+    }
+
+    /// Signal exit like signal_exit(), and start a watchdog thread that terminates the process
+    /// (as configured with ChexConfig::forced_exit()) if it is still alive once grace has
+    /// elapsed.
+    ///
+    /// Returns true iff this call was the first to signal exit.
+    ///
+    /// On wasm32-unknown-unknown there is no process to terminate, so this only signals exit.
+    #[track_caller]
+    pub fn signal_exit_with_deadline(&self, grace: std::time::Duration) -> bool {
+        let first = self.signal_exit();
+
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        let _ = grace;
+        #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+        self.shared.spawn_deadline(grace);
+
+        first
+    }
+
+    /// Like signal_exit().  Signalling an instance cannot fail, so this always returns Ok; it
+    /// mirrors Chex::try_signal_exit(), which fails if Chex is not initialized.
+    #[track_caller]
+    pub fn try_signal_exit(&self) -> Result<bool, ChexError> {
+        Ok(self.shared.signal_exit_from(ExitReason::Manual, ExitOrigin::from_location(Location::caller())))
+    }
+
+    /// Returns true iff exit has already been signalled.
+    ///
+    /// Once this returns true, writes made before the signal which raised the exit flag are
+    /// visible to the caller.  See signal_exit().
+    pub fn poll_exit(&self) -> bool {
+        if self.shared.global.is_some() {
+            GLOBAL_CHECK_EXIT.panic_hooks.ensure_if_replaced(&GLOBAL_CHECK_EXIT);
+        }
+        let exited = self.shared.poll_exit();
+        /*
+         * Only the first observation writes, so hot polling loops don't keep the cache line
+         * bouncing between cores.
+         */
+        if exited && !self.observed.load(Relaxed) && !self.observed.swap(true, Relaxed) {
+            self.shared.record_wakeup(latency::WakeupKind::Poll);
+        }
+        exited
+    }
+
+    /// How long listeners took to observe exit: every check_exit_async() and block_until_exit()
+    /// wakeup, the first poll_exit() returning true on each instance, and every registered
+    /// listener's ack.  Summarize with latency::LatencySummary::of().
+    pub fn wakeup_latencies(&self) -> Vec<latency::WakeupSample> {
+        self.shared.wakeup_latencies()
+    }
+
+    /// Signal exit because of err (with ExitReason::Error carrying its message), keeping err for main() to retrieve
+    /// with take_exit_error().  Only the error of the first signaller is kept.
+    ///
+    /// Returns true iff this call was the first to signal exit.
+    #[cfg(feature = "anyhow")]
+    #[track_caller]
+    pub fn signal_exit_with_error(&self, err: anyhow::Error) -> bool {
+        let origin = ExitOrigin::from_location(Location::caller());
+        let message = format!("{err:#}");
+        self.shared.errors.record(ExitReasonKind::Error, message.clone(), origin.clone());
+        self.shared.signal_exit_carrying(
+            ExitReason::Error { message },
+            origin,
+            ExitAttachments { error: Some(err), ..Default::default() },
+        )
+    }
+
+    /// Take the error exit was signalled with, if any.  Subsequent calls return None.
+    #[cfg(feature = "anyhow")]
+    pub fn take_exit_error(&self) -> Option<anyhow::Error> {
+        self.shared.error.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+
+    /// Every panic, error and missed watchdog reported to this coordinator, in order, including
+    /// those after exit was already signalled.  Kept up to a bound; see
+    /// dropped_error_count().
+    pub fn collected_errors(&self) -> Vec<errors::CollectedError> {
+        self.shared.errors.entries()
+    }
+
+    /// Number of failures not kept by collected_errors() because its buffer was full.
+    pub fn dropped_error_count(&self) -> usize {
+        self.shared.errors.dropped()
+    }
+
+    /// Returns Err(ChexExited) once exit has been signalled.  See chex::checkpoint().
+    pub fn checkpoint(&self) -> Result<(), ChexExited> {
+        if self.poll_exit() {
+            return Err(ChexExited);
+        }
+        Ok(())
+    }
+
+    /// Broadcast a non-fatal warning to every warning subscriber.  Does not signal exit.
+    #[track_caller]
+    pub fn signal_warning(&self, message: impl Into<String>) {
+        self.shared.warnings.send(message.into(), ExitOrigin::from_location(Location::caller()));
+    }
+
+    /// Subscribe to warnings signalled from now on.
+    pub fn subscribe_warnings(&self) -> WarningSubscription {
+        self.shared.warnings.subscribe()
+    }
+
+    /// Report that component name has finished starting up.
+    pub fn ready(&self, name: &str) {
+        self.shared.readiness.ready(name);
+    }
+
+    /// Whether component name has reported ready.
+    pub fn is_ready(&self, name: &str) -> bool {
+        self.shared.readiness.is_ready(name)
+    }
+
+    /// Park the current thread until every one of names has reported ready.  Fails with the
+    /// components still missing if timeout elapses or exit is signalled first.
+    pub fn wait_all_ready(&self, names: &[&str], timeout: std::time::Duration) -> Result<(), NotReady> {
+        self.shared.readiness.wait_all(names, timeout, || self.poll_exit())
+    }
+
+    /// Like wait_all_ready(), but signal exit (with ExitReason::NotReady) if the components
+    /// don't become ready in time.
+    #[track_caller]
+    pub fn wait_all_ready_or_exit(&self, names: &[&str], timeout: std::time::Duration) -> Result<(), NotReady> {
+        let location = Location::caller();
+        self.wait_all_ready(names, timeout).inspect_err(|_| {
+            self.shared.signal_exit_from(ExitReason::NotReady, ExitOrigin::from_location(location));
+        })
+    }
+
+    /// Number of live clones of this ChexInstance, including the one held by the global Chex.
+    pub fn listener_count(&self) -> usize {
+        self.shared.receiver_count()
+    }
+
+    /// Number of tasks currently waiting in check_exit_async() (or another async wait) on this
+    /// coordinator.  Clones which never wait don't count.
+    pub fn waiter_count(&self) -> usize {
+        self.shared.notify.waiters()
+    }
+
+    /// Park the current thread until exit is signalled.  The blocking counterpart of
+    /// check_exit_async() for non-async threads.
+    pub fn block_until_exit(&self) {
+        self.shared.block_until_exit(None);
+    }
+
+    /// Park the current thread until exit is signalled or timeout elapses on the configured
+    /// clock (ChexConfig::clock()).
+    ///
+    /// Returns true iff exit has been signalled.
+    pub fn block_until_exit_timeout(&self, timeout: std::time::Duration) -> bool {
+        self.shared.block_until_exit(Some(timeout))
+    }
+
+    /// Returns the code location and thread of the first signal_exit() call, or None if exit has
+    /// not been signalled.
+    pub fn exit_origin(&self) -> Option<&ExitOrigin> {
+        self.shared.origin.get()
+    }
+
+    /// Returns why exit was signalled, or None if exit has not been signalled.
+    pub fn exit_reason(&self) -> Option<ExitReason> {
+        self.shared.reason.get().cloned()
+    }
+
+    /// When exit was first signalled, or None if it has not been.
+    /// The time is taken from the configured clock (ChexConfig::clock()).
+    pub fn exited_at(&self) -> Option<Instant> {
+        self.shared.exited_at.get().copied()
+    }
+
+    /// Wall-clock time exit was first signalled, ie for logs and reports.
+    pub fn exited_at_system(&self) -> Option<SystemTime> {
+        self.shared.exited_at_system.get().copied()
+    }
+
+    /// How long we have been shutting down, or None if exit has not been signalled.
+    pub fn exit_elapsed(&self) -> Option<std::time::Duration> {
+        self.shared.exit_elapsed()
+    }
+
+    /// Link this coordinator with other, so that exit signalled on either side is also signalled
+    /// on the other, with the same reason and origin.  Useful when composing frameworks which
+    /// each own their own coordinator.  If either side has already exited, the other is
+    /// signalled immediately.
+    ///
+    /// Links don't keep the other coordinator alive.  An exit raised by the minimal panic hook
+    /// is not propagated, since that hook only raises the flag.
+    #[track_caller]
+    pub fn link<U>(&self, other: &ChexInstance<U>) {
+        self.shared.link(&other.shared, Location::caller());
+    }
+
+    /// Returns true iff exit has been signalled for one of reasons.
+    pub fn poll_exit_for(&self, reasons: &[ExitReasonKind]) -> bool {
+        self.shared.reason.get().is_some_and(|r| reasons.contains(&r.kind()))
+    }
+
+    /// Returns details of the first panic caught by the exit-on-panic hook, if any.
+    pub fn panic_info(&self) -> Option<&PanicReport> {
+        self.shared.panic.get()
+    }
+
+    /// Schedule exit to be signalled once delay has elapsed on the configured clock.  The exit
+    /// origin is the location of this call.
+    #[track_caller]
+    pub fn signal_exit_after(&self, delay: std::time::Duration) {
+        self.signal_exit_at(self.shared.config.timer_clock().now() + delay);
+    }
+
+    /// Schedule exit to be signalled at deadline, as told by the configured clock.  The exit
+    /// origin is the location of this call.
+    #[track_caller]
+    pub fn signal_exit_at(&self, deadline: Instant) {
+        self.shared.schedule_exit(deadline, Location::caller());
+    }
+
+    /// Register this listener under name, along with the current thread, so that it can be
+    /// enumerated with Chex::listeners(), and reported as a straggler if it has not acked (or
+    /// dropped the returned guard) some time after exit is signalled.  See
+    /// Chex::set_straggler_timeout().
+    ///
+    /// Registered listeners also let the exit-on-panic grace window (Chex::set_panic_grace())
+    /// wait for exactly the listeners on other threads.
+    pub fn register(&self, name: &str) -> ListenerGuard {
+        self.shared.listeners.register(name)
+    }
+
+    /// Returns a guard which signals exit when dropped, for a worker which must never stop
+    /// silently: hold it for the worker's lifetime, and its return or unwinding takes the
+    /// process down.  See ChexGuard.
+    #[track_caller]
+    pub fn exit_on_drop(&self) -> guard::ChexGuard {
+        guard::ChexGuard::new(self.shared.clone(), Location::caller())
+    }
+
+    /// Register hook to run once exit is signalled, on a dedicated "chex-teardown" thread.
+    ///
+    /// Hooks run one at a time in ascending priority order (registration order within a
+    /// priority), ie stop accepting traffic at priority 0, flush databases at 10 and close
+    /// telemetry at 20.  A panicking hook does not stop later ones.  Hooks registered after
+    /// exit run immediately on their own thread.  Exits raised by the minimal panic hook do not
+    /// start teardown.
+    pub fn on_teardown<F>(&self, priority: i32, hook: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.shared.teardown.add(priority, Box::new(hook));
+    }
+
+    /// Run hook on the thread which first signals exit, before the exit flag is raised and
+    /// before signal_exit() returns, like libc atexit().  Hooks run in reverse registration
+    /// order.  Useful for actions which must happen before anyone else reacts, ie persisting a
+    /// crash marker.
+    ///
+    /// Keep hooks short: every listener is held off while they run, and so is any other thread
+    /// signalling exit meanwhile, which waits for the flag (so a hook must not wait for such a
+    /// thread either).  For exit-on-panic they run inside the panic hook, where a panicking hook
+    /// aborts the process.  Hooks registered after exit run immediately on the registering
+    /// thread.
+    pub fn at_exit<F>(&self, hook: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.shared.add_at_exit(Box::new(hook));
+    }
+
+    /// Whether shutdown has begun: exit was signalled, or a SIGTERM pre-stop delay is running.
+    /// Readiness probes should fail once this is true.
+    pub fn is_draining(&self) -> bool {
+        self.shared.is_draining()
+    }
+
+    /// Begin draining ahead of exit (a "soft exit"): is_draining() and wait_draining() report it
+    /// and readiness probes fail, but exit is not signalled, so in-flight and (for now) new work
+    /// still runs.  Returns true iff this call was the first to start draining.
+    pub fn start_draining(&self) -> bool {
+        !self.shared.start_draining()
+    }
+
+    /// Returns once draining has begun (see start_draining()) or exit has been signalled.
+    pub async fn wait_draining(&self) {
+        std::future::poll_fn(|cx| {
+            if self.shared.is_draining() {
+                return std::task::Poll::Ready(());
+            }
+            self.shared.drain.register(cx.waker());
+            if self.shared.is_draining() {
+                std::task::Poll::Ready(())
+            } else {
+                std::task::Poll::Pending
+            }
+        }).await
+    }
+
+    /// Set whether the process is ready to serve traffic.  See is_ready_for_traffic().
+    pub fn set_ready(&self, ready: bool) {
+        self.shared.serving.store(ready, Relaxed);
+    }
+
+    /// For readiness probes: true iff set_ready(true) was called and is_draining() is false.
+    pub fn is_ready_for_traffic(&self) -> bool {
+        self.shared.serving.load(Relaxed) && !self.shared.is_draining()
+    }
+
+    /// For liveness probes: true unless exit was signalled for a failure.  See Chex::is_live().
+    pub fn is_live(&self) -> bool {
+        !self.shared.failed()
+    }
+
+    /// Signal exit with ExitReason::OsSignal(15) on SIGTERM, after the pre-stop delay from
+    /// options, during which is_draining() is already true.  This is the Kubernetes rolling
+    /// update dance: fail readiness, let the endpoint controller drain traffic, then exit.
+    #[cfg(all(feature = "sigterm", unix))]
+    #[track_caller]
+    pub fn handle_sigterm(&self, options: sigterm::SigtermOptions) {
+        let origin = ExitOrigin::from_location(Location::caller());
+        sigterm::add(Arc::downgrade(&self.shared), origin, &options);
+    }
+
+    /// Signal exit with ExitReason::Manual once the path of watch appears, disappears or changes
+    /// as configured, ie `PathWatch::appears("/run/myapp/stop")` for an operator's stop file.
+    /// The exit origin is the exit_on_path() call.
+    #[cfg(all(feature = "fswatch", not(all(target_arch = "wasm32", target_os = "unknown"))))]
+    #[track_caller]
+    pub fn exit_on_path(&self, watch: fswatch::PathWatch) {
+        let origin = ExitOrigin::from_location(Location::caller());
+        fswatch::watch(Arc::downgrade(&self.shared), origin, watch);
+    }
+
+    /// Act as a minimal init, ie for a container whose entrypoint is this process: SIGTERM and
+    /// SIGINT are forwarded to every child adopted with adopt_child() and signal exit with
+    /// ExitReason::OsSignal(signum), and a "chex-pid1" thread reaps every child that exits,
+    /// including orphaned grandchildren reparented to PID 1.  Adopted children are still
+    /// terminated (and killed after their grace period) during teardown.
+    ///
+    /// Adopted children are collected through their own Child handle, which keeps their exit
+    /// status.  Other children are only reaped once they have been zombies for
+    /// Pid1Options::orphan_grace(), so a std::process::Child you wait on yourself keeps its exit
+    /// status as long as you wait within that time.
+    ///
+    /// SIGTERM is then handled here, and also passed on to handle_sigterm(), whichever is set up
+    /// first.
+    ///
+    /// Returns false and does nothing if options require PID 1 (the default) and this process
+    /// isn't.
+    #[cfg(all(feature = "pid1", unix))]
+    #[track_caller]
+    pub fn act_as_pid1(&self, options: pid1::Pid1Options) -> bool {
+        let origin = ExitOrigin::from_location(Location::caller());
+        pid1::add(Arc::downgrade(&self.shared), origin, &options)
+    }
+
+    /// Once memory usage reaches watermark, start draining (a soft exit, the default) or signal
+    /// exit with ExitReason::Resource, so the service can shed load before the OOM killer
+    /// strikes.  A "chex-memory" thread samples usage every poll interval of watermark; the
+    /// exit origin is the exit_on_memory() call.
+    ///
+    /// Fails if usage can't be read, ie without a memory cgroup, or with
+    /// MemoryWatermark::cgroup_fraction() if the cgroup has no limit.
+    #[cfg(target_os = "linux")]
+    #[track_caller]
+    pub fn exit_on_memory(&self, watermark: memory::MemoryWatermark) -> std::io::Result<()> {
+        let origin = ExitOrigin::from_location(Location::caller());
+        memory::watch(Arc::downgrade(&self.shared), origin, watermark)
+    }
+
+    /// Signal exit with ExitReason::ParentExited once the parent process dies, so a worker
+    /// spawned by a supervisor never outlives it.  A "chex-parent-watch" thread notices the
+    /// process being reparented within one poll interval of options.  On Linux, options can also
+    /// have the kernel signal this process on parent death (PR_SET_PDEATHSIG), which the thread
+    /// notices within the same interval, with the same reason.
+    #[cfg(unix)]
+    #[track_caller]
+    pub fn exit_on_parent_death(&self, options: parent::ParentDeathOptions) {
+        let origin = ExitOrigin::from_location(Location::caller());
+        parent::watch(Arc::downgrade(&self.shared), origin, &options);
+    }
+
+    /// Tie this coordinator to systemd as configured by options: send STOPPING=1 once exit is
+    /// signalled, keep the service watchdog fed until teardown has finished, and signal exit
+    /// with ExitReason::OsSignal(15) on SIGTERM.  Outside systemd (NOTIFY_SOCKET unset) the
+    /// notifications are skipped, but SIGTERM is still handled.
+    #[cfg(all(feature = "systemd", target_os = "linux"))]
+    #[track_caller]
+    pub fn enable_systemd(&self, options: systemd::SystemdOptions) {
+        systemd::install(&self.shared, &options, ExitOrigin::from_location(Location::caller()));
+    }
+
+    /// Register an interceptor which may hold off the exit signal for up to max_delay, ie to
+    /// complete an in-flight two-phase commit.
+    ///
+    /// When exit is first signalled, the signalling thread waits (after the at_exit hooks)
+    /// until every interceptor has released or its max_delay has elapsed, and only then raises
+    /// the exit flag for everyone else.  Threads signalling exit meanwhile wait for it as well.
+    /// Poll Interceptor::exit_pending() to find out when to wrap up.
+    pub fn intercept(&self, name: &str, max_delay: std::time::Duration) -> intercept::Interceptor {
+        self.shared.interceptors.register(name, max_delay)
+    }
+
+    /// Acquire a permit for one unit of work, or None once shutdown has begun (exit signalled,
+    /// or held off by an interceptor).  Drop the permit when the work is done;
+    /// wait_for_work_permits() waits for every outstanding permit.
+    pub fn acquire_work_permit(&self) -> Option<quiesce::WorkPermit> {
+        if self.poll_exit() || self.shared.interceptors.pending() {
+            return None;
+        }
+        Some(self.shared.permits.acquire())
+    }
+
+    /// Number of work permits not dropped yet.
+    pub fn outstanding_work_permits(&self) -> usize {
+        self.shared.permits.outstanding()
+    }
+
+    /// Park the current thread until every work permit has been dropped, or until timeout.
+    /// Returns true iff no permits are outstanding.
+    pub fn wait_for_work_permits(&self, timeout: std::time::Duration) -> bool {
+        self.shared.permits.wait(timeout)
+    }
+
+    /// Returns a crossbeam receiver which becomes ready once exit is signalled, to use as one
+    /// arm of a crossbeam select!: `recv(ci.crossbeam_receiver()) -> _ => break`.  Nothing is
+    /// ever sent on it; exit disconnects it, so every receive then returns Err(RecvError).
+    #[cfg(feature = "crossbeam")]
+    pub fn crossbeam_receiver(&self) -> crossbeam_channel::Receiver<()> {
+        self.shared.exit_channel.receiver().clone()
+    }
+
+    /// Unpark thread when exit is signalled, so a thread waiting in std::thread::park() (or a
+    /// park()-based primitive) wakes up promptly to check poll_exit().  The thread stays
+    /// registered until the returned guard is dropped.  If exit has already been signalled,
+    /// thread is unparked right away.
+    pub fn unpark_on_exit(&self, thread: std::thread::Thread) -> interrupt::InterruptGuard {
+        self.interrupt_on_exit(thread)
+    }
+
+    /// Interrupt target when exit is signalled, ie deposit a try_clone() of a TcpStream or
+    /// UnixStream so a thread stuck in a blocking read() on it returns once the stream is shut
+    /// down.  The target stays registered until the returned guard is dropped.  If exit has
+    /// already been signalled, target is interrupted right away.
+    pub fn interrupt_on_exit(&self, target: impl interrupt::Interrupt + 'static) -> interrupt::InterruptGuard {
+        let target: Arc<dyn interrupt::Interrupt> = Arc::new(target);
+        let guard = self.shared.interrupts.register(Box::new(target.clone()));
+        if self.shared.poll_exit() {
+            target.interrupt();
+        }
+        guard
+    }
+
+    /// Run callback exactly once when exit is signalled, on the "chex-teardown" thread, ie to
+    /// remove a pid file or flip a health flag without a dedicated listener thread.
+    ///
+    /// Same as on_teardown() at priority 0.
+    ///
+    /// A panic under PanicHookMode::Minimal raises the exit flag without running any hooks: they
+    /// run on the next signal_exit() (or other signalling) call instead.
+    pub fn on_exit<F>(&self, callback: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.on_teardown(0, callback);
+    }
+
+    /// Run the future returned by factory once exit is signalled, ie to tell a load balancer
+    /// the process is draining.  The future runs on the "chex-teardown" thread in order with
+    /// the synchronous hooks (at priority 0), on the runtime selected by executor.
+    ///
+    /// Panics with AsyncExecutor::Current if called outside a tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub fn on_exit_async<F, Fut>(&self, executor: teardown::AsyncExecutor, factory: F)
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = ()> + 'static,
+    {
+        self.shared.teardown.add(0, executor.into_hook(factory));
+    }
+
+    /// Hand runtime over to chex, and get back a Handle to spawn onto it.  Once exit is
+    /// signalled and grace has elapsed (giving its tasks time to observe exit and finish), the
+    /// runtime is shut down with shutdown_timeout(timeout).
+    ///
+    /// Shutdown runs as the last teardown hook, so wait_for_teardown() covers it.  Adopted
+    /// runtimes are shut down one after another, each grace counted from the exit signal.
+    #[cfg(feature = "tokio")]
+    pub fn adopt_runtime(
+        &self,
+        runtime: tokio::runtime::Runtime,
+        grace: std::time::Duration,
+        timeout: std::time::Duration,
+    ) -> tokio::runtime::Handle {
+        let handle = runtime.handle().clone();
+        let shared = Arc::downgrade(&self.shared);
+        let clock = self.shared.config.timer_clock();
+        self.on_teardown(i32::MAX, move || {
+            let elapsed = shared.upgrade()
+                .and_then(|s| s.exit_elapsed())
+                .unwrap_or_default();
+            clock.sleep(grace.saturating_sub(elapsed));
+            runtime.shutdown_timeout(timeout);
+        });
+        handle
+    }
+
+    /// Adopt a thread, to be joined by join_all() during teardown.
+    pub fn adopt<R: Send + 'static>(&self, handle: std::thread::JoinHandle<R>) {
+        self.shared.joins.adopt_thread(handle);
+    }
+
+    /// Adopt a tokio task, to be joined by join_all() during teardown.  Note the task only
+    /// finishes if its runtime is still being driven.
+    #[cfg(feature = "tokio")]
+    pub fn adopt_task<R: Send + 'static>(&self, handle: tokio::task::JoinHandle<R>) {
+        self.shared.joins.adopt_task(handle);
+    }
+
+    /// Terminate child once exit is signalled: SIGTERM, then SIGKILL if it is still running
+    /// after the grace period of options (on unix; elsewhere it is killed right away).  All
+    /// adopted children are terminated together, by a teardown hook at priority 0, so
+    /// wait_for_teardown() waits until they have exited.  A child adopted after exit is
+    /// terminated right away.
+    #[cfg(feature = "process")]
+    pub fn adopt_child(&self, child: std::process::Child, options: process::ChildOptions) {
+        self.shared.children.adopt_std(child, options);
+        self.hook_children();
+    }
+
+    /// Like adopt_child(), for a tokio::process::Child.
+    #[cfg(all(feature = "process", feature = "tokio"))]
+    pub fn adopt_tokio_child(&self, child: tokio::process::Child, options: process::ChildOptions) {
+        self.shared.children.adopt_tokio(child, options);
+        self.hook_children();
+    }
+
+    #[cfg(feature = "process")]
+    fn hook_children(&self) {
+        /*
+         * Once teardown has started, a newly registered hook runs right away on its own thread.
+         */
+        if self.shared.children.mark_hooked() || self.shared.poll_exit() {
+            let children = self.shared.children.clone();
+            let config = self.shared.config.clone();
+            self.on_teardown(0, move || children.terminate_all(&config));
+        }
+    }
+
+    /// Park the current thread until every adopted thread and task has finished, or until
+    /// timeout.  Finished handles are released; those still running stay adopted.
+    pub fn join_all(&self, timeout: std::time::Duration) -> join::JoinReport {
+        self.shared.joins.join_all(timeout)
+    }
+
+    /// Park the current thread until exit has been signalled and every teardown hook has run,
+    /// or until timeout.  Returns true iff teardown completed.
+    pub fn wait_for_teardown(&self, timeout: std::time::Duration) -> bool {
+        self.shared.teardown.wait(timeout)
+    }
+
+    /// Register a subsystem whose shutdown should be accounted for by wait_for_subsystems().
+    pub fn register_subsystem(&self, name: &str) -> SubsystemHandle {
+        SubsystemHandle::new(self.register(name))
+    }
+
+    /// Park the current thread until every registered subsystem (and listener) has called done()
+    /// or been dropped.  Fails with the subsystems still running if timeout elapses first.
+    pub fn wait_for_subsystems(&self, timeout: std::time::Duration) -> Result<(), Vec<ListenerInfo>> {
+        self.shared.wait_for_subsystems(timeout)
+    }
+
+    /// A handle which can only signal exit (or start draining) and poll for it, for components
+    /// which never wait for exit.  Unlike a clone, it is not counted as a listener.
+    pub fn signaler(&self) -> ChexSignaler {
+        ChexSignaler { shared: self.shared.clone() }
+    }
+
+    /// Run trigger on a "chex-trigger" thread, handing it a ChexSignaler for this coordinator.
+    /// The trigger can also be a closure taking the ChexSignaler.
+    pub fn install_trigger(&self, trigger: impl trigger::ExitTrigger) {
+        trigger::install(trigger, self.signaler());
+    }
+
+    /// Register a heartbeat watchdog.  See Chex::watchdog().
+    ///
+    /// When a watchdog misses its heartbeat, the exit origin is the location where it was
+    /// registered.
+    #[track_caller]
+    pub fn watchdog(&self, name: &str, interval: std::time::Duration) -> Watchdog {
+        watchdog::WatchdogRegistry::register(&self.shared, name, interval, Location::caller())
+    }
+
+    /// Returns once exit has been signalled, and only then.
+    ///
+    /// Only holds a slot among the coordinator's waiters while the future is pending, so
+    /// instances which never wait cost no more than an Arc.
+    pub async fn check_exit_async(&mut self) {
+        if self.shared.poll_exit() {
+            return;
+        }
+
+        self.shared.notify.wait(&self.shared.exit).await;
+        self.shared.record_wakeup(latency::WakeupKind::Async);
+    }
+
+    /// Like check_exit_async(), but only returns if exit was signalled for one of reasons, ie
+    /// for a component which should ignore watchdog exits.  Otherwise never returns, so use it
+    /// within a tokio::select!() alongside the component's own work.
+    pub async fn check_exit_async_for(&mut self, reasons: &[ExitReasonKind]) {
+        self.check_exit_async().await;
+        if !self.poll_exit_for(reasons) {
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Clone + Send + 'static> ChexInstance<T> {
+    /// Signal exit like signal_exit(), attaching payload for listeners to retrieve with
+    /// exit_payload() or recv_exit_async().
+    ///
+    /// Only the first payload is kept: once exit has been signalled with a payload, later
+    /// payloads are dropped.  Returns true iff this call was the first to signal exit.
+    #[track_caller]
+    pub fn signal_exit_with(&self, payload: T) -> bool {
+        self.shared.signal_exit_carrying(
+            ExitReason::Manual,
+            ExitOrigin::from_location(Location::caller()),
+            ExitAttachments::payload(Box::new(Mutex::new(payload))),
+        )
+    }
+
+    /// The payload exit was signalled with, if any.  None before exit, and after an exit
+    /// signalled without a payload (ie by signal_exit() or a panic).
+    pub fn exit_payload(&self) -> Option<T> {
+        let payload = self.shared.payload.get()?.downcast_ref::<Mutex<T>>()?;
+        Some(payload.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    }
+
+    /// Wait until exit is signalled, like check_exit_async(), and return its payload.
+    pub async fn recv_exit_async(&mut self) -> Option<T> {
+        self.check_exit_async().await;
+        self.exit_payload()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> Clone for ChexInstance<T> {
+    fn clone(&self) -> Self {
+        telemetry::instance_created();
+        self.shared.notify.instance_created();
+        Self {
+            shared: self.shared.clone(),
+            observed: AtomicBool::new(false),
+            payload: std::marker::PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> Drop for ChexInstance<T> {
+    fn drop(&mut self) {
+        telemetry::instance_dropped();
+        self.shared.notify.instance_dropped();
+    }
+}
+
+#[cfg(feature = "std")]
+impl ChexSignaler {
+    /// Signal exit with ExitReason::Manual.  Returns true iff this call was the first to signal
+    /// exit.  See ChexInstance::signal_exit().
+    #[track_caller]
+    pub fn signal_exit(&self) -> bool {
+        self.shared.signal_exit_from(ExitReason::Manual, ExitOrigin::from_location(Location::caller()))
+    }
+
+    /// Signal exit with ExitReason::Resource { message }, ie for a resource which ran out.
+    /// Returns true iff this call was the first to signal exit.
+    #[track_caller]
+    pub fn signal_exit_for_resource(&self, message: impl Into<String>) -> bool {
+        let reason = ExitReason::Resource { message: message.into() };
+        self.shared.signal_exit_from(reason, ExitOrigin::from_location(Location::caller()))
+    }
+
+    /// Begin draining without signalling exit.  See ChexInstance::start_draining().
+    pub fn start_draining(&self) -> bool {
+        !self.shared.start_draining()
+    }
+
+    pub fn poll_exit(&self) -> bool {
+        self.shared.poll_exit()
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.shared.is_draining()
+    }
+}
 
-        let _ = self.chr_bcast.recv().await;
+#[cfg(feature = "std")]
+impl std::fmt::Debug for ChexSignaler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChexSignaler")
+            .field("exit", &self.poll_exit())
+            .finish()
     }
 }
@@ -0,0 +1,236 @@
+//! Named listener registration, for tracking which components have finished their teardown.
+
+use crate::{ExitOrigin,ExitReason};
+use crate::clock::Clock;
+use crate::sink::{self,ChexEvent};
+use crate::telemetry::{self,ShutdownSpan};
+use std::sync::{Arc,Mutex,OnceLock,RwLock};
+use std::thread::ThreadId;
+use crate::Instant;
+use std::time::Duration;
+
+type StragglerCallback = Box<dyn Fn(&[ListenerInfo]) + Sync + Send + 'static>;
+
+/*
+ * Listeners registered with one ChexShared.
+ */
+pub(crate) struct ListenerRegistry {
+    /* The coordinator's clock, for timestamps and the straggler timeout. */
+    clock: Arc<dyn Clock>,
+    entries: Mutex<Vec<Arc<ListenerEntry>>>,
+    straggler_timeout: Mutex<Option<Duration>>,
+    straggler_callback: RwLock<Option<StragglerCallback>>,
+    exited_at: OnceLock<Instant>,
+    span: ShutdownSpan,
+}
+
+struct ListenerEntry {
+    name: String,
+    thread_name: Option<String>,
+    thread_id: ThreadId,
+    registered_at: Instant,
+    acked_at: OnceLock<Instant>,
+}
+
+/*
+ * Handle returned by ChexInstance::register().  Call ack() (or drop the guard) once the
+ * listener has finished its teardown.
+ */
+pub struct ListenerGuard {
+    entry: Arc<ListenerEntry>,
+    registry: Arc<ListenerRegistry>,
+}
+
+/*
+ * Handle returned by register_subsystem().  Call done() (or drop the handle) once the subsystem
+ * has shut down.  Subsystems are listeners, so they also show up in listeners(), stragglers()
+ * and ExitSummary.
+ */
+pub struct SubsystemHandle {
+    guard: ListenerGuard,
+}
+
+/*
+ * Snapshot of a registered listener.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListenerInfo {
+    name: String,
+    thread_name: Option<String>,
+    thread_id: ThreadId,
+    registered_at: Instant,
+    acked_at: Option<Instant>,
+}
+
+impl ListenerInfo {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Name of the registering thread, if it was named.
+    pub fn thread_name(&self) -> Option<&str> {
+        self.thread_name.as_deref()
+    }
+
+    pub fn thread_id(&self) -> ThreadId {
+        self.thread_id
+    }
+
+    pub fn registered_at(&self) -> Instant {
+        self.registered_at
+    }
+
+    /// When the listener acked (or dropped its guard), if it has.
+    pub fn acked_at(&self) -> Option<Instant> {
+        self.acked_at
+    }
+}
+
+impl ListenerGuard {
+    /// Report that this listener has finished its teardown.
+    pub fn ack(self) {}
+
+    pub fn name(&self) -> &str {
+        &self.entry.name
+    }
+}
+
+impl SubsystemHandle {
+    pub(crate) fn new(guard: ListenerGuard) -> Self {
+        Self { guard }
+    }
+
+    /// Report that this subsystem has shut down.
+    pub fn done(self) {}
+
+    pub fn name(&self) -> &str {
+        self.guard.name()
+    }
+}
+
+impl Drop for ListenerGuard {
+    fn drop(&mut self) {
+        let _ = self.entry.acked_at.set(self.registry.clock.now());
+        self.registry.acked(&self.entry);
+    }
+}
+
+impl ListenerEntry {
+    fn info(&self) -> ListenerInfo {
+        ListenerInfo {
+            name: self.name.clone(),
+            thread_name: self.thread_name.clone(),
+            thread_id: self.thread_id,
+            registered_at: self.registered_at,
+            acked_at: self.acked_at.get().copied(),
+        }
+    }
+}
+
+impl ListenerRegistry {
+    pub(crate) fn new(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            clock,
+            entries: Mutex::new(Vec::new()),
+            straggler_timeout: Mutex::new(None),
+            straggler_callback: RwLock::new(None),
+            exited_at: OnceLock::new(),
+            span: ShutdownSpan::new(),
+        }
+    }
+
+    /// Register a listener on the current thread.
+    pub(crate) fn register(self: &Arc<Self>, name: &str) -> ListenerGuard {
+        let thread = std::thread::current();
+        let entry = Arc::new(ListenerEntry {
+            name: name.to_string(),
+            thread_name: thread.name().map(str::to_string),
+            thread_id: thread.id(),
+            registered_at: self.clock.now(),
+            acked_at: OnceLock::new(),
+        });
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).push(entry.clone());
+
+        ListenerGuard {
+            entry,
+            registry: self.clone(),
+        }
+    }
+
+    /// Snapshot of every listener registered so far.
+    pub(crate) fn listeners(&self) -> Vec<ListenerInfo> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|e| e.info())
+            .collect()
+    }
+
+    /// Registered listeners that have not acked yet.
+    pub(crate) fn live(&self) -> Vec<ListenerInfo> {
+        self.listeners().into_iter()
+            .filter(|l| l.acked_at.is_none())
+            .collect()
+    }
+
+    pub(crate) fn set_straggler_timeout(&self, timeout: Option<Duration>) {
+        *self.straggler_timeout.lock().unwrap_or_else(|e| e.into_inner()) = timeout;
+    }
+
+    pub(crate) fn set_straggler_callback(&self, callback: StragglerCallback) {
+        *self.straggler_callback.write().unwrap_or_else(|e| e.into_inner()) = Some(callback);
+    }
+
+    fn acked(&self, entry: &ListenerEntry) {
+        let Some(exited_at) = self.exited_at.get() else {
+            return;
+        };
+
+        let latency = entry.acked_at.get().map(|t| t.saturating_duration_since(*exited_at));
+        self.span.listener_acked(&entry.name, latency.unwrap_or_default());
+        if self.live().is_empty() {
+            self.span.finish();
+        }
+    }
+
+    /// Called once when exit is first signalled: open the shutdown span and start the
+    /// straggler check if configured.
+    pub(crate) fn on_exit(self: &Arc<Self>, reason: &ExitReason, origin: Option<&ExitOrigin>) {
+        let _ = self.exited_at.set(self.clock.now());
+        self.span.start(reason, origin);
+        if self.live().is_empty() {
+            self.span.finish();
+        }
+
+        let Some(timeout) = *self.straggler_timeout.lock().unwrap_or_else(|e| e.into_inner()) else {
+            return;
+        };
+
+        let registry = self.clone();
+        let spawned = std::thread::Builder::new()
+            .name("chex-stragglers".to_string())
+            .spawn(move || {
+                registry.clock.sleep(timeout);
+                registry.report_stragglers(timeout);
+            });
+        if let Err(e) = spawned {
+            sink::emit(ChexEvent::ThreadSpawnFailed { thread: "chex-stragglers", error: &e });
+        }
+    }
+
+    fn report_stragglers(&self, timeout: Duration) {
+        let stragglers = self.live();
+        if stragglers.is_empty() {
+            return;
+        }
+
+        match &*self.straggler_callback.read().unwrap_or_else(|e| e.into_inner()) {
+            Some(callback) => callback(&stragglers),
+            None => {
+                for l in &stragglers {
+                    telemetry::straggler(&l.name, l.thread_name.as_deref(), timeout);
+                    sink::emit(ChexEvent::Straggler { listener: l, timeout });
+                }
+            }
+        }
+    }
+}
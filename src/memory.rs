@@ -0,0 +1,228 @@
+//! Starting a soft exit (or signalling exit) once memory usage crosses a watermark, so a service
+//! can drain before the kernel OOM-kills it.
+//!
+//! Linux only.  Usage is either the process RSS from /proc/self/status, or the usage of the
+//! process's memory cgroup (v2, or v1 as a fallback), sampled by a "chex-memory" thread.
+
+use crate::{ChexShared,ExitOrigin,ExitReason};
+use crate::sink::{self,ChexEvent};
+use std::io::{Error,ErrorKind};
+use std::path::{Path,PathBuf};
+use std::sync::Weak;
+use std::time::Duration;
+
+/*
+ * Which memory usage a MemoryWatermark samples.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MemorySource {
+    /// Resident set size of this process.
+    Rss,
+    /// Usage of the memory cgroup this process belongs to, ie the container, which is what the
+    /// OOM killer compares against the cgroup limit.
+    Cgroup,
+}
+
+impl MemorySource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MemorySource::Rss => "rss",
+            MemorySource::Cgroup => "cgroup",
+        }
+    }
+}
+
+/*
+ * What happens once the watermark is crossed.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WatermarkAction {
+    /// Begin draining (see ChexInstance::start_draining()) without signalling exit.
+    #[default]
+    SoftExit,
+    /// Signal exit with ExitReason::Resource.
+    Exit,
+}
+
+/*
+ * The threshold for ChexInstance::exit_on_memory().
+ */
+#[derive(Clone, Debug)]
+pub struct MemoryWatermark {
+    source: MemorySource,
+    limit: Limit,
+    action: WatermarkAction,
+    poll_interval: Duration,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Limit {
+    Bytes(u64),
+    /// A fraction of the cgroup's own limit.
+    Fraction(f64),
+}
+
+impl MemoryWatermark {
+    fn new(source: MemorySource, limit: Limit) -> Self {
+        Self {
+            source,
+            limit,
+            action: WatermarkAction::default(),
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+
+    /// Trigger once the process RSS reaches bytes.
+    pub fn rss(bytes: u64) -> Self {
+        Self::new(MemorySource::Rss, Limit::Bytes(bytes))
+    }
+
+    /// Trigger once the memory cgroup's usage reaches bytes.
+    pub fn cgroup(bytes: u64) -> Self {
+        Self::new(MemorySource::Cgroup, Limit::Bytes(bytes))
+    }
+
+    /// Trigger once the memory cgroup's usage reaches fraction (ie 0.9) of its limit.
+    /// exit_on_memory() fails if the cgroup has no limit.
+    pub fn cgroup_fraction(fraction: f64) -> Self {
+        Self::new(MemorySource::Cgroup, Limit::Fraction(fraction))
+    }
+
+    /// What to do once the watermark is crossed.  Defaults to WatermarkAction::SoftExit.
+    pub fn action(mut self, action: WatermarkAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// How often to sample usage.  Defaults to 1 second.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+}
+
+/*
+ * Where the usage (and limit) of a MemorySource is read from.
+ */
+enum Sampler {
+    Rss,
+    Cgroup { usage: PathBuf, limit: PathBuf },
+}
+
+impl Sampler {
+    fn new(source: MemorySource) -> std::io::Result<Self> {
+        match source {
+            MemorySource::Rss => Ok(Sampler::Rss),
+            MemorySource::Cgroup => Self::cgroup(),
+        }
+    }
+
+    /// Find this process's memory cgroup: the unified (v2) hierarchy if it is mounted, else the
+    /// v1 memory controller.  Inside a container the hierarchy is often mounted at the cgroup
+    /// itself, so the root of the mount is tried after the path from /proc/self/cgroup.
+    fn cgroup() -> std::io::Result<Self> {
+        let cgroups = std::fs::read_to_string("/proc/self/cgroup")?;
+        for line in cgroups.lines() {
+            let mut fields = line.splitn(3, ':');
+            let (Some(_), Some(controllers), Some(path)) = (fields.next(), fields.next(), fields.next()) else {
+                continue;
+            };
+            let (root, usage, limit) = if controllers.is_empty() {
+                ("/sys/fs/cgroup", "memory.current", "memory.max")
+            } else if controllers.split(',').any(|c| c == "memory") {
+                ("/sys/fs/cgroup/memory", "memory.usage_in_bytes", "memory.limit_in_bytes")
+            } else {
+                continue;
+            };
+            let root = PathBuf::from(root);
+            for dir in [root.join(path.trim_start_matches('/')), root] {
+                if dir.join(usage).exists() {
+                    return Ok(Sampler::Cgroup { usage: dir.join(usage), limit: dir.join(limit) });
+                }
+            }
+        }
+        Err(Error::new(ErrorKind::NotFound, "no memory cgroup found for this process"))
+    }
+
+    fn usage(&self) -> std::io::Result<u64> {
+        match self {
+            Sampler::Rss => {
+                let status = std::fs::read_to_string("/proc/self/status")?;
+                let kb = status.lines()
+                    .find_map(|l| l.strip_prefix("VmRSS:"))
+                    .and_then(|v| v.trim().trim_end_matches("kB").trim().parse::<u64>().ok())
+                    .ok_or_else(|| Error::new(ErrorKind::InvalidData, "no VmRSS in /proc/self/status"))?;
+                Ok(kb.saturating_mul(1024))
+            }
+            Sampler::Cgroup { usage, .. } => read_bytes(usage)?
+                .ok_or_else(|| Error::new(ErrorKind::InvalidData, "unexpected cgroup memory usage")),
+        }
+    }
+
+    /// The cgroup's limit, or None if it has none.
+    fn limit(&self) -> std::io::Result<Option<u64>> {
+        match self {
+            Sampler::Rss => Ok(None),
+            Sampler::Cgroup { limit, .. } => read_bytes(limit),
+        }
+    }
+}
+
+/// Read a cgroup byte count, where "max" (or v1's page-rounded i64::MAX) means unlimited.
+fn read_bytes(path: &Path) -> std::io::Result<Option<u64>> {
+    let text = std::fs::read_to_string(path)?;
+    let text = text.trim();
+    if text == "max" {
+        return Ok(None);
+    }
+    let bytes = text.parse::<u64>().map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    Ok((bytes < 1 << 62).then_some(bytes))
+}
+
+pub(crate) fn watch(shared: Weak<ChexShared>, origin: ExitOrigin, watermark: MemoryWatermark) -> std::io::Result<()> {
+    let sampler = Sampler::new(watermark.source)?;
+    let limit = match watermark.limit {
+        Limit::Bytes(bytes) => bytes,
+        Limit::Fraction(fraction) => {
+            let Some(max) = sampler.limit()? else {
+                return Err(Error::new(ErrorKind::NotFound, "memory cgroup has no limit"));
+            };
+            (max as f64 * fraction) as u64
+        }
+    };
+    sampler.usage()?;
+
+    let spawned = std::thread::Builder::new()
+        .name("chex-memory".to_string())
+        .spawn(move || {
+            loop {
+                let Some(shared) = shared.upgrade() else { return };
+                if shared.poll_exit() {
+                    return;
+                }
+                if let Ok(usage) = sampler.usage() {
+                    if usage >= limit {
+                        match watermark.action {
+                            WatermarkAction::SoftExit => {
+                                shared.start_draining();
+                            }
+                            WatermarkAction::Exit => {
+                                let message = format!("{} usage of {usage} bytes reached the watermark of {limit} bytes",
+                                    watermark.source.as_str());
+                                shared.signal_exit_from(ExitReason::Resource { message }, origin);
+                            }
+                        }
+                        return;
+                    }
+                }
+                drop(shared);
+                std::thread::sleep(watermark.poll_interval);
+            }
+        });
+    if let Err(e) = spawned {
+        sink::emit(ChexEvent::ThreadSpawnFailed { thread: "chex-memory", error: &e });
+    }
+    Ok(())
+}
@@ -0,0 +1,135 @@
+//! Waking async listeners once exit is signalled.
+//!
+//! Replaces a broadcast channel, which gave every ChexInstance its own receiver whether or not
+//! it ever waited.  Here an instance costs one counter increment, and only a task which is
+//! actually waiting holds a slot, so tens of thousands of idle instances are cheap and a wakeup
+//! is one pass over the tasks which are waiting.
+
+use crate::sync::{AtomicBool,AtomicUsize,Mutex};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::Ordering::{Acquire,Relaxed};
+use std::task::{Context,Poll,Waker};
+
+/*
+ * The waiting tasks of one ChexShared, and the number of live ChexInstances.
+ */
+pub(crate) struct ExitNotify {
+    waiters: Mutex<Waiters>,
+    instances: AtomicUsize,
+}
+
+/*
+ * Wakers by slot.  Slots are reused, so waiting in a select!() loop doesn't grow this.
+ */
+#[derive(Default)]
+struct Waiters {
+    slots: Vec<Option<Waker>>,
+    free: Vec<usize>,
+}
+
+impl ExitNotify {
+    pub(crate) fn new() -> Self {
+        Self {
+            waiters: Mutex::new(Waiters::default()),
+            instances: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn instance_created(&self) {
+        self.instances.fetch_add(1, Relaxed);
+    }
+
+    pub(crate) fn instance_dropped(&self) {
+        self.instances.fetch_sub(1, Relaxed);
+    }
+
+    /// Number of live ChexInstances.
+    pub(crate) fn instances(&self) -> usize {
+        self.instances.load(Relaxed)
+    }
+
+    /// Number of tasks currently waiting for exit.
+    pub(crate) fn waiters(&self) -> usize {
+        let waiters = self.waiters.lock().unwrap_or_else(|e| e.into_inner());
+        waiters.slots.len() - waiters.free.len()
+    }
+
+    /// Wake every waiting task.  Must be called after the exit flag is raised: a waiter checks
+    /// the flag under the same lock before registering, so it either sees the flag or is woken.
+    pub(crate) fn notify_all(&self) {
+        let slots = {
+            let mut waiters = self.waiters.lock().unwrap_or_else(|e| e.into_inner());
+            waiters.free.clear();
+            std::mem::take(&mut waiters.slots)
+        };
+        for waker in slots.into_iter().flatten() {
+            waker.wake();
+        }
+    }
+
+    /// A future which resolves once exit is raised.
+    pub(crate) fn wait<'a>(&'a self, exit: &'a AtomicBool) -> ExitWait<'a> {
+        ExitWait { notify: self, exit, slot: None }
+    }
+}
+
+/*
+ * Future returned by ExitNotify::wait().  Releases its slot when dropped.
+ */
+pub(crate) struct ExitWait<'a> {
+    notify: &'a ExitNotify,
+    exit: &'a AtomicBool,
+    slot: Option<usize>,
+}
+
+impl Future for ExitWait<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.exit.load(Acquire) {
+            return Poll::Ready(());
+        }
+
+        let mut waiters = self.notify.waiters.lock().unwrap_or_else(|e| e.into_inner());
+        if self.exit.load(Acquire) {
+            return Poll::Ready(());
+        }
+        match self.slot {
+            Some(slot) => match &mut waiters.slots[slot] {
+                Some(waker) if waker.will_wake(cx.waker()) => {}
+                waker => *waker = Some(cx.waker().clone()),
+            },
+            None => {
+                let waker = Some(cx.waker().clone());
+                let slot = match waiters.free.pop() {
+                    Some(slot) => {
+                        waiters.slots[slot] = waker;
+                        slot
+                    }
+                    None => {
+                        waiters.slots.push(waker);
+                        waiters.slots.len() - 1
+                    }
+                };
+                drop(waiters);
+                self.slot = Some(slot);
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for ExitWait<'_> {
+    fn drop(&mut self) {
+        let Some(slot) = self.slot else { return };
+        let mut waiters = self.notify.waiters.lock().unwrap_or_else(|e| e.into_inner());
+        /*
+         * notify_all() already emptied the slots if exit was signalled.
+         */
+        if let Some(waker) = waiters.slots.get_mut(slot) {
+            *waker = None;
+            waiters.free.push(slot);
+        }
+    }
+}
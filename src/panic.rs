@@ -0,0 +1,578 @@
+//! Panic capture for exit-on-panic.
+
+use crate::{Chex,ChexInstance,ChexPanicHandler,ChexShared,ExitOrigin,ExitReason,ExitReasonKind};
+use crate::sink::{self,ChexEvent};
+use crate::telemetry;
+use std::backtrace::Backtrace;
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::collections::VecDeque;
+use std::panic::{Location,PanicHookInfo};
+use std::sync::{Arc,Mutex,Once,RwLock,Weak};
+use std::sync::atomic::{AtomicBool,AtomicU8,AtomicUsize};
+use std::sync::atomic::Ordering::Relaxed;
+use crate::Instant;
+use std::time::Duration;
+
+thread_local! {
+    static SUPPRESS_DEPTH: Cell<usize> = const { Cell::new(0) };
+    static IN_HOOK: Cell<bool> = const { Cell::new(false) };
+}
+
+type PanicFilter = Box<dyn Fn(&PanicHookInfo<'_>) -> bool + Sync + Send + 'static>;
+
+/*
+ * Decides which panics signal exit.
+ */
+pub(crate) struct PanicPolicy {
+    filter: RwLock<Option<PanicFilter>>,
+    ignored_threads: RwLock<Vec<String>>,
+    threshold: Mutex<PanicThreshold>,
+    mode: AtomicU8,
+    grace: Mutex<Option<Duration>>,
+}
+
+/*
+ * How much work the exit-on-panic hook does while panicking.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum PanicHookMode {
+    /// Apply filters and thresholds, log the panic, capture a PanicReport and origin, run
+    /// chained hooks and then the default panic handler.
+    Verbose,
+    /// Only raise the exit flag, wake async listeners and write a fixed message to stderr,
+    /// without allocating.  suppress_exit() is still honored, but filters, thresholds, chained
+    /// hooks and the default panic handler are skipped, and no PanicReport or origin is recorded.
+    /// Exit hooks (at_exit(), on_exit(), teardown, links) wait for the next signal_exit() call,
+    /// which records its own origin.
+    ///
+    /// For programs where panics may be caused by allocation failure or happen in otherwise
+    /// constrained contexts.
+    Minimal,
+    /// Like Verbose, but without logging and without calling the default panic handler: the
+    /// exit flag, PanicReport and origin are recorded and chained hooks still run, so the panic
+    /// is reported only by whatever the program chains with Chex::add_panic_hook().
+    Quiet,
+}
+
+/*
+ * Hooks chained after the exit-on-panic hook, and the process panic hook chex installs once.
+ * Enabling and disabling exit-on-panic only flips enabled, so the process hook is never swapped
+ * out while other threads may be panicking.
+ */
+pub(crate) struct PanicHooks {
+    chained: RwLock<Vec<ChexPanicHandler>>,
+    once: Once,
+    enabled: AtomicBool,
+    installed: AtomicUsize,
+    replaced: AtomicBool,
+}
+
+/*
+ * Captured by the process hook, so that std::panic::set_hook() (or take_hook()) dropping the
+ * hook tells lookups of the global Chex to reinstall it.  A foreign hook which keeps chex's hook
+ * and chains to it never drops it, and needs nothing reinstalled.
+ */
+struct ReplacedOnDrop(&'static AtomicBool);
+
+impl Drop for ReplacedOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Relaxed);
+    }
+}
+
+/*
+ * Number of panics (optionally within a sliding window) required before signalling exit.
+ */
+struct PanicThreshold {
+    count: usize,
+    window: Option<Duration>,
+    seen: VecDeque<Instant>,
+}
+
+/*
+ * Guard returned by suppress_exit().  Not Send: it only affects the thread that created it.
+ */
+pub struct SuppressExitGuard {
+    _not_send: PhantomData<*const ()>,
+}
+
+/*
+ * Details of the first panic caught by the exit-on-panic hook.
+ */
+#[derive(Debug)]
+pub struct PanicReport {
+    message: String,
+    payload_type: &'static str,
+    location: Option<String>,
+    thread_name: Option<String>,
+    backtrace: Backtrace,
+}
+
+impl PanicPolicy {
+    pub(crate) const fn new() -> Self {
+        Self {
+            filter: RwLock::new(None),
+            ignored_threads: RwLock::new(Vec::new()),
+            threshold: Mutex::new(PanicThreshold {
+                count: 1,
+                window: None,
+                seen: VecDeque::new(),
+            }),
+            mode: AtomicU8::new(PanicHookMode::Verbose as u8),
+            grace: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn set_grace(&self, grace: Option<Duration>) {
+        *self.grace.lock().unwrap_or_else(|e| e.into_inner()) = grace;
+    }
+
+    fn grace(&self) -> Option<Duration> {
+        *self.grace.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn set_mode(&self, mode: PanicHookMode) {
+        self.mode.store(mode as u8, Relaxed);
+    }
+
+    fn mode(&self) -> PanicHookMode {
+        match self.mode.load(Relaxed) {
+            m if m == PanicHookMode::Minimal as u8 => PanicHookMode::Minimal,
+            m if m == PanicHookMode::Quiet as u8 => PanicHookMode::Quiet,
+            _ => PanicHookMode::Verbose,
+        }
+    }
+
+    pub(crate) fn set_threshold(&self, count: usize, window: Option<Duration>) {
+        let mut threshold = self.threshold.lock().unwrap_or_else(|e| e.into_inner());
+        threshold.count = count.max(1);
+        threshold.window = window;
+        threshold.seen.clear();
+    }
+
+    /// Forget the panics counted so far, ie in a forked child or between tests.
+    #[cfg(any(all(feature = "fork", unix), feature = "test-util"))]
+    pub(crate) fn reset_threshold(&self) {
+        self.threshold.lock().unwrap_or_else(|e| e.into_inner()).seen.clear();
+    }
+
+    /// Count this panic, seen at now, returning true iff the threshold has now been reached.
+    fn threshold_reached(&self, now: Instant) -> bool {
+        let mut threshold = self.threshold.lock().unwrap_or_else(|e| e.into_inner());
+        if threshold.count <= 1 {
+            return true;
+        }
+
+        if let Some(window) = threshold.window {
+            while threshold.seen.front().is_some_and(|t| now.duration_since(*t) > window) {
+                threshold.seen.pop_front();
+            }
+        }
+        threshold.seen.push_back(now);
+
+        let (seen, count) = (threshold.seen.len(), threshold.count);
+        if seen < count {
+            drop(threshold);
+            if self.mode() != PanicHookMode::Quiet {
+                sink::emit(ChexEvent::PanicBelowThreshold { seen, threshold: count });
+            }
+            return false;
+        }
+        true
+    }
+
+    pub(crate) fn set_filter(&self, filter: PanicFilter) {
+        *self.filter.write().unwrap_or_else(|e| e.into_inner()) = Some(filter);
+    }
+
+    pub(crate) fn clear_filter(&self) {
+        *self.filter.write().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    pub(crate) fn ignore_thread(&self, thread_name: &str) {
+        self.ignored_threads.write().unwrap_or_else(|e| e.into_inner()).push(thread_name.to_string());
+    }
+
+    /// Returns true iff this panic should signal exit.
+    fn should_exit(&self, info: &PanicHookInfo<'_>, now: Instant) -> bool {
+        if SUPPRESS_DEPTH.with(|d| d.get()) > 0 {
+            return false;
+        }
+
+        if let Some(name) = std::thread::current().name() {
+            let ignored = self.ignored_threads.read().unwrap_or_else(|e| e.into_inner());
+            if ignored.iter().any(|t| t == name) {
+                return false;
+            }
+        }
+
+        let filtered = match &*self.filter.read().unwrap_or_else(|e| e.into_inner()) {
+            Some(filter) => !filter(info),
+            None => false,
+        };
+
+        !filtered && self.threshold_reached(now)
+    }
+}
+
+impl PanicHooks {
+    pub(crate) const fn new() -> Self {
+        Self {
+            chained: RwLock::new(Vec::new()),
+            once: Once::new(),
+            enabled: AtomicBool::new(false),
+            installed: AtomicUsize::new(0),
+            replaced: AtomicBool::new(false),
+        }
+    }
+
+    pub(crate) fn add(&self, hook: ChexPanicHandler) {
+        self.chained.write().unwrap_or_else(|e| e.into_inner()).push(hook);
+    }
+
+    /// Install chex's process panic hook, keeping the hook it replaces as the default panic
+    /// handler.  Only the first call does anything.
+    pub(crate) fn install_once(&self, chex: &'static Chex) {
+        self.once.call_once(|| {
+            let _ = chex.default_panic_handler.set(std::panic::take_hook());
+            self.set_process_hook(chex);
+        });
+    }
+
+    fn set_process_hook(&self, chex: &'static Chex) {
+        /*
+         * The hook captures a reference so that its Box has a unique address, which is how
+         * ensure() recognizes it later.
+         */
+        let dropped = ReplacedOnDrop(&chex.panic_hooks.replaced);
+        let hook: ChexPanicHandler = Box::new(move |info| {
+            let _ = &dropped;
+            process_hook(chex, info)
+        });
+        self.installed.store(hook_addr(&hook), Relaxed);
+        self.replaced.store(false, Relaxed);
+        std::panic::set_hook(hook);
+    }
+
+    /// Signal exit on panic from now on.
+    pub(crate) fn enable(&self, chex: &'static Chex) {
+        self.install_once(chex);
+        self.enabled.store(true, Relaxed);
+    }
+
+    /// Stop signalling exit on panic: the process hook only runs the chained hooks and the
+    /// default panic handler.
+    pub(crate) fn disable(&self) {
+        self.enabled.store(false, Relaxed);
+    }
+
+    /// Reinstall the process hook if another hook replaced it, chaining the replacement.
+    ///
+    /// Returns true iff the hook had to be reinstalled.
+    pub(crate) fn ensure(&self, chex: &'static Chex) -> bool {
+        let installed = self.installed.load(Relaxed);
+        if installed == 0 || std::thread::panicking() {
+            return false;
+        }
+
+        let current = std::panic::take_hook();
+        if hook_addr(&current) == installed {
+            std::panic::set_hook(current);
+            return false;
+        }
+
+        self.add(current);
+        self.set_process_hook(chex);
+        true
+    }
+
+    /// ensure(), but only if the process hook was dropped since it was installed, which costs a
+    /// single load otherwise.  Called on lookups of the global Chex.
+    pub(crate) fn ensure_if_replaced(&self, chex: &'static Chex) {
+        if self.replaced.load(Relaxed) {
+            self.ensure(chex);
+        }
+    }
+
+    pub(crate) fn is_installed(&self) -> bool {
+        self.enabled.load(Relaxed)
+    }
+
+    pub(crate) fn chained_count(&self) -> usize {
+        self.chained.read().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    fn run_chained(&self, info: &PanicHookInfo<'_>) {
+        for hook in self.chained.read().unwrap_or_else(|e| e.into_inner()).iter() {
+            hook(info);
+        }
+    }
+}
+
+fn hook_addr(hook: &ChexPanicHandler) -> usize {
+    &**hook as *const _ as *const () as usize
+}
+
+/// Disable exit-on-panic for panics on the current thread while the returned guard is alive.
+///
+/// For code paths that deliberately panic and catch_unwind(), ie probing deserializers.  Guards
+/// may be nested.
+///
+/// ```
+/// let chex = chex::Chex::init(true);
+/// {
+///     let _guard = chex::panic::suppress_exit();
+///     let _ = std::panic::catch_unwind(|| panic!("expected"));
+/// }
+/// assert!(!chex.poll_exit());
+/// ```
+pub fn suppress_exit() -> SuppressExitGuard {
+    SUPPRESS_DEPTH.with(|d| d.set(d.get() + 1));
+    SuppressExitGuard {
+        _not_send: PhantomData,
+    }
+}
+
+impl Drop for SuppressExitGuard {
+    fn drop(&mut self) {
+        SUPPRESS_DEPTH.with(|d| d.set(d.get() - 1));
+    }
+}
+
+/// The process panic hook: exit-on-panic if enabled, else the chained hooks and the default
+/// panic handler.
+fn process_hook(chex: &'static Chex, info: &PanicHookInfo<'_>) {
+    /*
+     * A chained hook may itself call the hook it replaced (ie us), so ignore re-entry.
+     */
+    if IN_HOOK.with(|h| h.replace(true)) {
+        return;
+    }
+
+    if chex.panic_hooks.enabled.load(Relaxed) {
+        exit_on_panic_hook(chex, info);
+    } else {
+        chex.panic_hooks.run_chained(info);
+        if let Some(default_handler) = chex.default_panic_handler.get() {
+            default_handler(info);
+        }
+    }
+
+    IN_HOOK.with(|h| h.set(false));
+}
+
+/// Exit-on-panic, as enabled by Chex::set_exit_on_panic().
+fn exit_on_panic_hook(chex: &Chex, info: &PanicHookInfo<'_>) {
+    if chex.panic_policy.mode() == PanicHookMode::Minimal {
+        if SUPPRESS_DEPTH.with(|d| d.get()) == 0 {
+            if let Some(c) = chex.instance() {
+                c.shared.signal_exit_minimal();
+            }
+            write_stderr_raw(b"PANIC: signalled exit to all Chex listeners\n");
+        }
+        return;
+    }
+
+    let quiet = chex.panic_policy.mode() == PanicHookMode::Quiet;
+    /*
+     * The threshold window runs on the configured clock, like chex's timers.
+     */
+    let now = chex.instance().map_or_else(Instant::now, |c| c.shared.config.timer_clock().now());
+    let signalled = chex.panic_policy.should_exit(info, now);
+    if signalled {
+        if !quiet {
+            sink::emit(ChexEvent::PanicCaught { info });
+        }
+
+        let origin = match info.location() {
+            Some(loc) => ExitOrigin::from_location(loc),
+            None => ExitOrigin::from_location(Location::caller()),
+        };
+        if let Some(c) = chex.instance() {
+            let report = PanicReport::capture(info, c.shared.panic.get().is_none());
+            telemetry::panic_caught(report.message(), report.location());
+            let message = report.message().to_string();
+            c.shared.errors.record(ExitReasonKind::Panic, message.clone(), origin.clone());
+            let _ = c.shared.panic.set(report);
+            c.shared.signal_exit_from(ExitReason::Panic { message }, origin);
+        }
+    }
+
+    chex.panic_hooks.run_chained(info);
+
+    /*
+     * Invoke the default panic handler.
+     */
+    if !quiet {
+        let default_handler = chex.default_panic_handler.get()
+            .expect("PANIC (nested): Failed to initialize Chex before Panic encountered");
+        sink::emit(ChexEvent::DefaultPanicHandler);
+        default_handler(info);
+    }
+
+    if signalled {
+        if let (Some(grace), Some(c)) = (chex.panic_policy.grace(), chex.instance()) {
+            wait_for_listeners(c, grace);
+        }
+    }
+}
+
+/// Write msg to fd 2 with a raw write(2), which neither allocates nor takes the stderr lock the
+/// panicking thread may be holding.
+#[cfg(unix)]
+#[allow(unsafe_code)]
+fn write_stderr_raw(msg: &[u8]) {
+    // SAFETY: msg points to msg.len() initialized bytes which outlive the call, and write(2)
+    // doesn't keep the pointer.  If fd 2 is closed the call just fails.
+    let _ = unsafe { libc::write(2, msg.as_ptr().cast(), msg.len()) };
+}
+
+/// Without write(2), fall back to stderr, which takes its lock.
+#[cfg(not(unix))]
+fn write_stderr_raw(msg: &[u8]) {
+    let _ = std::io::Write::write_all(&mut std::io::stderr(), msg);
+}
+
+/// Block the panicking thread until every registered listener on another thread has acked (or,
+/// without registered listeners, every other ChexInstance has been dropped) or grace expires.
+///
+/// Under panic = "abort" the process aborts as soon as the hook returns, so this is the only
+/// window other threads get to clean up.
+fn wait_for_listeners(c: &ChexInstance, grace: Duration) {
+    /*
+     * wasm32-unknown-unknown has no other threads to wait for, and can't sleep.
+     */
+    if cfg!(all(target_arch = "wasm32", target_os = "unknown")) {
+        return;
+    }
+
+    let deadline = c.shared.config.now() + grace;
+    let this_thread = std::thread::current().id();
+
+    loop {
+        let listeners = c.shared.listeners.live();
+        let waiting = if listeners.is_empty() {
+            /*
+             * No registered listeners, so fall back to counting instances.  The global instance
+             * always holds one receiver.  We can't tell whether the panicking thread holds
+             * another, in which case we wait out the full grace period.
+             */
+            c.shared.receiver_count() > 1
+        } else {
+            listeners.iter().any(|l| l.thread_id() != this_thread)
+        };
+
+        if !waiting || c.shared.config.now() >= deadline {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/*
+ * Local coordinators with exit-on-panic, see bridge_local().  Dropped coordinators are pruned
+ * whenever another one is added.
+ */
+static LOCAL_BRIDGES: Mutex<Vec<Weak<ChexShared>>> = Mutex::new(Vec::new());
+static LOCAL_BRIDGE_HOOK: Once = Once::new();
+
+/// Have panics signal exit to a local coordinator.  The first call chains one hook in front of
+/// the current panic hook, which signals every live coordinator in LOCAL_BRIDGES; the registry
+/// holds only weak references, so panics just pass through once every instance of a coordinator
+/// has been dropped.
+pub(crate) fn bridge_local(shared: &Arc<ChexShared>) {
+    {
+        let mut bridges = LOCAL_BRIDGES.lock().unwrap_or_else(|e| e.into_inner());
+        bridges.retain(|b| b.strong_count() > 0);
+        bridges.push(Arc::downgrade(shared));
+    }
+    LOCAL_BRIDGE_HOOK.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if SUPPRESS_DEPTH.with(|d| d.get()) == 0 {
+                signal_local_bridges(info);
+            }
+            previous(info);
+        }));
+    });
+}
+
+fn signal_local_bridges(info: &PanicHookInfo<'_>) {
+    let bridges: Vec<Arc<ChexShared>> = LOCAL_BRIDGES.lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .filter_map(Weak::upgrade)
+        .collect();
+    for shared in bridges {
+        let origin = match info.location() {
+            Some(loc) => ExitOrigin::from_location(loc),
+            None => ExitOrigin::from_location(Location::caller()),
+        };
+        let report = PanicReport::capture(info, shared.panic.get().is_none());
+        let message = report.message().to_string();
+        shared.errors.record(ExitReasonKind::Panic, message.clone(), origin.clone());
+        if !shared.poll_exit() {
+            let _ = shared.panic.set(report);
+            shared.signal_exit_from(ExitReason::Panic { message }, origin);
+        }
+    }
+}
+
+impl PanicReport {
+    /// Capture the panic message, payload type and location from within the hook, and a
+    /// backtrace if with_backtrace, ie unless an earlier report was already kept.
+    pub(crate) fn capture(info: &PanicHookInfo<'_>, with_backtrace: bool) -> Self {
+        let payload = info.payload();
+        let (message, payload_type) = if let Some(s) = payload.downcast_ref::<&'static str>() {
+            (s.to_string(), "&str")
+        } else if let Some(s) = payload.downcast_ref::<String>() {
+            (s.clone(), "String")
+        } else {
+            ("Box<dyn Any>".to_string(), "Box<dyn Any>")
+        };
+
+        Self {
+            message,
+            payload_type,
+            location: info.location().map(|l| l.to_string()),
+            thread_name: std::thread::current().name().map(str::to_string),
+            backtrace: if with_backtrace { Backtrace::force_capture() } else { Backtrace::disabled() },
+        }
+    }
+
+    /// The panic message, if the payload was a string.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Type of the panic payload: `"&str"`, `"String"` or `"Box<dyn Any>"`.
+    pub fn payload_type(&self) -> &'static str {
+        self.payload_type
+    }
+
+    /// "file:line:column" of the panic, if known.
+    pub fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+
+    /// Name of the panicking thread, if it was named.
+    pub fn thread_name(&self) -> Option<&str> {
+        self.thread_name.as_deref()
+    }
+
+    pub fn backtrace(&self) -> &Backtrace {
+        &self.backtrace
+    }
+}
+
+impl std::fmt::Display for PanicReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "thread '{}' panicked", self.thread_name.as_deref().unwrap_or("<unnamed>"))?;
+        if let Some(location) = &self.location {
+            write!(f, " at {location}")?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
@@ -0,0 +1,129 @@
+//! Signalling exit when the parent process dies, for workers spawned by a supervisor.
+//!
+//! Unix only.  On Linux the kernel can also be asked for a signal on parent death, whose
+//! handler only sets a flag for the watcher thread.
+#![cfg_attr(target_os = "linux", allow(unsafe_code))]
+
+use crate::{ChexShared,ExitOrigin,ExitReason};
+use crate::sink::{self,ChexEvent};
+use std::sync::Weak;
+#[cfg(target_os = "linux")]
+use std::sync::atomic::AtomicBool;
+#[cfg(target_os = "linux")]
+use std::sync::atomic::Ordering::Relaxed;
+use std::time::Duration;
+
+/*
+ * Raised by the parent death signal's handler.
+ */
+#[cfg(target_os = "linux")]
+static PARENT_DIED: AtomicBool = AtomicBool::new(false);
+
+/*
+ * How exit_on_parent_death() watches the parent process.
+ */
+#[derive(Clone, Debug)]
+pub struct ParentDeathOptions {
+    poll_interval: Duration,
+    #[cfg(target_os = "linux")]
+    death_signal: bool,
+}
+
+impl Default for ParentDeathOptions {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_millis(100),
+            #[cfg(target_os = "linux")]
+            death_signal: false,
+        }
+    }
+}
+
+impl ParentDeathOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How often to check whether the process has been reparented.  Defaults to 100ms.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Also ask the kernel for a signal on parent death (PR_SET_PDEATHSIG with SIGRTMAX, which
+    /// chex then handles), for parents which die without this process being visibly
+    /// reparented.  The kernel fires when the *thread* which spawned this process exits, so only
+    /// enable this if the parent spawns from a long-lived thread.  Defaults to false.
+    #[cfg(target_os = "linux")]
+    pub fn death_signal(mut self, enable: bool) -> Self {
+        self.death_signal = enable;
+        self
+    }
+}
+
+pub(crate) fn watch(shared: Weak<ChexShared>, origin: ExitOrigin, options: &ParentDeathOptions) {
+    let parent = std::os::unix::process::parent_id();
+
+    #[cfg(target_os = "linux")]
+    if options.death_signal {
+        if let Err(e) = set_death_signal() {
+            sink::emit(ChexEvent::ParentDeathSignalFailed { error: &e });
+        }
+    }
+
+    /*
+     * Once the parent is gone the process is reparented (to init or a subreaper), which also
+     * covers a parent which died before the thread started.
+     */
+    let interval = options.poll_interval;
+    let spawned = std::thread::Builder::new()
+        .name("chex-parent-watch".to_string())
+        .spawn(move || {
+            loop {
+                let Some(shared) = shared.upgrade() else { return };
+                if shared.poll_exit() {
+                    return;
+                }
+                if parent_died() || std::os::unix::process::parent_id() != parent {
+                    shared.signal_exit_from(ExitReason::ParentExited, origin);
+                    return;
+                }
+                drop(shared);
+                std::thread::sleep(interval);
+            }
+        });
+    if let Err(e) = spawned {
+        sink::emit(ChexEvent::ThreadSpawnFailed { thread: "chex-parent-watch", error: &e });
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn parent_died() -> bool {
+    PARENT_DIED.load(Relaxed)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn parent_died() -> bool {
+    false
+}
+
+#[cfg(target_os = "linux")]
+extern "C" fn on_death_signal(_signum: libc::c_int) {
+    PARENT_DIED.store(true, Relaxed);
+}
+
+/// Handle SIGRTMAX, and have the kernel send it when the thread which spawned this process
+/// exits.
+#[cfg(target_os = "linux")]
+fn set_death_signal() -> std::io::Result<()> {
+    let signum = libc::SIGRTMAX();
+    // SAFETY: on_death_signal only stores to an atomic, which is async-signal-safe.
+    if unsafe { libc::signal(signum, on_death_signal as extern "C" fn(libc::c_int) as libc::sighandler_t) } == libc::SIG_ERR {
+        return Err(std::io::Error::last_os_error());
+    }
+    // SAFETY: PR_SET_PDEATHSIG takes a signal number and no pointers.
+    if unsafe { libc::prctl(libc::PR_SET_PDEATHSIG, libc::c_ulong::from(signum.unsigned_abs())) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
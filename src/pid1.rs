@@ -0,0 +1,255 @@
+//! A minimal init for containers where the application runs as PID 1: SIGTERM and SIGINT are
+//! forwarded to adopted children and signal exit, and exited processes (including orphans
+//! reparented to PID 1) are reaped so they don't linger as zombies.
+//!
+//! Enabled with the "pid1" feature (which implies "process"), on unix only.  Like the "sigterm"
+//! feature, the handlers only write to a pipe, and a "chex-pid1" thread does the work.  Once
+//! installed, this handler owns SIGTERM, and also dispatches it to the coordinators of
+//! ChexInstance::handle_sigterm().
+#![allow(unsafe_code)]
+
+use crate::{ChexShared,ExitOrigin,ExitReason};
+use crate::sink::{self,ChexEvent};
+use libc::{SIGCHLD,SIGINT,SIGTERM,c_int};
+use crate::Instant;
+use std::io::Read;
+use std::os::fd::{AsRawFd,IntoRawFd};
+use std::sync::{Arc,Mutex,Weak};
+use std::sync::atomic::{AtomicBool,AtomicI32};
+use std::sync::atomic::Ordering::Relaxed;
+use std::time::Duration;
+
+/*
+ * How ChexInstance::act_as_pid1() sets up.
+ */
+#[derive(Clone, Debug)]
+pub struct Pid1Options {
+    require_pid1: bool,
+    orphan_grace: Duration,
+}
+
+impl Default for Pid1Options {
+    fn default() -> Self {
+        Self { require_pid1: true, orphan_grace: Duration::from_secs(1) }
+    }
+}
+
+impl Pid1Options {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only act as init if this process really is PID 1, so the same binary can call
+    /// act_as_pid1() unconditionally and still run normally outside a container.  Defaults to
+    /// true.
+    pub fn require_pid1(mut self, require: bool) -> Self {
+        self.require_pid1 = require;
+        self
+    }
+
+    /// How long a child which wasn't adopted may stay a zombie before it is reaped, which leaves
+    /// the process's own std::process::Child handles time to wait for it and get its exit
+    /// status.  Orphans reparented to PID 1 are reaped after this long too.  Only the options
+    /// of the first act_as_pid1() call count.  Defaults to 1s.
+    pub fn orphan_grace(mut self, grace: Duration) -> Self {
+        self.orphan_grace = grace;
+        self
+    }
+}
+
+struct Target {
+    shared: Weak<ChexShared>,
+    origin: ExitOrigin,
+}
+
+static WRITE_FD: AtomicI32 = AtomicI32::new(-1);
+static TARGETS: Mutex<Vec<Target>> = Mutex::new(Vec::new());
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn on_signal(signum: c_int) {
+    let fd = WRITE_FD.load(Relaxed);
+    if fd >= 0 {
+        /*
+         * write() is async-signal-safe, and the write end is non-blocking: if the pipe is full
+         * the write fails, but the reader has wakeups pending anyway.  Signal numbers fit in a
+         * byte.
+         */
+        let byte = signum as u8;
+        // SAFETY: byte is a live local for the duration of the call, and write(2) only reads one
+        // byte from it.
+        unsafe {
+            libc::write(fd, (&byte as *const u8).cast(), 1);
+        }
+    }
+}
+
+/// Returns false if options require PID 1 and this process isn't.
+pub(crate) fn add(shared: Weak<ChexShared>, origin: ExitOrigin, options: &Pid1Options) -> bool {
+    if options.require_pid1 && std::process::id() != 1 {
+        return false;
+    }
+    TARGETS.lock().unwrap_or_else(|e| e.into_inner()).push(Target { shared, origin });
+    if !INSTALLED.swap(true, Relaxed) {
+        install(options.orphan_grace);
+    }
+    true
+}
+
+fn install(orphan_grace: Duration) {
+    let (mut reader, writer) = match std::io::pipe() {
+        Ok(pipe) => pipe,
+        Err(e) => {
+            sink::emit(ChexEvent::Pid1HandlerFailed { error: &e });
+            return;
+        }
+    };
+    let spawned = std::thread::Builder::new()
+        .name("chex-pid1".to_string())
+        .spawn(move || {
+            /*
+             * Zombies from before the handler was installed never raise SIGCHLD again.  While a
+             * zombie sits out its grace, the pipe is polled with a timeout so it's reaped
+             * without another SIGCHLD.
+             */
+            let mut young = None;
+            let mut recheck = reap(&mut young, orphan_grace);
+            let mut buf = [0u8; 16];
+            loop {
+                let timeout = recheck.map_or(-1, |d| c_int::try_from(d.as_millis() + 1).unwrap_or(c_int::MAX));
+                let mut fd = libc::pollfd { fd: reader.as_raw_fd(), events: libc::POLLIN, revents: 0 };
+                // SAFETY: fd is a live local describing one open descriptor, as poll(2) expects.
+                let ready = unsafe { libc::poll(&mut fd, 1, timeout) };
+                if ready > 0 {
+                    let Ok(n @ 1..) = reader.read(&mut buf) else { break };
+                    for &signum in &buf[..n] {
+                        match c_int::from(signum) {
+                            SIGCHLD => recheck = Some(Duration::ZERO),
+                            signum => forward(signum),
+                        }
+                    }
+                } else if ready < 0 && std::io::Error::last_os_error().kind() != std::io::ErrorKind::Interrupted {
+                    break;
+                }
+                if recheck.is_some() {
+                    recheck = reap(&mut young, orphan_grace);
+                }
+            }
+        });
+    if let Err(e) = spawned {
+        sink::emit(ChexEvent::ThreadSpawnFailed { thread: "chex-pid1", error: &e });
+        return;
+    }
+
+    let fd = writer.into_raw_fd();
+    // SAFETY: fd is the pipe's write end, owned by this module from here on; F_GETFL and
+    // F_SETFL take no pointers.
+    let nonblocking = unsafe {
+        let flags = libc::fcntl(fd, libc::F_GETFL);
+        flags >= 0 && libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) >= 0
+    };
+    if !nonblocking {
+        let e = std::io::Error::last_os_error();
+        sink::emit(ChexEvent::Pid1HandlerFailed { error: &e });
+    }
+    WRITE_FD.store(fd, Relaxed);
+    for signum in [SIGTERM, SIGINT, SIGCHLD] {
+        // SAFETY: on_signal only loads an atomic and calls write(2), both async-signal-safe.
+        if unsafe { libc::signal(signum, on_signal as extern "C" fn(c_int) as libc::sighandler_t) } == libc::SIG_ERR {
+            let e = std::io::Error::last_os_error();
+            sink::emit(ChexEvent::Pid1HandlerFailed { error: &e });
+        }
+    }
+}
+
+/// Whether the SIGTERM handler installed here is in place, so handle_sigterm() must not replace
+/// it.
+#[cfg(feature = "sigterm")]
+pub(crate) fn owns_sigterm() -> bool {
+    INSTALLED.load(Relaxed)
+}
+
+/// Collect every exited child.  Zombies are only peeked at (WNOWAIT) first: adopted children
+/// are collected through their own Child handle, which keeps their exit status, and the rest
+/// are reaped here once they have been zombies for orphan_grace.  young holds the zombie
+/// sitting out its grace.  Returns how long until that zombie is due, if there is one.
+fn reap(young: &mut Option<(libc::pid_t, Instant)>, orphan_grace: Duration) -> Option<Duration> {
+    let mut handed_over: Option<libc::pid_t> = None;
+    loop {
+        // SAFETY: siginfo_t is plain data, for which all zeroes is a valid value.
+        let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+        // SAFETY: info is a live local which waitid(2) may write to.
+        if unsafe { libc::waitid(libc::P_ALL, 0, &mut info, libc::WEXITED | libc::WNOHANG | libc::WNOWAIT) } != 0 {
+            *young = None;
+            return None;
+        }
+        // SAFETY: waitid() filled in a child's exit, so si_pid is set.
+        let pid = unsafe { info.si_pid() };
+        if pid <= 0 {
+            *young = None;
+            return None;
+        }
+
+        /*
+         * An adopted child which is still a zombie after its handle had its go isn't collected
+         * by the handle (ie it was already taken over by terminate_all()), so treat it like any
+         * other.
+         */
+        if handed_over != Some(pid) && collect_adopted(pid) {
+            handed_over = Some(pid);
+            continue;
+        }
+
+        /*
+         * Someone may still wait for this child, so give them the grace.  Zombies are peeked at
+         * one at a time, so the ones behind it wait as well.
+         */
+        let seen = match *young {
+            Some((young_pid, seen)) if young_pid == pid => seen,
+            _ => young.insert((pid, Instant::now())).1,
+        };
+        let waited = seen.elapsed();
+        if waited < orphan_grace {
+            return Some(orphan_grace - waited);
+        }
+        *young = None;
+        let mut status: c_int = 0;
+        // SAFETY: status is a live local which waitpid(2) may write to.
+        unsafe {
+            libc::waitpid(pid, &mut status, libc::WNOHANG);
+        }
+    }
+}
+
+/// Have the Child handle of the adopted child pid (if any) collect its exit.  Returns true iff
+/// pid was adopted.
+fn collect_adopted(pid: libc::pid_t) -> bool {
+    let Ok(pid) = u32::try_from(pid) else { return false };
+    TARGETS.lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .filter_map(|t| t.shared.upgrade())
+        .any(|shared| shared.children.collect(pid))
+}
+
+fn forward(signum: c_int) {
+    let targets: Vec<(Arc<ChexShared>, ExitOrigin)> = TARGETS.lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .filter_map(|t| Some((t.shared.upgrade()?, t.origin.clone())))
+        .collect();
+
+    /*
+     * Coordinators which enabled SIGTERM handling get it through the sigterm module first, so
+     * their pre-stop delay applies; the rest exit right away.
+     */
+    #[cfg(feature = "sigterm")]
+    let handled = if signum == SIGTERM { crate::sigterm::dispatch() } else { Vec::new() };
+    for (shared, origin) in targets {
+        shared.children.signal_all(signum);
+        #[cfg(feature = "sigterm")]
+        if handled.iter().any(|h| Arc::ptr_eq(h, &shared)) {
+            continue;
+        }
+        shared.signal_exit_from(ExitReason::OsSignal(signum), origin);
+    }
+}
@@ -0,0 +1,206 @@
+//! Child processes terminated once exit is signalled, so they don't outlive a panicking parent.
+//!
+//! Enabled with the "process" feature.  On unix, children get SIGTERM (to the child or its
+//! process group), then SIGKILL once their grace period has elapsed.  Elsewhere they are killed
+//! right away.
+#![allow(unsafe_code)]
+
+use std::sync::Mutex;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::Relaxed;
+use std::time::Duration;
+use crate::{ChexConfig,Instant};
+
+/*
+ * How often terminate_all() checks whether children have exited.
+ */
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/*
+ * How an adopted child is terminated.
+ */
+#[derive(Clone, Debug)]
+pub struct ChildOptions {
+    grace: Duration,
+    process_group: bool,
+}
+
+impl Default for ChildOptions {
+    fn default() -> Self {
+        Self {
+            grace: Duration::from_secs(5),
+            process_group: false,
+        }
+    }
+}
+
+impl ChildOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How long to wait after SIGTERM before SIGKILL.  Defaults to 5 seconds.
+    pub fn grace(mut self, grace: Duration) -> Self {
+        self.grace = grace;
+        self
+    }
+
+    /// Send SIGTERM to the child's whole process group rather than the child alone.  Only
+    /// useful if the child leads its own group, ie was spawned with
+    /// `CommandExt::process_group(0)`.  SIGKILL still only reaches the child.  Defaults to false.
+    pub fn process_group(mut self, process_group: bool) -> Self {
+        self.process_group = process_group;
+        self
+    }
+}
+
+trait Adopted: Send {
+    fn id(&mut self) -> Option<u32>;
+    /// True once the child has exited (and been reaped).
+    fn has_exited(&mut self) -> bool;
+    fn kill(&mut self);
+}
+
+impl Adopted for std::process::Child {
+    fn id(&mut self) -> Option<u32> {
+        Some(std::process::Child::id(self))
+    }
+
+    fn has_exited(&mut self) -> bool {
+        !matches!(self.try_wait(), Ok(None))
+    }
+
+    fn kill(&mut self) {
+        let _ = std::process::Child::kill(self);
+        let _ = self.wait();
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Adopted for tokio::process::Child {
+    fn id(&mut self) -> Option<u32> {
+        tokio::process::Child::id(self)
+    }
+
+    fn has_exited(&mut self) -> bool {
+        !matches!(self.try_wait(), Ok(None))
+    }
+
+    fn kill(&mut self) {
+        let _ = self.start_kill();
+        let _ = self.try_wait();
+    }
+}
+
+/*
+ * Children adopted by one ChexShared.
+ */
+pub(crate) struct ChildRegistry {
+    children: Mutex<Vec<(Box<dyn Adopted>, ChildOptions)>>,
+    hooked: AtomicBool,
+}
+
+impl ChildRegistry {
+    pub(crate) fn new() -> Self {
+        Self {
+            children: Mutex::new(Vec::new()),
+            hooked: AtomicBool::new(false),
+        }
+    }
+
+    /// True only for the first call, when the teardown hook running terminate_all() has to be
+    /// registered.
+    pub(crate) fn mark_hooked(&self) -> bool {
+        !self.hooked.swap(true, Relaxed)
+    }
+
+    pub(crate) fn adopt_std(&self, child: std::process::Child, options: ChildOptions) {
+        self.children.lock().unwrap_or_else(|e| e.into_inner()).push((Box::new(child), options));
+    }
+
+    #[cfg(feature = "tokio")]
+    pub(crate) fn adopt_tokio(&self, child: tokio::process::Child, options: ChildOptions) {
+        self.children.lock().unwrap_or_else(|e| e.into_inner()).push((Box::new(child), options));
+    }
+
+    /// Send signum to every adopted child (or its process group) which is still running.
+    #[cfg(all(feature = "pid1", unix))]
+    pub(crate) fn signal_all(&self, signum: i32) {
+        for (child, options) in self.children.lock().unwrap_or_else(|e| e.into_inner()).iter_mut() {
+            if !child.has_exited() {
+                send(&mut **child, options, signum);
+            }
+        }
+    }
+
+    /// If pid is an adopted child, let its handle collect its exit (keeping the status).
+    /// Returns true iff pid is adopted.
+    #[cfg(all(feature = "pid1", unix))]
+    pub(crate) fn collect(&self, pid: u32) -> bool {
+        for (child, _) in self.children.lock().unwrap_or_else(|e| e.into_inner()).iter_mut() {
+            if child.id() == Some(pid) {
+                child.has_exited();
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Terminate every adopted child, and wait until they have all exited.  Grace periods run on
+    /// config's clock.
+    pub(crate) fn terminate_all(&self, config: &ChexConfig) {
+        let children = std::mem::take(&mut *self.children.lock().unwrap_or_else(|e| e.into_inner()));
+        let start = config.now();
+        let mut pending: Vec<(Box<dyn Adopted>, Instant)> = Vec::new();
+        for (mut child, options) in children {
+            if child.has_exited() {
+                continue;
+            }
+            if !terminate(&mut *child, &options) {
+                child.kill();
+                continue;
+            }
+            pending.push((child, start + options.grace));
+        }
+
+        while !pending.is_empty() {
+            let now = config.now();
+            pending.retain_mut(|(child, deadline)| {
+                if child.has_exited() {
+                    return false;
+                }
+                if now >= *deadline {
+                    child.kill();
+                    return false;
+                }
+                true
+            });
+            if !pending.is_empty() {
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        }
+    }
+}
+
+/// Send SIGTERM.  Returns false if the child should be killed right away instead.
+#[cfg(unix)]
+fn terminate(child: &mut dyn Adopted, options: &ChildOptions) -> bool {
+    send(child, options, libc::SIGTERM)
+}
+
+/// Send signum to the child, or to its process group if options say so.  Returns true iff the
+/// signal was sent.
+#[cfg(unix)]
+fn send(child: &mut dyn Adopted, options: &ChildOptions, signum: libc::c_int) -> bool {
+    let Some(pid) = child.id().and_then(|id| libc::pid_t::try_from(id).ok()) else {
+        return false;
+    };
+    let target = if options.process_group { -pid } else { pid };
+    // SAFETY: kill(2) takes no pointers; a stale pid at worst signals nothing.
+    unsafe { libc::kill(target, signum) == 0 }
+}
+
+#[cfg(not(unix))]
+fn terminate(_child: &mut dyn Adopted, _options: &ChildOptions) -> bool {
+    false
+}
@@ -0,0 +1,121 @@
+//! Work permits, for refusing new work once shutdown starts and waiting for in-flight work, and
+//! the draining flag raised ahead of exit.
+
+use crate::Instant;
+use std::sync::{Arc,Condvar,Mutex};
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::Relaxed;
+use std::task::Waker;
+use std::time::Duration;
+
+/*
+ * Draining flag of one ChexShared, raised ahead of exit (ie by start_draining() or during a
+ * SIGTERM pre-stop delay), with the wakers of tasks waiting for draining to begin.
+ */
+pub(crate) struct DrainFlag {
+    draining: AtomicBool,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl DrainFlag {
+    pub(crate) fn new() -> Self {
+        Self {
+            draining: AtomicBool::new(false),
+            wakers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Raise the flag and wake waiting tasks.  Returns true if it was already raised.
+    pub(crate) fn raise(&self) -> bool {
+        let was = self.draining.swap(true, Relaxed);
+        self.wake();
+        was
+    }
+
+    pub(crate) fn is_raised(&self) -> bool {
+        self.draining.load(Relaxed)
+    }
+
+    /// Wake waiting tasks, ie because exit was signalled.  Skipped if the lock is contended, as
+    /// from the minimal panic path; the holder is about to recheck the flags anyway.
+    pub(crate) fn wake(&self) {
+        let wakers = match self.wakers.try_lock() {
+            Ok(mut wakers) => std::mem::take(&mut *wakers),
+            Err(std::sync::TryLockError::Poisoned(e)) => std::mem::take(&mut *e.into_inner()),
+            Err(std::sync::TryLockError::WouldBlock) => return,
+        };
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    /// Register waker to be woken when draining begins or exit is signalled.
+    pub(crate) fn register(&self, waker: &Waker) {
+        let mut wakers = self.wakers.lock().unwrap_or_else(|e| e.into_inner());
+        if !wakers.iter().any(|w| w.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+    }
+}
+
+/*
+ * Outstanding work permits of one ChexShared.
+ */
+pub(crate) struct PermitRegistry {
+    outstanding: Mutex<usize>,
+    released_cv: Condvar,
+}
+
+/*
+ * Returned by acquire_work_permit() while the process is not shutting down.  Hold it for the
+ * duration of one unit of work; shutdown can wait for every permit to be dropped.
+ */
+pub struct WorkPermit {
+    registry: Arc<PermitRegistry>,
+}
+
+impl Drop for WorkPermit {
+    fn drop(&mut self) {
+        let mut outstanding = self.registry.outstanding.lock().unwrap_or_else(|e| e.into_inner());
+        *outstanding -= 1;
+        self.registry.released_cv.notify_all();
+    }
+}
+
+impl PermitRegistry {
+    pub(crate) fn new() -> Self {
+        Self {
+            outstanding: Mutex::new(0),
+            released_cv: Condvar::new(),
+        }
+    }
+
+    pub(crate) fn acquire(self: &Arc<Self>) -> WorkPermit {
+        *self.outstanding.lock().unwrap_or_else(|e| e.into_inner()) += 1;
+        WorkPermit {
+            registry: self.clone(),
+        }
+    }
+
+    pub(crate) fn outstanding(&self) -> usize {
+        *self.outstanding.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Wait until no permits are outstanding, or until timeout.
+    pub(crate) fn wait(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let mut outstanding = self.outstanding.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            if *outstanding == 0 {
+                return true;
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return false;
+            }
+            outstanding = self.released_cv.wait_timeout(outstanding, deadline - now)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+}
@@ -0,0 +1,30 @@
+//! Parallel iterators which stop once exit is signalled.
+//!
+//! Enabled with the "rayon" feature.
+
+use crate::ChexInstance;
+use ::rayon::iter::ParallelIterator;
+
+/// Exit-aware adapters for every rayon ParallelIterator.
+pub trait ParallelIteratorExt: ParallelIterator {
+    /// Stop once exit is signalled on ci: items not yet started are skipped, while items
+    /// already running finish.  Which items were processed is unspecified, as with
+    /// take_any_while(); check ci.poll_exit() afterwards to tell whether the run was cut short.
+    ///
+    /// ```
+    /// use chex::rayon::ParallelIteratorExt;
+    /// use rayon::prelude::*;
+    ///
+    /// let ci = chex::Chex::new_local(chex::ChexConfig::new());
+    /// let sum: u64 = (0..1000u64).into_par_iter().until_exit(&ci).sum();
+    /// assert_eq!(sum, 499500);
+    /// ```
+    fn until_exit<U>(self, ci: &ChexInstance<U>) -> impl ParallelIterator<Item = Self::Item>;
+}
+
+impl<I: ParallelIterator> ParallelIteratorExt for I {
+    fn until_exit<U>(self, ci: &ChexInstance<U>) -> impl ParallelIterator<Item = Self::Item> {
+        let shared = ci.shared.clone();
+        self.take_any_while(move |_| !shared.poll_exit())
+    }
+}
@@ -0,0 +1,108 @@
+//! Startup readiness barrier, the counterpart to exit coordination.
+
+use crate::Instant;
+use std::collections::HashSet;
+use std::sync::{Condvar,Mutex};
+use std::time::Duration;
+
+/*
+ * Longest wait_all_ready() sleeps before rechecking whether exit has been signalled.
+ */
+const EXIT_CHECK: Duration = Duration::from_millis(50);
+
+/*
+ * Components which have reported ready to one ChexShared.
+ */
+pub(crate) struct ReadinessRegistry {
+    ready: Mutex<HashSet<String>>,
+    cv: Condvar,
+}
+
+/*
+ * Returned by wait_all_ready() when some components did not become ready in time.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NotReady {
+    missing: Vec<String>,
+    exited: bool,
+}
+
+impl NotReady {
+    /// None of names can become ready, since Chex is not initialized.
+    pub(crate) fn uninitialized(names: &[&str]) -> Self {
+        Self {
+            missing: names.iter().map(|n| n.to_string()).collect(),
+            exited: false,
+        }
+    }
+
+    /// Components which had not reported ready.
+    pub fn missing(&self) -> &[String] {
+        &self.missing
+    }
+
+    /// Whether the wait ended early because exit was signalled.
+    pub fn exited(&self) -> bool {
+        self.exited
+    }
+}
+
+impl std::fmt::Display for NotReady {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.exited {
+            write!(f, "exit signalled before ready: ")?;
+        } else {
+            write!(f, "timed out waiting for ready: ")?;
+        }
+        write!(f, "{}", self.missing.join(", "))
+    }
+}
+
+impl std::error::Error for NotReady {}
+
+impl ReadinessRegistry {
+    pub(crate) fn new() -> Self {
+        Self {
+            ready: Mutex::new(HashSet::new()),
+            cv: Condvar::new(),
+        }
+    }
+
+    pub(crate) fn ready(&self, name: &str) {
+        self.ready.lock().unwrap_or_else(|e| e.into_inner()).insert(name.to_string());
+        self.cv.notify_all();
+    }
+
+    pub(crate) fn is_ready(&self, name: &str) -> bool {
+        self.ready.lock().unwrap_or_else(|e| e.into_inner()).contains(name)
+    }
+
+    /// Wait until every one of names is ready, timeout elapses, or exited() returns true.
+    pub(crate) fn wait_all(
+        &self,
+        names: &[&str],
+        timeout: Duration,
+        exited: impl Fn() -> bool,
+    ) -> Result<(), NotReady> {
+        let deadline = Instant::now() + timeout;
+        let mut ready = self.ready.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            let missing: Vec<String> = names.iter()
+                .filter(|n| !ready.contains(**n))
+                .map(|n| n.to_string())
+                .collect();
+            if missing.is_empty() {
+                return Ok(());
+            }
+
+            let exited = exited();
+            let now = Instant::now();
+            if exited || now >= deadline {
+                return Err(NotReady { missing, exited });
+            }
+            ready = self.cv.wait_timeout(ready, (deadline - now).min(EXIT_CHECK))
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+}
@@ -0,0 +1,281 @@
+//! Structured concurrency: run a group of workers under a child coordinator, and wait for all
+//! of them.
+//!
+//! ```no_run
+//! let chex = chex::Chex::init(true);
+//! let result = chex::scope::scope(|s| {
+//!     s.spawn("ingest", |ci| {
+//!         while !ci.poll_exit() {
+//!             // ...
+//!         }
+//!     });
+//!     s.spawn("flush", |ci| -> Result<(), std::io::Error> {
+//!         ci.block_until_exit();
+//!         Ok(())
+//!     });
+//! });
+//! # drop((chex, result));
+//! ```
+
+use crate::{ChexConfig,ChexInstance,ChexShared,ExitOrigin,ExitReason,ExitReasonKind};
+use crate::sink::{self,ChexEvent};
+use std::panic::Location;
+use std::sync::{Arc,Mutex};
+
+/*
+ * What a failed worker does beyond cancelling its scope.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ScopePolicy {
+    /// Cancel the scope only; the failure is reported by scope() returning Err.
+    Contain,
+    /// Also signal exit on the parent coordinator, with the failure as reason.
+    #[default]
+    Escalate,
+}
+
+/*
+ * One worker which panicked or returned Err.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScopeFailure {
+    worker: String,
+    reason: ExitReasonKind,
+    message: String,
+}
+
+impl ScopeFailure {
+    pub fn worker(&self) -> &str {
+        &self.worker
+    }
+
+    /// ExitReasonKind::Panic or ExitReasonKind::Error.
+    pub fn reason(&self) -> ExitReasonKind {
+        self.reason
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl std::fmt::Display for ScopeFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "worker '{}' failed ({}): {}", self.worker, self.reason, self.message)
+    }
+}
+
+/*
+ * Returned by scope() when at least one worker failed, in the order they failed.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScopeError {
+    failures: Vec<ScopeFailure>,
+}
+
+impl ScopeError {
+    pub fn failures(&self) -> &[ScopeFailure] {
+        &self.failures
+    }
+
+    /// The failure which cancelled the scope.
+    pub fn first(&self) -> &ScopeFailure {
+        &self.failures[0]
+    }
+}
+
+impl std::fmt::Display for ScopeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.first())?;
+        if self.failures.len() > 1 {
+            write!(f, " (and {} more)", self.failures.len() - 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ScopeError {}
+
+/// What a worker returns: () never fails, Result<_, E> fails with Err.
+pub trait WorkerOutcome {
+    /// The failure message, if the worker failed.
+    fn failure(self) -> Option<String>;
+}
+
+impl WorkerOutcome for () {
+    fn failure(self) -> Option<String> {
+        None
+    }
+}
+
+impl<T, E: std::fmt::Display> WorkerOutcome for Result<T, E> {
+    fn failure(self) -> Option<String> {
+        self.err().map(|e| e.to_string())
+    }
+}
+
+struct ScopeState {
+    ci: ChexInstance,
+    parent: Arc<ChexShared>,
+    policy: ScopePolicy,
+    location: &'static Location<'static>,
+    failures: Mutex<Vec<ScopeFailure>>,
+}
+
+impl ScopeState {
+    fn fail(&self, worker: &str, reason: ExitReasonKind, message: String) {
+        self.failures.lock().unwrap_or_else(|e| e.into_inner()).push(ScopeFailure {
+            worker: worker.to_string(),
+            reason,
+            message: message.clone(),
+        });
+
+        let origin = ExitOrigin::from_location(self.location);
+        let message = format!("{worker}: {message}");
+        let reason = match reason {
+            ExitReasonKind::Panic => ExitReason::Panic { message },
+            _ => ExitReason::Error { message },
+        };
+        self.ci.shared.signal_exit_from(reason.clone(), origin.clone());
+        if self.policy == ScopePolicy::Escalate {
+            self.parent.errors.record(reason.kind(), reason.to_string(), origin.clone());
+            self.parent.signal_exit_from(reason, origin);
+        }
+    }
+
+    /// Run a worker body, turning a panic or Err into a scope-wide cancel.  Under
+    /// ScopePolicy::Contain the exit-on-panic hooks stand down while it runs, so a panic only
+    /// reaches the scope.
+    fn run<O: WorkerOutcome>(&self, worker: &str, body: impl FnOnce() -> O) {
+        let _suppress = (self.policy == ScopePolicy::Contain).then(crate::panic::suppress_exit);
+        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(body)) {
+            Ok(outcome) => {
+                if let Some(message) = outcome.failure() {
+                    self.fail(worker, ExitReasonKind::Error, message);
+                }
+            }
+            Err(payload) => {
+                let message = if let Some(s) = payload.downcast_ref::<&'static str>() {
+                    s.to_string()
+                } else if let Some(s) = payload.downcast_ref::<String>() {
+                    s.clone()
+                } else {
+                    "Box<dyn Any>".to_string()
+                };
+                self.fail(worker, ExitReasonKind::Panic, message);
+            }
+        }
+    }
+}
+
+/*
+ * Handle passed to the scope() closure, to spawn workers onto.
+ */
+pub struct Scope<'scope, 'env: 'scope> {
+    inner: &'scope std::thread::Scope<'scope, 'env>,
+    state: Arc<ScopeState>,
+}
+
+impl<'scope, 'env> Scope<'scope, 'env> {
+    /// The child coordinator every worker is handed.  Exit is signalled on it when a worker
+    /// fails, when cancel() is called, or when exit is signalled on the parent.
+    pub fn instance(&self) -> &ChexInstance {
+        &self.state.ci
+    }
+
+    /// Cancel the scope, ie signal exit on its coordinator.  The parent is not affected.
+    #[track_caller]
+    pub fn cancel(&self) -> bool {
+        self.state.ci.shared.signal_exit_from(ExitReason::Manual, ExitOrigin::from_location(Location::caller()))
+    }
+
+    /// Spawn worker on a thread named name.  It is handed a clone of the scope's coordinator,
+    /// and may return () or a Result; a panic or Err cancels the scope.
+    pub fn spawn<F, O>(&self, name: &str, worker: F)
+    where
+        F: FnOnce(ChexInstance) -> O + Send + 'scope,
+        O: WorkerOutcome,
+    {
+        let state = self.state.clone();
+        let worker_name = name.to_string();
+        let spawned = std::thread::Builder::new()
+            .name(name.to_string())
+            .spawn_scoped(self.inner, move || {
+                let ci = state.ci.clone();
+                state.run(&worker_name, move || worker(ci));
+            });
+        if let Err(e) = spawned {
+            sink::emit(ChexEvent::ThreadSpawnFailed { thread: "chex-scope", error: &e });
+            self.state.fail(name, ExitReasonKind::Error, e.to_string());
+        }
+    }
+
+    /// Spawn an async worker on a thread named name.  The future returned by factory is driven
+    /// with block_on() on the tokio runtime current at the time of the call, or on a new
+    /// current-thread runtime outside one.
+    #[cfg(feature = "tokio")]
+    pub fn spawn_async<F, Fut>(&self, name: &str, factory: F)
+    where
+        F: FnOnce(ChexInstance) -> Fut + Send + 'scope,
+        Fut: std::future::Future,
+        Fut::Output: WorkerOutcome,
+    {
+        let handle = tokio::runtime::Handle::try_current();
+        self.spawn(name, move |ci| -> Result<(), std::io::Error> {
+            let fut = factory(ci);
+            let output = match handle {
+                Ok(handle) => handle.block_on(fut),
+                Err(_) => tokio::runtime::Builder::new_current_thread().enable_all().build()?.block_on(fut),
+            };
+            match output.failure() {
+                Some(message) => Err(std::io::Error::other(message)),
+                None => Ok(()),
+            }
+        });
+    }
+}
+
+/// Run f with a Scope whose workers are cancelled together, under the global Chex, with
+/// ScopePolicy::Escalate.  See scope_with().
+///
+/// Panics if Chex is not initialized.
+#[track_caller]
+pub fn scope<'env, F, R>(f: F) -> Result<R, ScopeError>
+where
+    F: for<'scope> FnOnce(&Scope<'scope, 'env>) -> R,
+{
+    scope_with(&crate::Chex::get_chex_instance(), ScopePolicy::default(), f)
+}
+
+/// Run f with a Scope, then wait for every worker it spawned.  Workers share a child
+/// coordinator of parent: exit signalled on parent cancels the scope, and the first worker to
+/// panic or return Err cancels it too (and, with ScopePolicy::Escalate, signals exit on parent).
+///
+/// Returns f's output, or every failure once all workers have finished.  With
+/// ScopePolicy::Contain, worker panics don't trigger exit-on-panic (global or local) either.
+#[track_caller]
+pub fn scope_with<'env, U, F, R>(parent: &ChexInstance<U>, policy: ScopePolicy, f: F) -> Result<R, ScopeError>
+where
+    F: for<'scope> FnOnce(&Scope<'scope, 'env>) -> R,
+{
+    let location = Location::caller();
+    let ci = ChexInstance::new(ChexConfig::new());
+    parent.shared.link_child(&ci.shared, location);
+    let state = Arc::new(ScopeState {
+        ci,
+        parent: parent.shared.clone(),
+        policy,
+        location,
+        failures: Mutex::new(Vec::new()),
+    });
+
+    let output = std::thread::scope(|s| f(&Scope { inner: s, state: state.clone() }));
+
+    let failures = std::mem::take(&mut *state.failures.lock().unwrap_or_else(|e| e.into_inner()));
+    if failures.is_empty() {
+        Ok(output)
+    } else {
+        Err(ScopeError { failures })
+    }
+}
@@ -0,0 +1,211 @@
+//! A machine-local exit flag in shared memory, for sibling processes.
+//!
+//! Enabled with the "shm" feature, on 64-bit unix.  The flag is one word in a file mapped from
+//! /dev/shm (or the temp dir where there is no /dev/shm), so checking it costs an atomic load.
+//! On Linux waiters sleep on a futex on that word, which serves as the named event; elsewhere
+//! they poll.  Processes which don't use chex can open the same ShmFlag directly.
+#![allow(unsafe_code)]
+
+use crate::{ChexInstance,ChexShared,ExitOrigin,ExitReason};
+use crate::interrupt::{Interrupt,InterruptGuard};
+use crate::sink::{self,ChexEvent};
+use libc::c_void;
+use std::os::fd::AsRawFd;
+use std::panic::Location;
+use std::path::PathBuf;
+use std::sync::{Arc,Weak};
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering::SeqCst;
+use std::time::Duration;
+
+const LEN: usize = 4096;
+
+/*
+ * How long the "chex-shm" watcher sleeps between checks on whether its coordinator is gone.
+ */
+const WATCH_INTERVAL: Duration = Duration::from_millis(100);
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod futex {
+    use std::sync::atomic::AtomicU32;
+    use std::time::Duration;
+
+    /// Sleep while word == expected, for at most timeout.  Not private: the word is shared
+    /// between processes.
+    pub(super) fn wait(word: &AtomicU32, expected: u32, timeout: Duration) {
+        let ts = libc::timespec {
+            tv_sec: libc::time_t::try_from(timeout.as_secs()).unwrap_or(libc::time_t::MAX),
+            tv_nsec: timeout.subsec_nanos() as libc::c_long,
+        };
+        // SAFETY: word and ts outlive the call; FUTEX_WAIT only reads both.
+        unsafe {
+            libc::syscall(libc::SYS_futex, word.as_ptr(), libc::FUTEX_WAIT, expected, &ts as *const libc::timespec);
+        }
+    }
+
+    pub(super) fn wake_all(word: &AtomicU32) {
+        // SAFETY: FUTEX_WAKE only uses the address of word as a key.
+        unsafe {
+            libc::syscall(libc::SYS_futex, word.as_ptr(), libc::FUTEX_WAKE, i32::MAX);
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+mod futex {
+    use std::sync::atomic::AtomicU32;
+    use std::time::Duration;
+
+    pub(super) fn wait(_word: &AtomicU32, _expected: u32, timeout: Duration) {
+        std::thread::sleep(timeout.min(Duration::from_millis(10)));
+    }
+
+    pub(super) fn wake_all(_word: &AtomicU32) {}
+}
+
+/*
+ * A named exit flag shared by every process on the machine which opens the same name.
+ */
+pub struct ShmFlag {
+    word: *const AtomicU32,
+}
+
+/*
+ * The mapping is only accessed through the AtomicU32.
+ */
+// SAFETY: see above; the mapping stays valid until drop.
+unsafe impl Send for ShmFlag {}
+// SAFETY: see above.
+unsafe impl Sync for ShmFlag {}
+
+impl Drop for ShmFlag {
+    fn drop(&mut self) {
+        // SAFETY: word is the start of the LEN byte mapping from open(), and word() borrows end
+        // with self.
+        unsafe {
+            libc::munmap(self.word as *mut c_void, LEN);
+        }
+    }
+}
+
+impl ShmFlag {
+    /// Open (creating if needed) the flag called name, which may only contain ASCII letters,
+    /// digits, '-' and '_'.
+    pub fn open(name: &str) -> std::io::Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(Self::path(name)?)?;
+        if file.metadata()?.len() < LEN as u64 {
+            file.set_len(LEN as u64)?;
+        }
+        // SAFETY: a fresh shared mapping of a file at least LEN bytes long; no existing memory is
+        // affected.
+        let addr = unsafe {
+            libc::mmap(std::ptr::null_mut(), LEN, libc::PROT_READ | libc::PROT_WRITE, libc::MAP_SHARED, file.as_raw_fd(), 0)
+        };
+        if addr == libc::MAP_FAILED {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(Self { word: addr as *const AtomicU32 })
+    }
+
+    /// Remove the file backing the flag called name, so that the next open() starts lowered.
+    /// Processes which already opened it keep their (raised) mapping.
+    pub fn remove(name: &str) -> std::io::Result<()> {
+        std::fs::remove_file(Self::path(name)?)
+    }
+
+    fn path(name: &str) -> std::io::Result<PathBuf> {
+        if name.is_empty() || !name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_') {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid shm flag name"));
+        }
+        let dir = PathBuf::from("/dev/shm");
+        let dir = if dir.is_dir() { dir } else { std::env::temp_dir() };
+        Ok(dir.join(format!("chex-{name}")))
+    }
+
+    fn word(&self) -> &AtomicU32 {
+        // SAFETY: the mapping is page aligned and lives until drop; other processes only access
+        // it atomically too.
+        unsafe { &*self.word }
+    }
+
+    pub fn is_raised(&self) -> bool {
+        self.word().load(SeqCst) != 0
+    }
+
+    /// Raise the flag and wake every waiter.  Returns true iff it was not raised yet.
+    pub fn raise(&self) -> bool {
+        let first = self.word().swap(1, SeqCst) == 0;
+        futex::wake_all(self.word());
+        first
+    }
+
+    /// Park the current thread until the flag is raised, or until timeout.  Returns true iff
+    /// the flag is raised.
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        let deadline = crate::Instant::now() + timeout;
+        loop {
+            if self.is_raised() {
+                return true;
+            }
+            let now = crate::Instant::now();
+            if now >= deadline {
+                return false;
+            }
+            futex::wait(self.word(), 0, deadline - now);
+        }
+    }
+}
+
+/*
+ * Returned by link().  Local exits raise the flag for as long as it is kept; a raised flag
+ * signals exit locally regardless.
+ */
+pub struct ShmLink {
+    _guard: InterruptGuard,
+}
+
+struct Raise(Arc<ShmFlag>);
+
+impl Interrupt for Raise {
+    fn interrupt(&self) {
+        self.0.raise();
+    }
+}
+
+/// Tie ci to the shared flag called name: exit signalled on ci raises the flag, and a raised
+/// flag (ie by a sibling process) signals exit on ci with ExitReason::Manual and the link()
+/// call as origin.  If the flag is already raised, exit is signalled right away.
+#[track_caller]
+pub fn link<U>(ci: &ChexInstance<U>, name: &str) -> std::io::Result<ShmLink> {
+    let origin = ExitOrigin::from_location(Location::caller());
+    let flag = Arc::new(ShmFlag::open(name)?);
+    let guard = ci.interrupt_on_exit(Raise(flag.clone()));
+
+    let shared = Arc::downgrade(&ci.shared);
+    let spawned = std::thread::Builder::new()
+        .name("chex-shm".to_string())
+        .spawn(move || watch(&flag, shared, origin));
+    if let Err(e) = spawned {
+        sink::emit(ChexEvent::ThreadSpawnFailed { thread: "chex-shm", error: &e });
+    }
+    Ok(ShmLink { _guard: guard })
+}
+
+fn watch(flag: &ShmFlag, shared: Weak<ChexShared>, origin: ExitOrigin) {
+    loop {
+        let raised = flag.wait_timeout(WATCH_INTERVAL);
+        let Some(shared) = shared.upgrade() else { return };
+        if shared.poll_exit() {
+            return;
+        }
+        if raised {
+            shared.signal_exit_from(ExitReason::Manual, origin);
+            return;
+        }
+    }
+}
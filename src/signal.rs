@@ -0,0 +1,44 @@
+//! A small object-safe view of a coordinator, so libraries can accept `impl ExitSignal` (or
+//! `&dyn ExitSignal`) instead of a ChexInstance, and their unit tests can pass a fake without
+//! touching global state.
+
+use crate::{ChexInstance,latency};
+use std::future::Future;
+use std::pin::Pin;
+
+/*
+ * The exit protocol as seen by a component: check for exit, trigger it, or wait for it.
+ */
+pub trait ExitSignal: Send + Sync {
+    /// Returns true iff exit has been signalled.
+    fn poll_exit(&self) -> bool;
+
+    /// Signal exit.  Returns true iff this call was the first to signal exit.
+    #[track_caller]
+    fn signal_exit(&self) -> bool;
+
+    /// Resolves once exit has been signalled.
+    fn wait_exit(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>>;
+}
+
+impl<T> ExitSignal for ChexInstance<T> {
+    fn poll_exit(&self) -> bool {
+        ChexInstance::poll_exit(self)
+    }
+
+    #[track_caller]
+    fn signal_exit(&self) -> bool {
+        ChexInstance::signal_exit(self)
+    }
+
+    /// Like check_exit_async(), but only needs a shared reference.
+    fn wait_exit(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(async move {
+            if self.shared.poll_exit() {
+                return;
+            }
+            self.shared.notify.wait(&self.shared.exit).await;
+            self.shared.record_wakeup(latency::WakeupKind::Async);
+        })
+    }
+}
@@ -0,0 +1,188 @@
+//! SIGTERM handling, with an optional pre-stop delay for Kubernetes rolling updates.
+//!
+//! Enabled with the "sigterm" feature (implied by "systemd"), on unix only.  The handler only
+//! writes to a pipe; a "chex-sigterm" thread reads it and acts on every coordinator which
+//! enabled SIGTERM handling.
+#![allow(unsafe_code)]
+
+use crate::{ChexShared,ExitOrigin,ExitReason};
+use crate::sink::{self,ChexEvent};
+use libc::{SIGTERM,c_int};
+use std::io::Read;
+use std::os::fd::IntoRawFd;
+use std::sync::{Arc,Mutex,Weak};
+use std::sync::atomic::{AtomicBool,AtomicI32};
+use std::sync::atomic::Ordering::Relaxed;
+use std::time::Duration;
+
+/*
+ * How handle_sigterm() reacts to SIGTERM.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct SigtermOptions {
+    pre_stop_delay: Duration,
+}
+
+impl SigtermOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// On SIGTERM, start draining (is_draining() turns true) and wait delay before signalling
+    /// exit, so the Kubernetes endpoint controller can stop routing traffic here first.  A
+    /// second SIGTERM signals exit right away.  Defaults to zero, ie exit immediately.
+    pub fn pre_stop_delay(mut self, delay: Duration) -> Self {
+        self.pre_stop_delay = delay;
+        self
+    }
+}
+
+struct Target {
+    shared: Weak<ChexShared>,
+    origin: ExitOrigin,
+    pre_stop_delay: Duration,
+    /* SIGTERMs dispatched to this target so far; only the first one waits out the delay */
+    sigterms: usize,
+}
+
+static WRITE_FD: AtomicI32 = AtomicI32::new(-1);
+static TARGETS: Mutex<Vec<Target>> = Mutex::new(Vec::new());
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn on_sigterm(_signum: c_int) {
+    let fd = WRITE_FD.load(Relaxed);
+    if fd >= 0 {
+        /*
+         * write() is async-signal-safe, and the write end is non-blocking: if the pipe is full
+         * the write fails, and a wakeup is already pending.
+         */
+        let byte = 1u8;
+        // SAFETY: byte is a live local for the duration of the call, and write(2) only reads one
+        // byte from it.
+        unsafe {
+            libc::write(fd, (&byte as *const u8).cast(), 1);
+        }
+    }
+}
+
+pub(crate) fn add(shared: Weak<ChexShared>, origin: ExitOrigin, options: &SigtermOptions) {
+    TARGETS.lock().unwrap_or_else(|e| e.into_inner()).push(Target {
+        shared,
+        origin,
+        pre_stop_delay: options.pre_stop_delay,
+        sigterms: 0,
+    });
+    if !INSTALLED.swap(true, Relaxed) {
+        install();
+    }
+}
+
+/// In a forked child, forget the parent's targets and restore the default SIGTERM action: the
+/// pipe's reader thread only runs in the parent.  The next add() installs a new pipe and reader.
+#[cfg(feature = "fork")]
+pub(crate) fn reinit_after_fork() {
+    TARGETS.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    if !INSTALLED.swap(false, Relaxed) {
+        return;
+    }
+    // SAFETY: restoring the default action doesn't run any code of ours.
+    unsafe {
+        libc::signal(SIGTERM, libc::SIG_DFL);
+    }
+    let fd = WRITE_FD.swap(-1, Relaxed);
+    if fd >= 0 {
+        // SAFETY: fd came from into_raw_fd() in install() and the swap above took sole ownership.
+        unsafe {
+            libc::close(fd);
+        }
+    }
+}
+
+fn install() {
+    /*
+     * PID 1's handler owns SIGTERM, and dispatches it here.
+     */
+    #[cfg(feature = "pid1")]
+    if crate::pid1::owns_sigterm() {
+        return;
+    }
+    let (mut reader, writer) = match std::io::pipe() {
+        Ok(pipe) => pipe,
+        Err(e) => {
+            sink::emit(ChexEvent::SigtermHandlerFailed { error: &e });
+            return;
+        }
+    };
+    let spawned = std::thread::Builder::new()
+        .name("chex-sigterm".to_string())
+        .spawn(move || {
+            let mut buf = [0u8; 16];
+            while matches!(reader.read(&mut buf), Ok(n) if n > 0) {
+                dispatch();
+            }
+        });
+    if let Err(e) = spawned {
+        sink::emit(ChexEvent::ThreadSpawnFailed { thread: "chex-sigterm", error: &e });
+        return;
+    }
+
+    let fd = writer.into_raw_fd();
+    // SAFETY: fd is the pipe's write end, owned by this module from here on; F_GETFL and
+    // F_SETFL take no pointers.
+    let nonblocking = unsafe {
+        let flags = libc::fcntl(fd, libc::F_GETFL);
+        flags >= 0 && libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) >= 0
+    };
+    if !nonblocking {
+        let e = std::io::Error::last_os_error();
+        sink::emit(ChexEvent::SigtermHandlerFailed { error: &e });
+    }
+    WRITE_FD.store(fd, Relaxed);
+    // SAFETY: on_sigterm only loads an atomic and calls write(2), both async-signal-safe.
+    if unsafe { libc::signal(SIGTERM, on_sigterm as extern "C" fn(c_int) as libc::sighandler_t) } == libc::SIG_ERR {
+        let e = std::io::Error::last_os_error();
+        sink::emit(ChexEvent::SigtermHandlerFailed { error: &e });
+    }
+}
+
+/// Act on SIGTERM for every coordinator which enabled SIGTERM handling.  Returns those
+/// coordinators.
+pub(crate) fn dispatch() -> Vec<Arc<ChexShared>> {
+    let targets: Vec<(Arc<ChexShared>, ExitOrigin, Duration, usize)> = {
+        let mut targets = TARGETS.lock().unwrap_or_else(|e| e.into_inner());
+        targets.retain(|t| t.shared.strong_count() > 0);
+        targets.iter_mut()
+            .filter_map(|t| {
+                let shared = t.shared.upgrade()?;
+                t.sigterms += 1;
+                Some((shared, t.origin.clone(), t.pre_stop_delay, t.sigterms))
+            })
+            .collect()
+    };
+
+    let handled = targets.iter().map(|(shared, _, _, _)| shared.clone()).collect();
+    for (shared, origin, delay, sigterms) in targets {
+        /*
+         * Counted here rather than read off the drain state: draining may have begun for other
+         * reasons, and the first SIGTERM still gets its delay.
+         */
+        shared.start_draining();
+        if delay.is_zero() || sigterms > 1 {
+            shared.signal_exit_from(ExitReason::OsSignal(SIGTERM), origin);
+            continue;
+        }
+
+        let spawned = std::thread::Builder::new()
+            .name("chex-prestop".to_string())
+            .spawn(move || {
+                shared.config.timer_clock().sleep(delay);
+                if !shared.poll_exit() {
+                    shared.signal_exit_from(ExitReason::OsSignal(SIGTERM), origin);
+                }
+            });
+        if let Err(e) = spawned {
+            sink::emit(ChexEvent::ThreadSpawnFailed { thread: "chex-prestop", error: &e });
+        }
+    }
+    handled
+}
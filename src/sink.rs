@@ -0,0 +1,273 @@
+//! Pluggable destination for the crate's internal diagnostics.
+//!
+//! By default every event is written through `defmt` (with the "defmt" feature), else through
+//! the `log` facade (with the default-on "log" feature), else dropped.  Install a ChexSink with Chex::set_sink() to route them to
+//! your own logging or alerting instead.
+
+use crate::{ChexError,ExitOrigin,ExitReason,ForcedExit};
+use crate::listener::ListenerInfo;
+use crate::warning::ChexWarning;
+#[cfg(feature = "log")]
+use log::{error,warn};
+use std::panic::PanicHookInfo;
+use std::sync::RwLock;
+use std::sync::atomic::AtomicU8;
+use std::sync::atomic::Ordering::Relaxed;
+use std::time::Duration;
+
+static SINK: RwLock<Option<Box<dyn ChexSink>>> = RwLock::new(None);
+static LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Warn as u8);
+
+/*
+ * The most verbose events which the default sink reports, see ChexConfig::log_level().  An
+ * installed ChexSink receives every event regardless.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+}
+
+impl LogLevel {
+    /// Parse a level name, ie from CHEX_LOG_LEVEL.  Levels more verbose than "warn" ("info",
+    /// "debug", "trace") are accepted as Warn, since chex reports nothing below it.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "off" | "none" => Some(LogLevel::Off),
+            "error" => Some(LogLevel::Error),
+            "warn" | "warning" | "info" | "debug" | "trace" => Some(LogLevel::Warn),
+            _ => None,
+        }
+    }
+}
+
+/*
+ * Receiver for internal Chex events.  Implementations may be called from any thread, including
+ * from inside the panic hook, so they should not block or panic.
+ */
+pub trait ChexSink: Send + Sync + 'static {
+    fn on_event(&self, event: ChexEvent<'_>);
+}
+
+impl<F> ChexSink for F
+where
+    F: Fn(ChexEvent<'_>) + Send + Sync + 'static,
+{
+    fn on_event(&self, event: ChexEvent<'_>) {
+        self(event)
+    }
+}
+
+/*
+ * Internal event reported to the installed ChexSink.
+ */
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ChexEvent<'a> {
+    /// A panic was caught and is about to signal exit.
+    PanicCaught { info: &'a PanicHookInfo<'a> },
+    /// A panic was caught but the panic threshold has not been reached yet.
+    PanicBelowThreshold { seen: usize, threshold: usize },
+    /// The panic hook is handing over to the default panic handler.
+    DefaultPanicHandler,
+    /// Exit was signalled for the first time.
+    ExitSignalled { reason: &'a ExitReason, origin: Option<&'a ExitOrigin> },
+    /// Signalling exit failed.
+    #[deprecated(note = "never emitted: signalling exit cannot fail once Chex is initialized")]
+    SignalFailed { error: &'a ChexError },
+    /// Chex was used before Chex::init().
+    NotInitialized { operation: &'static str },
+    /// A grace period expired and the process is being terminated.
+    DeadlineExpired { grace: Duration, forced_exit: ForcedExit },
+    /// A watchdog missed its heartbeat.
+    WatchdogMissed { name: &'a str, interval: Duration },
+    /// A listener has not acked within the straggler timeout.
+    Straggler { listener: &'a ListenerInfo, timeout: Duration },
+    /// An interceptor did not release within its max_delay, so exit went ahead without it.
+    InterceptorExpired { name: &'a str, max_delay: Duration },
+    /// A non-fatal warning was signalled.
+    Warning { warning: &'a ChexWarning },
+    /// A background thread could not be spawned.
+    ThreadSpawnFailed { thread: &'static str, error: &'a std::io::Error },
+    /// A systemd notification (ie "STOPPING=1") could not be sent.
+    #[cfg(all(feature = "systemd", target_os = "linux"))]
+    SystemdFailed { action: &'static str, error: &'a std::io::Error },
+    /// The SIGTERM handler could not be installed.
+    #[cfg(all(feature = "sigterm", unix))]
+    SigtermHandlerFailed { error: &'a std::io::Error },
+    /// The PID 1 signal handlers could not be installed.
+    #[cfg(all(feature = "pid1", unix))]
+    Pid1HandlerFailed { error: &'a std::io::Error },
+    /// The parent death signal could not be set up, so only reparenting is watched for.
+    #[cfg(target_os = "linux")]
+    ParentDeathSignalFailed { error: &'a std::io::Error },
+    /// A CHEX_* environment variable could not be parsed, and was ignored.
+    InvalidEnvVar { name: &'static str, value: &'a str },
+}
+
+impl ChexEvent<'_> {
+    /// The level the default sink reports this event at.
+    pub fn level(&self) -> LogLevel {
+        match self {
+            ChexEvent::PanicBelowThreshold { .. } | ChexEvent::Warning { .. } => LogLevel::Warn,
+            ChexEvent::InvalidEnvVar { .. } => LogLevel::Warn,
+            _ => LogLevel::Error,
+        }
+    }
+}
+
+/*
+ * The default sink, writing events through the `log` facade.  ExitSignalled is not logged, as
+ * signalling exit is normal operation.
+ */
+#[cfg(feature = "log")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LogSink;
+
+#[cfg(feature = "log")]
+impl ChexSink for LogSink {
+    fn on_event(&self, event: ChexEvent<'_>) {
+        match event {
+            ChexEvent::PanicCaught { info } => {
+                error!("PANIC: {info}");
+                error!("PANIC: signal exit to all Chex listeners");
+            }
+            ChexEvent::PanicBelowThreshold { seen, threshold } => {
+                warn!("PANIC: {seen} of {threshold} panics before signalling exit");
+            }
+            ChexEvent::DefaultPanicHandler => error!("PANIC: calling default panic handler"),
+            ChexEvent::ExitSignalled { .. } => {}
+            #[allow(deprecated)]
+            ChexEvent::SignalFailed { error } => error!("signal_exit failed: {error}"),
+            ChexEvent::NotInitialized { operation } => {
+                error!("Failed to initialize Chex before {operation}");
+            }
+            ChexEvent::DeadlineExpired { grace, forced_exit } => {
+                error!("Exit deadline of {grace:?} expired, forcing {forced_exit:?}");
+            }
+            ChexEvent::WatchdogMissed { name, interval } => {
+                error!("Watchdog '{name}' missed its {interval:?} heartbeat");
+            }
+            ChexEvent::Straggler { listener, timeout } => {
+                error!("Listener '{}' on thread {} has not exited {timeout:?} after exit was signalled",
+                    listener.name(), listener.thread_name().unwrap_or("<unnamed>"));
+            }
+            ChexEvent::InterceptorExpired { name, max_delay } => {
+                error!("Interceptor '{name}' did not release within {max_delay:?}, exiting anyway");
+            }
+            ChexEvent::Warning { warning } => warn!("{warning}"),
+            ChexEvent::ThreadSpawnFailed { thread, error } => {
+                error!("Failed to spawn {thread} thread: {error}");
+            }
+            #[cfg(all(feature = "systemd", target_os = "linux"))]
+            ChexEvent::SystemdFailed { action, error } => error!("systemd: {action} failed: {error}"),
+            #[cfg(all(feature = "sigterm", unix))]
+            ChexEvent::SigtermHandlerFailed { error } => error!("Failed to install SIGTERM handler: {error}"),
+            #[cfg(all(feature = "pid1", unix))]
+            ChexEvent::Pid1HandlerFailed { error } => error!("Failed to install PID 1 signal handlers: {error}"),
+            #[cfg(target_os = "linux")]
+            ChexEvent::ParentDeathSignalFailed { error } => error!("Failed to set up the parent death signal: {error}"),
+            ChexEvent::InvalidEnvVar { name, value } => warn!("Ignoring invalid {name}={value:?}"),
+        }
+    }
+}
+
+/*
+ * Sink for embedded targets with std, writing events through `defmt`.  Used by default when the
+ * "defmt" feature is enabled, even if "log" is enabled too.  The feature turns std on.
+ */
+#[cfg(feature = "defmt")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefmtSink;
+
+#[cfg(feature = "defmt")]
+impl ChexSink for DefmtSink {
+    fn on_event(&self, event: ChexEvent<'_>) {
+        use defmt::Display2Format;
+
+        match event {
+            ChexEvent::PanicCaught { info } => {
+                defmt::error!("PANIC: {}", Display2Format(info));
+                defmt::error!("PANIC: signal exit to all Chex listeners");
+            }
+            ChexEvent::PanicBelowThreshold { seen, threshold } => {
+                defmt::warn!("PANIC: {=usize} of {=usize} panics before signalling exit", seen, threshold);
+            }
+            ChexEvent::DefaultPanicHandler => defmt::error!("PANIC: calling default panic handler"),
+            ChexEvent::ExitSignalled { .. } => {}
+            #[allow(deprecated)]
+            ChexEvent::SignalFailed { error } => {
+                defmt::error!("signal_exit failed: {}", Display2Format(error));
+            }
+            ChexEvent::NotInitialized { operation } => {
+                defmt::error!("Failed to initialize Chex before {=str}", operation);
+            }
+            ChexEvent::DeadlineExpired { grace, forced_exit } => {
+                defmt::error!("Exit deadline of {=u64}ms expired, forcing {}",
+                    grace.as_millis() as u64, defmt::Debug2Format(&forced_exit));
+            }
+            ChexEvent::WatchdogMissed { name, interval } => {
+                defmt::error!("Watchdog '{=str}' missed its {=u64}ms heartbeat",
+                    name, interval.as_millis() as u64);
+            }
+            ChexEvent::Straggler { listener, timeout } => {
+                defmt::error!("Listener '{=str}' on thread {=str} has not exited {=u64}ms after exit was signalled",
+                    listener.name(), listener.thread_name().unwrap_or("<unnamed>"), timeout.as_millis() as u64);
+            }
+            ChexEvent::InterceptorExpired { name, max_delay } => {
+                defmt::error!("Interceptor '{=str}' did not release within {=u64}ms, exiting anyway",
+                    name, max_delay.as_millis() as u64);
+            }
+            ChexEvent::Warning { warning } => defmt::warn!("{}", Display2Format(warning)),
+            ChexEvent::ThreadSpawnFailed { thread, error } => {
+                defmt::error!("Failed to spawn {=str} thread: {}", thread, Display2Format(error));
+            }
+            #[cfg(all(feature = "systemd", target_os = "linux"))]
+            ChexEvent::SystemdFailed { action, error } => {
+                defmt::error!("systemd: {=str} failed: {}", action, Display2Format(error));
+            }
+            #[cfg(all(feature = "sigterm", unix))]
+            ChexEvent::SigtermHandlerFailed { error } => {
+                defmt::error!("Failed to install SIGTERM handler: {}", Display2Format(error));
+            }
+            #[cfg(all(feature = "pid1", unix))]
+            ChexEvent::Pid1HandlerFailed { error } => {
+                defmt::error!("Failed to install PID 1 signal handlers: {}", Display2Format(error));
+            }
+            #[cfg(target_os = "linux")]
+            ChexEvent::ParentDeathSignalFailed { error } => {
+                defmt::error!("Failed to set up the parent death signal: {}", Display2Format(error));
+            }
+            ChexEvent::InvalidEnvVar { name, value } => {
+                defmt::warn!("Ignoring invalid {=str}={=str}", name, value);
+            }
+        }
+    }
+}
+
+/// Replace the process-wide sink.
+pub(crate) fn set(sink: Box<dyn ChexSink>) {
+    *SINK.write().unwrap_or_else(|e| e.into_inner()) = Some(sink);
+}
+
+/// Set the most verbose level the default sink reports.
+pub(crate) fn set_level(level: LogLevel) {
+    LEVEL.store(level as u8, Relaxed);
+}
+
+/// Report an event to the installed sink, or to the default sink if none is installed and the
+/// event is within the configured level.
+pub(crate) fn emit(event: ChexEvent<'_>) {
+    match &*SINK.read().unwrap_or_else(|e| e.into_inner()) {
+        Some(sink) => sink.on_event(event),
+        None if event.level() as u8 > LEVEL.load(Relaxed) => {}
+        #[cfg(feature = "defmt")]
+        None => DefmtSink.on_event(event),
+        #[cfg(all(feature = "log", not(feature = "defmt")))]
+        None => LogSink.on_event(event),
+        #[cfg(not(any(feature = "log", feature = "defmt")))]
+        None => {}
+    }
+}
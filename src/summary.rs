@@ -0,0 +1,97 @@
+//! Structured summary of a shutdown.
+
+use crate::{ExitOrigin,ExitReason};
+use crate::listener::ListenerInfo;
+use std::time::Duration;
+
+/*
+ * Returned by Chex::wait_for_shutdown(): one record describing how the shutdown went.
+ */
+#[derive(Clone, Debug)]
+pub struct ExitSummary {
+    pub(crate) exited: bool,
+    pub(crate) origin: Option<ExitOrigin>,
+    pub(crate) reason: Option<ExitReason>,
+    pub(crate) panic_message: Option<String>,
+    pub(crate) completions: Vec<(ListenerInfo, Duration)>,
+    pub(crate) stragglers: Vec<ListenerInfo>,
+    pub(crate) elapsed: Option<Duration>,
+}
+
+impl ExitSummary {
+    /// Whether exit had been signalled by the time the summary was taken.
+    pub fn exited(&self) -> bool {
+        self.exited
+    }
+
+    /// Where exit was first signalled, if known.
+    pub fn origin(&self) -> Option<&ExitOrigin> {
+        self.origin.as_ref()
+    }
+
+    /// Why exit was signalled, if it was.
+    pub fn reason(&self) -> Option<&ExitReason> {
+        self.reason.as_ref()
+    }
+
+    /// Message of the panic that signalled exit, if exit was caused by a panic.
+    pub fn panic_message(&self) -> Option<&str> {
+        self.panic_message.as_deref()
+    }
+
+    /// Registered listeners that acked, with the time from the exit signal to their ack.
+    pub fn completions(&self) -> &[(ListenerInfo, Duration)] {
+        &self.completions
+    }
+
+    /// Registered listeners that never acked.
+    pub fn stragglers(&self) -> &[ListenerInfo] {
+        &self.stragglers
+    }
+
+    /// Time from the exit signal until the summary was taken.
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.elapsed
+    }
+
+    /// True iff exit was signalled and every registered listener acked.
+    pub fn is_complete(&self) -> bool {
+        self.exited && self.stragglers.is_empty()
+    }
+}
+
+impl std::fmt::Display for ExitSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.exited {
+            return write!(f, "exit not signalled");
+        }
+
+        write!(f, "exit signalled")?;
+        if let Some(origin) = &self.origin {
+            write!(f, " at {origin}")?;
+        }
+        if let Some(reason) = &self.reason {
+            write!(f, " by {reason}")?;
+        }
+        if let Some(elapsed) = self.elapsed {
+            write!(f, ", {elapsed:?} ago")?;
+        }
+
+        write!(f, "; completed: [")?;
+        for (i, (l, latency)) in self.completions.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{} in {latency:?}", l.name())?;
+        }
+
+        write!(f, "]; stragglers: [")?;
+        for (i, l) in self.stragglers.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", l.name())?;
+        }
+        write!(f, "]")
+    }
+}
@@ -0,0 +1,51 @@
+//! Synchronization primitives behind the exit flag and its wakeups: std's normally, loom's when
+//! built with `RUSTFLAGS="--cfg loom"`, so that the signal/poll/wait paths can be model-checked
+//! (see tests/loom.rs).
+//!
+//! Only the state of one coordinator goes through here.  Process-wide statics keep using std,
+//! since loom's primitives can't be constructed in a const context.
+
+#[cfg(not(loom))]
+pub(crate) use std::sync::{Condvar,Mutex,OnceLock};
+#[cfg(not(loom))]
+pub(crate) use std::sync::atomic::{AtomicBool,AtomicUsize};
+
+#[cfg(loom)]
+pub(crate) use loom::sync::{Condvar,Mutex};
+#[cfg(loom)]
+pub(crate) use loom::sync::atomic::{AtomicBool,AtomicUsize};
+
+/*
+ * loom has no OnceLock.  The value lives in a std OnceLock, and a loom atomic, raised with
+ * Release once the value is in place and read with Acquire, stands in for the publication
+ * ordering OnceLock provides, so loom sees the happens-before edge readers rely on.
+ */
+#[cfg(loom)]
+pub(crate) struct OnceLock<T> {
+    value: std::sync::OnceLock<T>,
+    set: AtomicBool,
+}
+
+#[cfg(loom)]
+impl<T> OnceLock<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            value: std::sync::OnceLock::new(),
+            set: AtomicBool::new(false),
+        }
+    }
+
+    pub(crate) fn get(&self) -> Option<&T> {
+        if self.set.load(std::sync::atomic::Ordering::Acquire) {
+            self.value.get()
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn set(&self, value: T) -> Result<(), T> {
+        let set = self.value.set(value);
+        self.set.store(true, std::sync::atomic::Ordering::Release);
+        set
+    }
+}
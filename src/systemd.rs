@@ -0,0 +1,128 @@
+//! systemd integration: STOPPING=1 and WATCHDOG=1 notifications during shutdown, and SIGTERM.
+//!
+//! Enabled with the "systemd" feature, on Linux only.  Speaks the sd_notify datagram protocol
+//! directly, so it does not need libsystemd.
+
+use crate::{ChexShared,ExitOrigin};
+use crate::sigterm;
+use crate::sink::{self,ChexEvent};
+use std::os::unix::net::UnixDatagram;
+use std::sync::Arc;
+use std::time::Duration;
+
+/*
+ * What enable_systemd() sets up.  Everything is enabled by default.
+ */
+#[derive(Clone, Debug)]
+pub struct SystemdOptions {
+    notify_stopping: bool,
+    watchdog_during_shutdown: bool,
+    handle_sigterm: bool,
+}
+
+impl Default for SystemdOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SystemdOptions {
+    pub fn new() -> Self {
+        Self {
+            notify_stopping: true,
+            watchdog_during_shutdown: true,
+            handle_sigterm: true,
+        }
+    }
+
+    /// Send STOPPING=1 when exit is first signalled.
+    pub fn notify_stopping(mut self, notify_stopping: bool) -> Self {
+        self.notify_stopping = notify_stopping;
+        self
+    }
+
+    /// Keep sending WATCHDOG=1 (at half of WatchdogSec=) from when exit is signalled until the
+    /// teardown hooks have finished, so a slow teardown is not killed by the service watchdog.
+    pub fn watchdog_during_shutdown(mut self, watchdog_during_shutdown: bool) -> Self {
+        self.watchdog_during_shutdown = watchdog_during_shutdown;
+        self
+    }
+
+    /// Signal exit with ExitReason::OsSignal(15) on SIGTERM, instead of being terminated.
+    pub fn handle_sigterm(mut self, handle_sigterm: bool) -> Self {
+        self.handle_sigterm = handle_sigterm;
+        self
+    }
+}
+
+/// Send state (ie "READY=1") to the service manager.  Returns Ok(false) without sending if the
+/// process is not running under systemd, ie NOTIFY_SOCKET is unset.
+pub fn notify(state: &str) -> std::io::Result<bool> {
+    let Some(path) = std::env::var_os("NOTIFY_SOCKET") else {
+        return Ok(false);
+    };
+    let socket = UnixDatagram::unbound()?;
+    match path.as_encoded_bytes().strip_prefix(b"@") {
+        Some(name) => {
+            use std::os::linux::net::SocketAddrExt;
+            let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+            socket.send_to_addr(state.as_bytes(), &addr)?;
+        }
+        None => {
+            socket.send_to(state.as_bytes(), &path)?;
+        }
+    }
+    Ok(true)
+}
+
+/// The service watchdog interval (WatchdogSec=), if systemd enabled it for this process.
+pub fn watchdog_interval() -> Option<Duration> {
+    let usec: u64 = std::env::var("WATCHDOG_USEC").ok()?.parse().ok()?;
+    if let Ok(pid) = std::env::var("WATCHDOG_PID") {
+        if pid.parse::<u32>().ok()? != std::process::id() {
+            return None;
+        }
+    }
+    (usec > 0).then(|| Duration::from_micros(usec))
+}
+
+fn notify_or_emit(state: &'static str) {
+    if let Err(e) = notify(state) {
+        sink::emit(ChexEvent::SystemdFailed { action: state, error: &e });
+    }
+}
+
+pub(crate) fn install(shared: &Arc<ChexShared>, options: &SystemdOptions, origin: ExitOrigin) {
+    let notify_stopping = options.notify_stopping;
+    let watchdog = options.watchdog_during_shutdown.then(watchdog_interval).flatten();
+    if notify_stopping || watchdog.is_some() {
+        let weak = Arc::downgrade(shared);
+        shared.add_at_exit(Box::new(move || {
+            if notify_stopping {
+                notify_or_emit("STOPPING=1");
+            }
+            if let (Some(interval), Some(shared)) = (watchdog, weak.upgrade()) {
+                spawn_watchdog(shared, interval);
+            }
+        }));
+    }
+
+    if options.handle_sigterm {
+        sigterm::add(Arc::downgrade(shared), origin, &sigterm::SigtermOptions::new());
+    }
+}
+
+/// Ping the service watchdog until teardown has finished.
+fn spawn_watchdog(shared: Arc<ChexShared>, interval: Duration) {
+    let spawned = std::thread::Builder::new()
+        .name("chex-systemd-watchdog".to_string())
+        .spawn(move || loop {
+            notify_or_emit("WATCHDOG=1");
+            if shared.teardown.wait(interval / 2) {
+                break;
+            }
+        });
+    if let Err(e) = spawned {
+        sink::emit(ChexEvent::ThreadSpawnFailed { thread: "chex-systemd-watchdog", error: &e });
+    }
+}
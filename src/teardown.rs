@@ -0,0 +1,159 @@
+//! Teardown callbacks run in priority order once exit is signalled.
+//!
+//! With the "tokio" feature, async hooks registered with on_exit_async() run on the teardown
+//! thread too, blocking it until their future completes.
+
+use crate::Instant;
+use crate::sink::{self,ChexEvent};
+use std::sync::{Arc,Condvar,Mutex};
+use std::time::Duration;
+
+type TeardownHook = Box<dyn FnOnce() + Send + 'static>;
+
+/*
+ * Where on_exit_async() runs its future, on the teardown thread.
+ */
+#[cfg(feature = "tokio")]
+#[derive(Clone, Debug)]
+pub enum AsyncExecutor {
+    /// The tokio runtime which was current when the hook was registered.  Registering outside
+    /// a runtime panics.
+    Current,
+    /// The given tokio runtime.
+    Handle(tokio::runtime::Handle),
+    /// A single-threaded tokio runtime built for the hook.
+    Dedicated,
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncExecutor {
+    /// Turn an async hook into a teardown hook which blocks the teardown thread until the
+    /// future completes.
+    pub(crate) fn into_hook<F, Fut>(self, factory: F) -> TeardownHook
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = ()> + 'static,
+    {
+        let handle = match self {
+            AsyncExecutor::Current => Some(tokio::runtime::Handle::current()),
+            AsyncExecutor::Handle(handle) => Some(handle),
+            AsyncExecutor::Dedicated => None,
+        };
+        Box::new(move || match handle {
+            Some(handle) => handle.block_on(factory()),
+            None => {
+                let runtime = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build();
+                match runtime {
+                    Ok(runtime) => runtime.block_on(factory()),
+                    Err(e) => sink::emit(ChexEvent::ThreadSpawnFailed { thread: "tokio runtime", error: &e }),
+                }
+            }
+        })
+    }
+}
+
+/*
+ * Teardown hooks registered with one ChexShared.
+ */
+pub(crate) struct TeardownRegistry {
+    state: Mutex<TeardownState>,
+    done_cv: Condvar,
+}
+
+struct TeardownState {
+    hooks: Vec<(i32, TeardownHook)>,
+    started: bool,
+    running: usize,
+}
+
+impl TeardownRegistry {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: Mutex::new(TeardownState {
+                hooks: Vec::new(),
+                started: false,
+                running: 0,
+            }),
+            done_cv: Condvar::new(),
+        }
+    }
+
+    /// Register hook at priority.  Hooks registered after teardown started run right away, on
+    /// their own thread.
+    pub(crate) fn add(self: &Arc<Self>, priority: i32, hook: TeardownHook) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.started {
+            state.running += 1;
+            drop(state);
+            self.spawn(vec![hook]);
+        } else {
+            state.hooks.push((priority, hook));
+        }
+    }
+
+    /// Called once when exit is first signalled: run every hook on the teardown thread, lowest
+    /// priority first, and in registration order within a priority.
+    pub(crate) fn start(self: &Arc<Self>) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.started {
+            return;
+        }
+        state.started = true;
+
+        let mut hooks = std::mem::take(&mut state.hooks);
+        if hooks.is_empty() {
+            return;
+        }
+        hooks.sort_by_key(|(priority, _)| *priority);
+        state.running += 1;
+        drop(state);
+        self.spawn(hooks.into_iter().map(|(_, hook)| hook).collect());
+    }
+
+    fn spawn(self: &Arc<Self>, hooks: Vec<TeardownHook>) {
+        let registry = self.clone();
+        let spawned = std::thread::Builder::new()
+            .name("chex-teardown".to_string())
+            .spawn(move || registry.run(hooks));
+        if let Err(e) = spawned {
+            sink::emit(ChexEvent::ThreadSpawnFailed { thread: "chex-teardown", error: &e });
+            self.finished();
+        }
+    }
+
+    fn run(&self, hooks: Vec<TeardownHook>) {
+        /*
+         * A panicking hook must not prevent the later stages from running.
+         */
+        for hook in hooks {
+            let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(hook));
+        }
+        self.finished();
+    }
+
+    fn finished(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.running -= 1;
+        self.done_cv.notify_all();
+    }
+
+    /// Wait until teardown has started and every hook has run, or until timeout.
+    pub(crate) fn wait(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            if state.started && state.running == 0 {
+                return true;
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return false;
+            }
+            state = self.done_cv.wait_timeout(state, deadline - now)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Optional instrumentation of exit signalling.
+//!
+//! With the "metrics" feature, metrics are emitted through the metrics facade:
+//! - chex_exits_signalled_total (counter, labelled by reason)
+//! - chex_live_instances (gauge)
+//! - chex_exit_wakeup_seconds (histogram of time from exit signal to a waiting listener waking)
+//!
+//! With the "tracing" feature, structured tracing events are emitted alongside the log
+//! messages, and a "shutdown" span covers the interval from the first exit signal until the
+//! last registered listener acks.
+
+use crate::{ExitOrigin,ExitReason};
+use std::time::Duration;
+
+/*
+ * The "shutdown" span, held open until the last registered listener acks.
+ */
+pub(crate) struct ShutdownSpan {
+    #[cfg(feature = "tracing")]
+    span: std::sync::Mutex<Option<tracing::Span>>,
+}
+
+impl ShutdownSpan {
+    pub(crate) fn new() -> Self {
+        Self {
+            #[cfg(feature = "tracing")]
+            span: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// Exit was signalled for the first time.
+    pub(crate) fn start(&self, reason: &ExitReason, origin: Option<&ExitOrigin>) {
+        #[cfg(feature = "tracing")]
+        {
+            let reason = reason.as_str();
+            let origin = origin.map(|o| o.to_string());
+            let span = tracing::info_span!("shutdown", reason, origin);
+            span.in_scope(|| tracing::warn!(reason, origin, "exit signalled"));
+            *self.span.lock().unwrap_or_else(|e| e.into_inner()) = Some(span);
+        }
+        #[cfg(not(feature = "tracing"))]
+        let _ = (reason, origin);
+    }
+
+    /// A registered listener acked, latency after exit was signalled.
+    pub(crate) fn listener_acked(&self, name: &str, latency: Duration) {
+        #[cfg(feature = "tracing")]
+        if let Some(span) = &*self.span.lock().unwrap_or_else(|e| e.into_inner()) {
+            span.in_scope(|| tracing::info!(listener = name, ?latency, "listener acked"));
+        }
+        #[cfg(not(feature = "tracing"))]
+        let _ = (name, latency);
+    }
+
+    /// The last registered listener acked: close the span.
+    pub(crate) fn finish(&self) {
+        #[cfg(feature = "tracing")]
+        if let Some(span) = self.span.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            span.in_scope(|| tracing::info!("all listeners acked"));
+        }
+    }
+}
+
+/// Exit was signalled for the first time.
+pub(crate) fn exit_signalled(reason: &ExitReason) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!("chex_exits_signalled_total", "reason" => reason.as_str()).increment(1);
+    #[cfg(not(feature = "metrics"))]
+    let _ = reason;
+}
+
+/// The exit-on-panic hook caught a panic that signals exit.
+pub(crate) fn panic_caught(message: &str, location: Option<&str>) {
+    #[cfg(feature = "tracing")]
+    tracing::error!(message, location, "panic caught, signalling exit");
+    #[cfg(not(feature = "tracing"))]
+    let _ = (message, location);
+}
+
+/// A heartbeat watchdog missed its deadline.
+pub(crate) fn watchdog_missed(name: &str, interval: Duration) {
+    #[cfg(feature = "tracing")]
+    tracing::error!(watchdog = name, ?interval, "watchdog missed heartbeat");
+    #[cfg(not(feature = "tracing"))]
+    let _ = (name, interval);
+}
+
+/// A registered listener had not acked timeout after exit was signalled.
+pub(crate) fn straggler(name: &str, thread_name: Option<&str>, timeout: Duration) {
+    #[cfg(feature = "tracing")]
+    tracing::error!(listener = name, thread = thread_name, ?timeout, "listener has not exited");
+    #[cfg(not(feature = "tracing"))]
+    let _ = (name, thread_name, timeout);
+}
+
+/// A ChexInstance was created or cloned.
+pub(crate) fn instance_created() {
+    #[cfg(feature = "metrics")]
+    metrics::gauge!("chex_live_instances").increment(1.0);
+}
+
+/// A ChexInstance was dropped.
+pub(crate) fn instance_dropped() {
+    #[cfg(feature = "metrics")]
+    metrics::gauge!("chex_live_instances").decrement(1.0);
+}
+
+/// A listener waiting in check_exit_async() or block_until_exit() woke up latency after exit
+/// was signalled.
+pub(crate) fn listener_woke(latency: Duration) {
+    #[cfg(feature = "metrics")]
+    metrics::histogram!("chex_exit_wakeup_seconds").record(latency.as_secs_f64());
+    #[cfg(not(feature = "metrics"))]
+    let _ = latency;
+}
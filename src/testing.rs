@@ -0,0 +1,59 @@
+//! Serializing tests which touch the global Chex, and resetting it between them, see
+//! Chex::test_lock().
+//!
+//! Enabled with the "test-util" feature, ie as a dev-dependency feature.  Each reset starts a
+//! new generation: a fresh coordinator is leaked and takes over from the previous one, which is
+//! left behind since ChexInstances and &'static references may still point into it.  That costs
+//! one coordinator (and whatever was registered on it) per reset, which is fine for a test
+//! binary, and keeps looking up the global Chex down to one atomic load.
+#![allow(unsafe_code)]
+
+use crate::ChexInstance;
+use std::sync::{Mutex,MutexGuard};
+use std::sync::atomic::AtomicPtr;
+use std::sync::atomic::Ordering::{Acquire,Release};
+
+static LOCK: Mutex<()> = Mutex::new(());
+
+/*
+ * The coordinator of the latest generation, or null if the global Chex was never reset.  Only
+ * ever points to a leaked Box, so it stays valid for the rest of the process.
+ */
+static CURRENT: AtomicPtr<ChexInstance> = AtomicPtr::new(std::ptr::null_mut());
+
+/*
+ * Held by a test using the global Chex.  Dropping it resets the global Chex and lets the next
+ * test run.
+ */
+#[must_use = "the lock is released (and the global Chex reset) when the guard is dropped"]
+pub struct TestLock {
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl Drop for TestLock {
+    fn drop(&mut self) {
+        crate::Chex::reset_generation();
+    }
+}
+
+/// Take the lock.  A test which panicked while holding it still reset the global Chex on the
+/// way out, so poisoning is ignored.
+pub(crate) fn lock() -> TestLock {
+    TestLock {
+        _guard: LOCK.lock().unwrap_or_else(|e| e.into_inner()),
+    }
+}
+
+pub(crate) fn current() -> Option<&'static ChexInstance> {
+    let ptr = CURRENT.load(Acquire);
+    if ptr.is_null() {
+        return None;
+    }
+    // SAFETY: never freed, see CURRENT.
+    Some(unsafe { &*ptr })
+}
+
+/// Make instance the coordinator of a new generation.
+pub(crate) fn start_generation(instance: ChexInstance) {
+    CURRENT.store(Box::into_raw(Box::new(instance)), Release);
+}
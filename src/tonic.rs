@@ -0,0 +1,52 @@
+//! Graceful shutdown for tonic gRPC servers: the future for `Server::serve_with_shutdown()`, and
+//! gRPC health reporting (tonic-health) which turns NOT_SERVING while the server drains.
+//!
+//! Enabled with the "tonic" feature, for tonic and tonic-health 0.14.
+
+use crate::ChexInstance;
+use std::future::Future;
+use tonic_health::ServingStatus;
+use tonic_health::server::HealthReporter;
+
+/// Resolves once draining begins or exit is signalled on the global Chex, for
+/// `Server::builder().add_service(svc).serve_with_shutdown(addr, chex::tonic::shutdown_signal())`.
+///
+/// Panics if Chex is not initialized.
+pub fn shutdown_signal() -> impl Future<Output = ()> + Send + 'static {
+    shutdown_signal_for(crate::Chex::get_chex_instance())
+}
+
+/// Like shutdown_signal(), for the coordinator of ci.
+///
+/// tonic then starts its graceful shutdown: every connection gets an HTTP/2 GOAWAY, so clients
+/// stop opening new streams here and reconnect elsewhere, while in-flight RPCs finish.
+/// Resolving on a soft exit (start_draining(), or a SIGTERM pre-stop delay) rather than only on
+/// exit gives clients the whole drain period to move away.
+pub async fn shutdown_signal_for(ci: ChexInstance) {
+    ci.wait_draining().await
+}
+
+/// The future for serve_with_shutdown() when health checks decide where traffic goes, ie a
+/// Kubernetes gRPC readiness probe or a health-checking load balancer.  Once draining begins (or
+/// exit is signalled) on ci, the whole server (service "") and every service in services are
+/// set to NOT_SERVING on reporter, while the server keeps serving; the future resolves, and tonic
+/// starts its graceful shutdown, only once exit is signalled.
+///
+/// reporter is the HealthReporter from `tonic_health::server::health_reporter()`, whose
+/// HealthServer is added to the same server.
+pub fn shutdown_signal_with_health<I, S>(ci: ChexInstance, reporter: HealthReporter, services: I) -> impl Future<Output = ()> + Send + 'static
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let services: Vec<String> = services.into_iter().map(|s| s.as_ref().to_string()).collect();
+    async move {
+        let mut ci = ci;
+        ci.wait_draining().await;
+        reporter.set_service_status("", ServingStatus::NotServing).await;
+        for service in &services {
+            reporter.set_service_status(service, ServingStatus::NotServing).await;
+        }
+        ci.check_exit_async().await
+    }
+}
@@ -0,0 +1,155 @@
+//! tower middleware which turns away new requests once shutdown has begun.
+//!
+//! Enabled with the "tower" feature.  Each admitted request holds a work permit until its
+//! response future completes, so wait_for_work_permits() waits for in-flight requests.
+
+use crate::ChexInstance;
+use crate::quiesce::WorkPermit;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context,Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/*
+ * Builds the response for a request turned away during shutdown.
+ */
+pub trait Reject<Response> {
+    fn reject(&self) -> Response;
+}
+
+/*
+ * The default rejection: an empty 503 Service Unavailable.
+ */
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ServiceUnavailable;
+
+impl<B: Default> Reject<::http::Response<B>> for ServiceUnavailable {
+    fn reject(&self) -> ::http::Response<B> {
+        let mut response = ::http::Response::new(B::default());
+        *response.status_mut() = ::http::StatusCode::SERVICE_UNAVAILABLE;
+        response
+    }
+}
+
+impl<F, R> Reject<R> for F
+where
+    F: Fn() -> R,
+{
+    fn reject(&self) -> R {
+        self()
+    }
+}
+
+/*
+ * Layer which rejects new requests once exit has been signalled (or is held off by an
+ * interceptor), and lets in-flight requests complete.  Requests are still served during a
+ * SIGTERM pre-stop delay, since the load balancer may still route traffic here.
+ */
+#[derive(Clone, Debug)]
+pub struct ChexLayer<J = ServiceUnavailable> {
+    chex: ChexInstance,
+    reject: J,
+}
+
+impl ChexLayer {
+    /// Reject with an empty 503 Service Unavailable.
+    pub fn new(chex: ChexInstance) -> Self {
+        Self {
+            chex,
+            reject: ServiceUnavailable,
+        }
+    }
+}
+
+impl<J> ChexLayer<J> {
+    /// Reject with the response built by reject instead, ie a 503 with a Retry-After header.
+    pub fn with_response<K>(self, reject: K) -> ChexLayer<K> {
+        ChexLayer {
+            chex: self.chex,
+            reject,
+        }
+    }
+}
+
+impl<S, J: Clone> Layer<S> for ChexLayer<J> {
+    type Service = ChexService<S, J>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ChexService {
+            inner,
+            chex: self.chex.clone(),
+            reject: self.reject.clone(),
+        }
+    }
+}
+
+/*
+ * Service produced by ChexLayer.
+ */
+#[derive(Clone, Debug)]
+pub struct ChexService<S, J> {
+    inner: S,
+    chex: ChexInstance,
+    reject: J,
+}
+
+impl<S, J, Req> Service<Req> for ChexService<S, J>
+where
+    S: Service<Req>,
+    J: Reject<S::Response>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future, S::Response>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Req) -> Self::Future {
+        match self.chex.acquire_work_permit() {
+            Some(permit) => ResponseFuture {
+                inner: Some(self.inner.call(req)),
+                rejected: None,
+                permit: Some(permit),
+            },
+            None => ResponseFuture {
+                inner: None,
+                rejected: Some(self.reject.reject()),
+                permit: None,
+            },
+        }
+    }
+}
+
+pin_project_lite::pin_project! {
+    /*
+     * Response future of ChexService.
+     */
+    pub struct ResponseFuture<F, R> {
+        #[pin]
+        inner: Option<F>,
+        rejected: Option<R>,
+        permit: Option<WorkPermit>,
+    }
+}
+
+impl<F, R, E> Future for ResponseFuture<F, R>
+where
+    F: Future<Output = Result<R, E>>,
+{
+    type Output = Result<R, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        match this.inner.as_pin_mut() {
+            Some(inner) => {
+                let output = std::task::ready!(inner.poll(cx));
+                this.permit.take();
+                Poll::Ready(output)
+            }
+            None => Poll::Ready(Ok(this.rejected.take().expect("ResponseFuture polled after completion"))),
+        }
+    }
+}
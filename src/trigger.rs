@@ -0,0 +1,32 @@
+//! Custom exit conditions (queue depth, disk space, license expiry...) packaged as reusable
+//! triggers, installed with ChexInstance::install_trigger() or Chex::install_trigger().
+
+use crate::ChexSignaler;
+use crate::sink::{self,ChexEvent};
+
+/*
+ * A condition which signals exit.  watch() runs on its own "chex-trigger" thread, and should
+ * return once the condition has fired or exit was signalled by someone else (see
+ * ChexSignaler::poll_exit()).
+ */
+pub trait ExitTrigger: Send + 'static {
+    fn watch(self, signaler: ChexSignaler);
+}
+
+impl<F> ExitTrigger for F
+where
+    F: FnOnce(ChexSignaler) + Send + 'static,
+{
+    fn watch(self, signaler: ChexSignaler) {
+        self(signaler)
+    }
+}
+
+pub(crate) fn install(trigger: impl ExitTrigger, signaler: ChexSignaler) {
+    let spawned = std::thread::Builder::new()
+        .name("chex-trigger".to_string())
+        .spawn(move || trigger.watch(signaler));
+    if let Err(e) = spawned {
+        sink::emit(ChexEvent::ThreadSpawnFailed { thread: "chex-trigger", error: &e });
+    }
+}
@@ -0,0 +1,103 @@
+//! Non-fatal process-wide warnings, broadcast alongside (but independently of) the exit signal.
+
+use crate::ExitOrigin;
+use crate::sink::{self,ChexEvent};
+use async_broadcast::{InactiveReceiver,Receiver,RecvError,Sender,TryRecvError};
+
+/*
+ * Warnings buffered per subscriber.  Slow subscribers lose the oldest warnings.
+ */
+const CAPACITY: usize = 16;
+
+/*
+ * A non-fatal condition (ie degraded mode, low disk) reported with signal_warning().
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChexWarning {
+    message: String,
+    origin: ExitOrigin,
+}
+
+impl ChexWarning {
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Where signal_warning() was called.
+    pub fn origin(&self) -> &ExitOrigin {
+        &self.origin
+    }
+}
+
+impl std::fmt::Display for ChexWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (at {})", self.message, self.origin)
+    }
+}
+
+/*
+ * Warning broadcast shared by every clone of a ChexInstance.
+ */
+pub(crate) struct WarningChannel {
+    sender: Sender<ChexWarning>,
+    _open: InactiveReceiver<ChexWarning>,
+}
+
+impl WarningChannel {
+    pub(crate) fn new() -> Self {
+        let (mut sender, receiver) = async_broadcast::broadcast(CAPACITY);
+        sender.set_overflow(true);
+        Self {
+            sender,
+            _open: receiver.deactivate(),
+        }
+    }
+
+    pub(crate) fn subscribe(&self) -> WarningSubscription {
+        WarningSubscription {
+            rx: self.sender.new_receiver(),
+        }
+    }
+
+    pub(crate) fn send(&self, message: String, origin: ExitOrigin) {
+        let warning = ChexWarning { message, origin };
+        sink::emit(ChexEvent::Warning { warning: &warning });
+        /*
+         * This can only fail if nobody is subscribed, in which case there is nobody to tell.
+         */
+        let _ = self.sender.try_broadcast(warning);
+    }
+}
+
+/*
+ * Handle returned by subscribe_warnings().  Receives every warning signalled after it was
+ * created; poll_exit() and the exit signal are unaffected by warnings.
+ */
+pub struct WarningSubscription {
+    rx: Receiver<ChexWarning>,
+}
+
+impl WarningSubscription {
+    /// Wait for the next warning.  Returns None once every ChexInstance of the coordinator has
+    /// been dropped.
+    pub async fn recv(&mut self) -> Option<ChexWarning> {
+        loop {
+            match self.rx.recv().await {
+                Ok(warning) => return Some(warning),
+                Err(RecvError::Overflowed(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// The next warning, if one is pending.
+    pub fn try_recv(&mut self) -> Option<ChexWarning> {
+        loop {
+            match self.rx.try_recv() {
+                Ok(warning) => return Some(warning),
+                Err(TryRecvError::Overflowed(_)) => continue,
+                Err(_) => return None,
+            }
+        }
+    }
+}
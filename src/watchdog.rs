@@ -0,0 +1,166 @@
+//! Heartbeat watchdogs that signal exit when a worker stops making progress.
+
+use crate::{ChexShared,ExitOrigin,ExitReason,ExitReasonKind};
+use crate::clock::Clock;
+use crate::sink::{self,ChexEvent};
+use crate::telemetry;
+use std::panic::Location;
+use std::sync::{Arc,Mutex,OnceLock,RwLock,Weak};
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::Relaxed;
+use crate::Instant;
+use std::time::Duration;
+
+type WatchdogPolicy = Box<dyn Fn(&str, Duration) + Sync + Send + 'static>;
+
+/*
+ * Longest the monitor thread sleeps between checks.
+ */
+const MAX_TICK: Duration = Duration::from_millis(100);
+
+/*
+ * Watchdogs registered with one ChexShared, and the thread monitoring them.
+ */
+pub(crate) struct WatchdogRegistry {
+    dogs: Mutex<Vec<Weak<WatchdogState>>>,
+    monitor: OnceLock<()>,
+    policy: RwLock<Option<WatchdogPolicy>>,
+}
+
+struct WatchdogState {
+    name: String,
+    interval: Duration,
+    location: &'static Location<'static>,
+    clock: Arc<dyn Clock>,
+    last_beat: Mutex<Instant>,
+    missed: AtomicBool,
+}
+
+/*
+ * Handle returned by Chex::watchdog().  Call beat() at least once per interval; dropping the
+ * handle unregisters the watchdog.
+ */
+pub struct Watchdog {
+    state: Arc<WatchdogState>,
+}
+
+impl Watchdog {
+    /// Record that the watched worker is still making progress.
+    pub fn beat(&self) {
+        *self.state.last_beat.lock().unwrap_or_else(|e| e.into_inner()) = self.state.clock.now();
+        self.state.missed.store(false, Relaxed);
+    }
+
+    pub fn name(&self) -> &str {
+        &self.state.name
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.state.interval
+    }
+}
+
+impl WatchdogRegistry {
+    pub(crate) fn new() -> Self {
+        Self {
+            dogs: Mutex::new(Vec::new()),
+            monitor: OnceLock::new(),
+            policy: RwLock::new(None),
+        }
+    }
+
+    pub(crate) fn set_policy(&self, policy: WatchdogPolicy) {
+        *self.policy.write().unwrap_or_else(|e| e.into_inner()) = Some(policy);
+    }
+
+    /// Number of live watchdogs.
+    pub(crate) fn count(&self) -> usize {
+        self.dogs.lock().unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|d| d.strong_count() > 0)
+            .count()
+    }
+
+    /// Register a new watchdog, starting the monitor thread if needed.
+    pub(crate) fn register(
+        shared: &Arc<ChexShared>,
+        name: &str,
+        interval: Duration,
+        location: &'static Location<'static>,
+    ) -> Watchdog {
+        let clock = shared.config.timer_clock();
+        let state = Arc::new(WatchdogState {
+            name: name.to_string(),
+            interval,
+            location,
+            last_beat: Mutex::new(clock.now()),
+            clock,
+            missed: AtomicBool::new(false),
+        });
+
+        let registry = &shared.watchdogs;
+        registry.dogs.lock().unwrap_or_else(|e| e.into_inner()).push(Arc::downgrade(&state));
+        registry.monitor.get_or_init(|| {
+            let clock = shared.config.timer_clock();
+            let shared = Arc::downgrade(shared);
+            let spawned = std::thread::Builder::new()
+                .name("chex-watchdog".to_string())
+                .spawn(move || monitor(shared, clock));
+            if let Err(e) = spawned {
+                sink::emit(ChexEvent::ThreadSpawnFailed { thread: "chex-watchdog", error: &e });
+            }
+        });
+
+        Watchdog { state }
+    }
+
+    /// Check all live watchdogs, returning how long to sleep before checking again.
+    fn check(&self, shared: &ChexShared) -> Duration {
+        let mut missed: Vec<Arc<WatchdogState>> = Vec::new();
+        let mut tick = MAX_TICK;
+
+        {
+            let mut dogs = self.dogs.lock().unwrap_or_else(|e| e.into_inner());
+            dogs.retain(|d| d.strong_count() > 0);
+
+            let now = shared.config.timer_clock().now();
+            for dog in dogs.iter().filter_map(Weak::upgrade) {
+                tick = tick.min(dog.interval / 4);
+                let last_beat = *dog.last_beat.lock().unwrap_or_else(|e| e.into_inner());
+                if now.duration_since(last_beat) > dog.interval && !dog.missed.swap(true, Relaxed) {
+                    missed.push(dog);
+                }
+            }
+        }
+
+        for dog in missed {
+            telemetry::watchdog_missed(&dog.name, dog.interval);
+            sink::emit(ChexEvent::WatchdogMissed { name: &dog.name, interval: dog.interval });
+            shared.errors.record(
+                ExitReasonKind::Watchdog,
+                format!("watchdog '{}' missed its {:?} heartbeat", dog.name, dog.interval),
+                ExitOrigin::from_location(dog.location),
+            );
+            match &*self.policy.read().unwrap_or_else(|e| e.into_inner()) {
+                Some(policy) => policy(&dog.name, dog.interval),
+                None => {
+                    let reason = ExitReason::Watchdog { name: dog.name.clone() };
+                    shared.signal_exit_from(reason, ExitOrigin::from_location(dog.location));
+                }
+            }
+        }
+
+        tick.max(Duration::from_millis(1))
+    }
+}
+
+/// Body of the monitor thread.  Runs until exit is signalled or the coordinator is dropped.
+fn monitor(shared: Weak<ChexShared>, clock: Arc<dyn Clock>) {
+    loop {
+        let tick = match shared.upgrade() {
+            Some(shared) if !shared.poll_exit() => shared.watchdogs.check(&shared),
+            _ => return,
+        };
+        clock.sleep(tick);
+    }
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "actix")]
+
+use actix_web::{App,HttpServer};
+use actix_web::dev::Server;
+use chex::{Chex,ChexConfig};
+use std::time::Duration;
+
+fn server() -> Server {
+    HttpServer::new(App::new)
+        .disable_signals()
+        .workers(1)
+        .shutdown_timeout(1)
+        .bind(("127.0.0.1", 0))
+        .expect("bind")
+        .run()
+}
+
+#[test]
+fn server_and_chex_stop_together() {
+    /*
+     * chex exit stops the server.
+     */
+    let chex = Chex::new_local(ChexConfig::new());
+    let signaller = chex.clone();
+    actix_web::rt::System::new().block_on(async {
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            signaller.signal_exit();
+        });
+        chex::actix::run_server(chex.clone(), server(), true).await.expect("server");
+    });
+
+    /*
+     * A server stopping on its own signals chex exit.
+     */
+    let chex = Chex::new_local(ChexConfig::new());
+    actix_web::rt::System::new().block_on(async {
+        let server = server();
+        let handle = server.handle();
+        actix_web::rt::spawn(async move { handle.stop(false).await });
+        chex::actix::run_server(chex.clone(), server, false).await.expect("server");
+    });
+    assert!(chex.poll_exit());
+    assert_eq!(chex.exit_origin().expect("origin").file(), file!());
+}
@@ -0,0 +1,47 @@
+#![cfg(feature = "admin")]
+
+use chex::Chex;
+use chex::admin::AdminAddr;
+use std::io::{BufRead,BufReader,Write};
+use std::net::{TcpListener,TcpStream};
+
+fn response(reader: &mut impl BufRead) -> String {
+    let mut response = String::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        if line == "\n" || line.is_empty() {
+            return response;
+        }
+        response.push_str(&line);
+    }
+}
+
+#[test]
+fn admin_commands_drive_shutdown() {
+    let chex = Chex::init(false);
+    assert!(chex::admin::serve(AdminAddr::Tcp("0.0.0.0:0".parse().unwrap())).is_err());
+
+    let addr = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    chex::admin::serve(AdminAddr::Tcp(addr)).unwrap();
+    let mut stream = TcpStream::connect(addr).unwrap();
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+    stream.write_all(b"status\n").unwrap();
+    assert!(response(&mut reader).contains("draining: false"));
+
+    stream.write_all(b"soft-exit\n").unwrap();
+    assert_eq!(response(&mut reader), "ok: draining\n");
+    assert!(chex.is_draining());
+    assert!(!chex.poll_exit());
+
+    stream.write_all(b"bogus\n").unwrap();
+    assert_eq!(response(&mut reader), "error: unknown command 'bogus'\n");
+
+    stream.write_all(b"exit\n").unwrap();
+    assert_eq!(response(&mut reader), "ok: exit signalled\n");
+    assert!(chex.poll_exit());
+
+    stream.write_all(b"status\n").unwrap();
+    assert!(response(&mut reader).contains("exit signalled: true"));
+}
@@ -0,0 +1,29 @@
+#![cfg(all(feature = "process", unix))]
+
+use chex::{Chex,ChexConfig};
+use chex::process::ChildOptions;
+use std::io::{BufRead,BufReader};
+use std::process::{Command,Stdio};
+use std::time::{Duration,Instant};
+
+#[test]
+fn adopted_children_are_terminated_on_exit() {
+    let ci = Chex::new_local(ChexConfig::new());
+    let polite = Command::new("sleep").arg("60").spawn().unwrap();
+    let mut stubborn = Command::new("sh")
+        .args(["-c", "trap '' TERM; echo ready; exec sleep 60"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut line = String::new();
+    BufReader::new(stubborn.stdout.take().unwrap()).read_line(&mut line).unwrap();
+    assert_eq!(line, "ready\n");
+    ci.adopt_child(polite, ChildOptions::new());
+    ci.adopt_child(stubborn, ChildOptions::new().grace(Duration::from_millis(200)));
+
+    let start = Instant::now();
+    ci.signal_exit();
+    assert!(ci.wait_for_teardown(Duration::from_secs(10)));
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
@@ -0,0 +1,28 @@
+#![cfg(feature = "tokio")]
+
+use chex::{Chex,ChexConfig};
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::Relaxed;
+use std::time::Duration;
+
+#[test]
+fn adopted_runtimes_shut_down_after_grace() {
+    let chex = Chex::new_local(ChexConfig::new());
+    let finished = Arc::new(AtomicUsize::new(0));
+
+    for _ in 0..2 {
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let handle = chex.adopt_runtime(runtime, Duration::from_millis(50), Duration::from_secs(1));
+
+        let finished = finished.clone();
+        handle.spawn_blocking(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            finished.fetch_add(1, Relaxed);
+        });
+    }
+
+    chex.signal_exit();
+    assert!(chex.wait_for_teardown(Duration::from_secs(5)));
+    assert_eq!(finished.load(Relaxed), 2);
+}
@@ -0,0 +1,28 @@
+#![cfg(feature = "std")]
+
+use chex::Chex;
+use std::sync::{Arc,Mutex};
+
+#[test]
+fn at_exit_hooks_run_lifo_before_signal_returns() {
+    let chex: &Chex = Chex::init(false);
+    let order: Arc<Mutex<Vec<&'static str>>> = Arc::new(Mutex::new(Vec::new()));
+    let main_thread = std::thread::current().id();
+
+    for name in ["first", "second", "third"] {
+        let order = order.clone();
+        let ci = chex.get_instance();
+        chex.at_exit(move || {
+            assert_eq!(std::thread::current().id(), main_thread);
+            assert!(!ci.poll_exit());
+            order.lock().unwrap().push(name);
+        });
+    }
+
+    assert!(chex.signal_exit());
+    assert_eq!(*order.lock().unwrap(), ["third", "second", "first"]);
+
+    let late = order.clone();
+    chex.at_exit(move || late.lock().unwrap().push("late"));
+    assert_eq!(order.lock().unwrap().last(), Some(&"late"));
+}
@@ -0,0 +1,39 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool,Ordering::SeqCst};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/*
+ * A second signaller arriving while the first one's at-exit hook runs must not let anyone see
+ * the exit before the hook is done.
+ */
+#[test]
+fn concurrent_signal_waits_for_at_exit_hooks() {
+    let ci = Chex::new_local(ChexConfig::new());
+    let hook_done = Arc::new(AtomicBool::new(false));
+    let (started_tx, started) = mpsc::channel();
+    ci.at_exit({
+        let hook_done = hook_done.clone();
+        move || {
+            let _ = started_tx.send(());
+            std::thread::sleep(Duration::from_millis(200));
+            hook_done.store(true, SeqCst);
+        }
+    });
+
+    let second = std::thread::spawn({
+        let ci = ci.clone();
+        let hook_done = hook_done.clone();
+        move || {
+            started.recv().unwrap();
+            assert!(!ci.signal_exit());
+            assert!(ci.poll_exit());
+            hook_done.load(SeqCst)
+        }
+    });
+    assert!(ci.signal_exit());
+    assert!(second.join().unwrap(), "second signaller returned before the at-exit hook finished");
+}
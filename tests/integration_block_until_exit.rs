@@ -0,0 +1,33 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexInstance};
+use std::time::Duration;
+
+#[test]
+fn block_until_exit_parks_main() {
+    let chex: &Chex = Chex::init(false);
+    assert!(!chex.block_until_exit_timeout(Duration::from_millis(10)));
+
+    let ci: ChexInstance = chex.get_instance();
+    let waiter = std::thread::Builder::new().spawn({
+        let ci = ci.clone();
+        move || {
+            ci.block_until_exit();
+            assert!(ci.poll_exit());
+        }
+    }).expect("Failed to spawn thread");
+
+    let signaller = std::thread::Builder::new().spawn({
+        move || {
+            std::thread::sleep(Duration::from_millis(20));
+            ci.signal_exit();
+        }
+    }).expect("Failed to spawn thread");
+
+    chex.block_until_exit();
+    assert!(chex.poll_exit());
+    assert!(chex.block_until_exit_timeout(Duration::from_millis(10)));
+
+    waiter.join().expect("waiter panicked");
+    signaller.join().expect("signaller panicked");
+}
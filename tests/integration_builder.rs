@@ -0,0 +1,32 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ExitReasonKind};
+use chex::panic::PanicHookMode;
+use chex::sink::ChexEvent;
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use std::time::Duration;
+
+#[test]
+fn builder_initializes_with_settings() {
+    let signalled = Arc::new(AtomicUsize::new(0));
+    let seen = signalled.clone();
+    let chex = Chex::builder()
+        .exit_on_panic(true)
+        .grace(Duration::from_secs(3600))
+        .panic_hook_mode(PanicHookMode::Quiet)
+        .sink(move |event: ChexEvent<'_>| {
+            if matches!(event, ChexEvent::ExitSignalled { .. }) {
+                seen.fetch_add(1, SeqCst);
+            }
+        })
+        .init();
+    assert!(std::ptr::eq(chex, Chex::init(false)));
+
+    let ci = chex.get_instance();
+    assert!(std::thread::spawn(|| panic!("boom")).join().is_err());
+    assert!(ci.block_until_exit_timeout(Duration::from_secs(10)));
+    assert_eq!(ci.exit_reason().map(|r| r.kind()), Some(ExitReasonKind::Panic));
+    assert_eq!(signalled.load(SeqCst), 1);
+}
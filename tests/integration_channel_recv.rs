@@ -0,0 +1,22 @@
+#![cfg(feature = "tokio")]
+
+use chex::{Chex,ChexConfig,ChexExited};
+use chex::channel::{self,ExitAwareReceiver};
+
+#[tokio::test]
+async fn receives_until_exit() {
+    let mut ci = Chex::new_local(ChexConfig::new());
+    let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+
+    tx.send(1).await.unwrap();
+    assert_eq!(channel::recv_or_exit(&mut rx, &mut ci).await, Ok(Some(1)));
+
+    let (utx, urx) = tokio::sync::mpsc::unbounded_channel::<u32>();
+    let mut urx = ExitAwareReceiver::new(urx, ci.clone());
+    drop(utx);
+    assert_eq!(urx.recv().await, Ok(None));
+
+    tx.send(2).await.unwrap();
+    ci.signal_exit();
+    assert_eq!(channel::recv_or_exit(&mut rx, &mut ci).await, Err(ChexExited));
+}
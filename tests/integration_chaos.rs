@@ -0,0 +1,27 @@
+#![cfg(feature = "chaos")]
+
+use chex::{Chex,ChexConfig,ExitReason,ExitReasonKind};
+use chex::chaos::ChaosTrigger;
+use std::time::Duration;
+
+#[test]
+fn chaos_trigger_exits_within_the_window() {
+    let min = Duration::from_millis(10);
+    let max = Duration::from_millis(50);
+    let chaos = ChaosTrigger::within(min, max);
+    assert_eq!(chaos.delay_for(7), chaos.delay_for(7));
+    assert!((0..100).map(|seed| chaos.delay_for(seed)).all(|d| d >= min && d <= max));
+
+    let ci = Chex::new_local(ChexConfig::new());
+    ci.install_trigger(chaos.seed(7));
+    assert!(ci.block_until_exit_timeout(Duration::from_secs(10)));
+    assert_eq!(ci.exit_reason(), Some(ExitReason::Chaos { seed: 7 }));
+    assert_eq!(ci.exit_reason().unwrap().exit_code(), 1);
+    assert_eq!(ci.exit_origin().expect("origin").line(), 11);
+
+    let quiet = Chex::new_local(ChexConfig::new());
+    quiet.install_trigger(ChaosTrigger::within(min, max));
+    quiet.signal_exit();
+    std::thread::sleep(max * 2);
+    assert_eq!(quiet.exit_reason().map(|r| r.kind()), Some(ExitReasonKind::Manual));
+}
@@ -0,0 +1,37 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig};
+use futures::FutureExt;
+
+#[test]
+fn check_exit_async_only_resolves_on_exit() {
+    let signaller = Chex::new_local(ChexConfig::new());
+    let mut waiter = signaller.clone();
+    let others: Vec<_> = (0..4).map(|_| signaller.clone()).collect();
+
+    /*
+     * Dropping every other handle, including the one exit would be signalled with, only lowers
+     * the live instance count: it must not look like an exit to the last one.
+     */
+    drop(others);
+    assert!(waiter.check_exit_async().now_or_never().is_none());
+    let hook_ci = signaller.clone();
+    drop(signaller);
+    assert!(waiter.check_exit_async().now_or_never().is_none());
+    assert!(!waiter.poll_exit());
+
+    /*
+     * A pending wait holds a waker slot.  The first signal empties the slots and wakes it; the
+     * repeated ones find nothing left to wake, and later waits return without taking a slot.
+     */
+    let mut pending_ci = waiter.clone();
+    let mut pending = Box::pin(async move { pending_ci.check_exit_async().await });
+    assert!(pending.as_mut().now_or_never().is_none());
+    for _ in 0..3 {
+        hook_ci.signal_exit();
+    }
+    drop(hook_ci);
+    assert_eq!(pending.now_or_never(), Some(()));
+    assert_eq!(waiter.check_exit_async().now_or_never(), Some(()));
+    assert_eq!(waiter.check_exit_async().now_or_never(), Some(()));
+}
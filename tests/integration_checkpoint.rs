@@ -0,0 +1,23 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexExited};
+
+fn deep_work(depth: usize) -> Result<usize, ChexExited> {
+    chex::checkpoint()?;
+    if depth == 0 {
+        return Ok(0);
+    }
+    Ok(deep_work(depth - 1)? + 1)
+}
+
+#[test]
+fn checkpoint_cancels_call_chain() {
+    assert_eq!(chex::checkpoint(), Ok(()));
+
+    let chex: &Chex = Chex::init(false);
+    assert_eq!(deep_work(3), Ok(3));
+
+    chex.signal_exit();
+    assert_eq!(deep_work(3), Err(ChexExited));
+    assert_eq!(chex.get_instance().checkpoint(), Err(ChexExited));
+}
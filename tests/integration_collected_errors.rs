@@ -0,0 +1,25 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ExitReasonKind};
+
+#[test]
+fn every_panic_is_collected() {
+    let chex: &Chex = Chex::init(true);
+
+    let workers: Vec<_> = ["first", "second", "third"].into_iter()
+        .map(|msg| {
+            let w = std::thread::spawn(move || std::panic::panic_any(msg));
+            assert!(w.join().is_err());
+        })
+        .collect();
+    assert_eq!(workers.len(), 3);
+
+    assert!(chex.poll_exit());
+    assert_eq!(chex.panic_info().expect("first panic").message(), "first");
+
+    let errors = chex.collected_errors();
+    let messages: Vec<&str> = errors.iter().map(|e| e.message()).collect();
+    assert_eq!(messages, ["first", "second", "third"]);
+    assert!(errors.iter().all(|e| e.reason() == ExitReasonKind::Panic));
+    assert_eq!(errors[1].origin().file(), file!());
+}
@@ -0,0 +1,22 @@
+#![cfg(feature = "crossbeam")]
+
+use chex::{Chex,ChexConfig,ChexExited};
+use std::time::Duration;
+
+#[test]
+fn crossbeam_receives_until_exit() {
+    let ci = Chex::new_local(ChexConfig::new());
+    let (tx, rx) = crossbeam_channel::unbounded();
+
+    tx.send(1).unwrap();
+    assert_eq!(chex::crossbeam::recv_or_exit(&rx, &ci), Ok(Some(1)));
+    assert_eq!(chex::crossbeam::recv_or_exit_timeout(&rx, &ci, Duration::from_millis(10)), Ok(None));
+
+    let signaller = ci.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        signaller.signal_exit();
+    });
+    assert_eq!(chex::crossbeam::recv_or_exit(&rx, &ci), Err(ChexExited));
+    drop(tx);
+}
@@ -0,0 +1,33 @@
+#![cfg(feature = "crossbeam")]
+
+use chex::{Chex,ChexConfig};
+use std::time::Duration;
+
+#[test]
+fn crossbeam_receiver_is_a_select_arm() {
+    let ci = Chex::new_local(ChexConfig::new());
+    let (tx, rx) = crossbeam_channel::unbounded::<u32>();
+    let exit = ci.crossbeam_receiver();
+
+    let signaller = ci.clone();
+    std::thread::spawn(move || {
+        tx.send(1).unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+        signaller.signal_exit();
+        std::thread::sleep(Duration::from_secs(5));
+        drop(tx);
+    });
+
+    let mut received = Vec::new();
+    loop {
+        crossbeam_channel::select! {
+            recv(rx) -> message => received.push(message.unwrap()),
+            recv(exit) -> message => {
+                assert!(message.is_err());
+                break;
+            }
+        }
+    }
+    assert_eq!(received, [1]);
+    assert_eq!(ci.crossbeam_receiver().try_recv(), Err(crossbeam_channel::TryRecvError::Disconnected));
+}
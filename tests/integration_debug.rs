@@ -0,0 +1,18 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexInstance};
+
+#[test]
+fn debug_shows_state() {
+    let chex: &Chex = Chex::init(false);
+    let ci: ChexInstance = chex.get_instance();
+
+    let dbg = format!("{ci:?}");
+    assert!(dbg.contains("exit: false"));
+    assert!(dbg.contains("receiver_count: 2"));
+
+    ci.signal_exit();
+    let dbg = format!("{chex:?}");
+    assert!(dbg.contains("initialized: true"));
+    assert!(dbg.contains("exit: true"));
+}
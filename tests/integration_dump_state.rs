@@ -0,0 +1,33 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexInstance};
+use std::time::Duration;
+
+#[test]
+fn dump_state_reflects_chex() {
+    let chex: &Chex = Chex::init(true);
+    chex.add_panic_hook(|_| {});
+
+    let ci: ChexInstance = chex.get_instance();
+    let _listener = ci.register("api");
+    let _watchdog = chex.watchdog("api-loop", Duration::from_secs(60)).expect("initialized");
+
+    let state = chex.dump_state();
+    assert!(state.initialized());
+    assert!(!state.exit_signalled());
+    assert!(state.exit_on_panic());
+    assert_eq!(state.panic_hooks(), 1);
+    assert_eq!(state.watchdogs(), 1);
+    assert_eq!(state.receiver_count(), 2);
+    assert_eq!(state.pending_acks().len(), 1);
+
+    chex.signal_exit();
+    let state = chex.dump_state();
+    assert!(state.exit_signalled());
+    assert_eq!(state.origin().expect("origin").file(), file!());
+    assert!(state.exit_elapsed().is_some());
+
+    let text = state.to_string();
+    assert!(text.contains("exit signalled: true"));
+    assert!(text.contains("api on thread"));
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig,InitError};
+use chex::sink::{ChexEvent,LogLevel};
+use std::process::Command;
+use std::sync::{Arc,Mutex};
+use std::time::{Duration,Instant};
+
+/*
+ * The test re-runs itself with CHEX_ENV_CHILD and CHEX_GRACE_SECS set, to check that the
+ * environment's grace period terminates a process which does not exit by itself.
+ */
+#[test]
+fn config_defaults_from_environment() {
+    if std::env::var("CHEX_ENV_CHILD").is_ok() {
+        Chex::init(false).signal_exit();
+        std::thread::sleep(Duration::from_secs(10));
+        return;
+    }
+
+    std::env::set_var("CHEX_EXIT_ON_PANIC", "yes");
+    std::env::set_var("CHEX_LOG_LEVEL", "off");
+    std::env::set_var("CHEX_GRACE_SECS", "2.5");
+    assert_eq!(
+        ChexConfig::new().from_env(),
+        ChexConfig::new().exit_on_panic(true).log_level(LogLevel::Off).grace(Some(Duration::from_millis(2500))),
+    );
+    assert_eq!(
+        ChexConfig::new().exit_on_panic(false).grace(None).from_env(),
+        ChexConfig::new().exit_on_panic(false).grace(None).log_level(LogLevel::Off),
+    );
+
+    let chex = Chex::try_init(ChexConfig::new()).unwrap();
+    assert!(matches!(Chex::try_init(ChexConfig::new()), Err(InitError::AlreadyInitialized(_))));
+    assert!(matches!(
+        Chex::try_init(ChexConfig::new().exit_on_panic(false)),
+        Err(InitError::ConflictingConfig { .. }),
+    ));
+
+    let invalid = Arc::new(Mutex::new(Vec::new()));
+    let seen = invalid.clone();
+    chex.set_sink(move |event: ChexEvent<'_>| {
+        if let ChexEvent::InvalidEnvVar { name, value } = event {
+            seen.lock().unwrap().push(format!("{name}={value}"));
+        }
+    });
+    std::env::set_var("CHEX_GRACE_SECS", "soon");
+    assert_eq!(ChexConfig::new().grace(None).from_env(), ChexConfig::new().grace(None).exit_on_panic(true).log_level(LogLevel::Off));
+    assert_eq!(ChexConfig::new().from_env(), ChexConfig::new().exit_on_panic(true).log_level(LogLevel::Off));
+    assert_eq!(*invalid.lock().unwrap(), vec!["CHEX_GRACE_SECS=soon".to_string()]);
+
+    let start = Instant::now();
+    let status = Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", "config_defaults_from_environment", "--test-threads=1"])
+        .env("CHEX_ENV_CHILD", "1")
+        .env("CHEX_GRACE_SECS", "0.1")
+        .env_remove("CHEX_EXIT_ON_PANIC")
+        .env_remove("CHEX_LOG_LEVEL")
+        .stdout(std::process::Stdio::null())
+        .status()
+        .unwrap();
+    assert_eq!(status.code(), Some(1));
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
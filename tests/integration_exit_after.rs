@@ -0,0 +1,18 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexInstance};
+use std::time::{Duration,Instant};
+
+#[tokio::test]
+async fn scheduled_exit_fires() {
+    let chex: &Chex = Chex::init(false);
+    let mut ci: ChexInstance = chex.get_instance();
+
+    let start = Instant::now();
+    chex.signal_exit_after(Duration::from_millis(50));
+    assert!(!chex.poll_exit());
+
+    ci.check_exit_async().await;
+    assert!(start.elapsed() >= Duration::from_millis(50));
+    assert_eq!(chex.exit_origin().expect("exit origin recorded").file(), file!());
+}
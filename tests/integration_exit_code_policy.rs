@@ -0,0 +1,21 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ExitReason};
+
+fn policy(reason: &ExitReason) -> i32 {
+    match reason {
+        ExitReason::OsSignal(15) => 0,
+        ExitReason::Deadline => 3,
+        other => i32::from(other.exit_code()),
+    }
+}
+
+#[test]
+fn policy_maps_reason_to_exit_code() {
+    let chex: &Chex = Chex::init(false);
+    chex.set_exit_code_policy(policy);
+    assert_eq!(chex.termination().exit_code(), 0);
+
+    chex.signal_exit_on_os_signal(15);
+    assert_eq!(chex.termination().exit_code(), 0);
+}
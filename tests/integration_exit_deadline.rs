@@ -0,0 +1,38 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig,ForcedExit};
+use std::time::Duration;
+
+const CHILD_ENV: &str = "CHEX_TEST_DEADLINE_CHILD";
+
+/*
+ * The deadline terminates the whole process, so run the hung program as a child copy of this
+ * test binary.
+ */
+#[test]
+fn deadline_forces_process_exit() {
+    if std::env::var_os(CHILD_ENV).is_some() {
+        let chex: &Chex = Chex::try_init(ChexConfig::new().forced_exit(ForcedExit::Exit(7)))
+            .expect("first try_init should initialize");
+        assert!(chex.signal_exit_with_deadline(Duration::from_secs(600)));
+        /*
+         * An earlier deadline moves the pending one forward, a later one is ignored.
+         */
+        assert!(!chex.signal_exit_with_deadline(Duration::from_millis(50)));
+        assert!(!chex.signal_exit_with_deadline(Duration::from_secs(600)));
+
+        /*
+         * A listener that never finishes its teardown.
+         */
+        loop {
+            std::thread::sleep(Duration::from_secs(1));
+        }
+    }
+
+    let status = std::process::Command::new(std::env::current_exe().expect("current_exe"))
+        .args(["--exact", "deadline_forces_process_exit", "--nocapture"])
+        .env(CHILD_ENV, "1")
+        .status()
+        .expect("Failed to run child test process");
+    assert_eq!(status.code(), Some(7));
+}
@@ -0,0 +1,21 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig,SystemTime};
+use std::time::Duration;
+
+#[test]
+fn exit_elapsed_counts_from_first_signal() {
+    let chex = Chex::new_local(ChexConfig::new());
+    assert!(chex.exited_at().is_none());
+    assert!(chex.exit_elapsed().is_none());
+
+    let before = SystemTime::now();
+    chex.signal_exit();
+    let at = chex.exited_at().expect("exit time recorded");
+    assert!(chex.exited_at_system().expect("wall-clock time recorded") >= before);
+
+    std::thread::sleep(Duration::from_millis(20));
+    chex.signal_exit();
+    assert_eq!(chex.exited_at(), Some(at));
+    assert!(chex.exit_elapsed().expect("elapsed") >= Duration::from_millis(20));
+}
@@ -0,0 +1,24 @@
+#![cfg(feature = "anyhow")]
+
+use chex::{Chex,ExitReason};
+
+#[test]
+fn exit_error_is_returned_to_main() {
+    let chex: &Chex = Chex::init(false);
+    let ci = chex.get_instance();
+
+    let worker = std::thread::spawn(move || {
+        let err = anyhow::anyhow!("disk full").context("uploading batch 7");
+        assert!(ci.signal_exit_with_error(err));
+        assert!(!ci.signal_exit_with_error(anyhow::anyhow!("secondary failure")));
+    });
+    worker.join().unwrap();
+
+    assert_eq!(
+        chex.exit_reason(),
+        Some(ExitReason::Error { message: "uploading batch 7: disk full".to_string() }),
+    );
+    let err = chex.take_exit_error().expect("error kept");
+    assert_eq!(format!("{err:#}"), "uploading batch 7: disk full");
+    assert!(chex.take_exit_error().is_none());
+}
@@ -0,0 +1,32 @@
+#![cfg(feature = "anyhow")]
+
+use chex::{Chex,ChexConfig,ExitReason};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/*
+ * An error signalled while a manual exit is still running its at-exit hooks loses the race, so
+ * it must not be kept either.
+ */
+#[test]
+fn losing_error_is_not_kept() {
+    let ci = Chex::new_local(ChexConfig::new());
+    let (started_tx, started) = mpsc::channel();
+    ci.at_exit(move || {
+        let _ = started_tx.send(());
+        std::thread::sleep(Duration::from_millis(100));
+    });
+
+    let failing = std::thread::spawn({
+        let ci = ci.clone();
+        move || {
+            started.recv().unwrap();
+            ci.signal_exit_with_error(anyhow::anyhow!("too late"))
+        }
+    });
+    assert!(ci.signal_exit());
+    assert!(!failing.join().unwrap());
+
+    assert_eq!(ci.exit_reason(), Some(ExitReason::Manual));
+    assert!(ci.take_exit_error().is_none());
+}
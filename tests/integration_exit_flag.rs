@@ -0,0 +1,24 @@
+use chex::flag::ExitFlag;
+use std::sync::Arc;
+use std::time::Duration;
+
+static EXIT: ExitFlag = ExitFlag::new();
+
+#[tokio::test]
+async fn exit_flag_wakes_waiters() {
+    let waiters: Vec<_> = (0..4).map(|_| tokio::spawn(EXIT.wait())).collect();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert!(!EXIT.poll_exit());
+
+    assert!(EXIT.signal_exit());
+    assert!(!EXIT.signal_exit());
+    for waiter in waiters {
+        tokio::time::timeout(Duration::from_secs(5), waiter).await.expect("woken").expect("joined");
+    }
+    EXIT.wait().await;
+
+    let local = Arc::new(ExitFlag::default());
+    let signaller = Arc::clone(&local);
+    std::thread::spawn(move || signaller.signal_exit());
+    tokio::time::timeout(Duration::from_secs(5), local.wait()).await.expect("woken");
+}
@@ -0,0 +1,27 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig,ExitReasonKind};
+use std::time::Duration;
+
+#[test]
+fn critical_workers_signal_exit_when_they_stop() {
+    let returned = Chex::new_local(ChexConfig::new());
+    let ci = returned.clone();
+    std::thread::Builder::new().name("consensus".to_string()).spawn(move || {
+        let _guard = ci.exit_on_drop();
+    }).unwrap().join().unwrap();
+    assert_eq!(returned.exit_reason().map(|r| r.kind()), Some(ExitReasonKind::Error));
+    assert!(format!("{:?}", returned.exit_reason()).contains("consensus"));
+
+    let unwound = Chex::new_local(ChexConfig::new());
+    let ci = unwound.clone();
+    assert!(std::thread::spawn(move || {
+        let _guard = ci.exit_on_drop();
+        panic!("lost quorum");
+    }).join().is_err());
+    assert_eq!(unwound.exit_reason().map(|r| r.kind()), Some(ExitReasonKind::Panic));
+
+    let disarmed = Chex::new_local(ChexConfig::new());
+    disarmed.exit_on_drop().disarm();
+    assert!(!disarmed.block_until_exit_timeout(Duration::from_millis(10)));
+}
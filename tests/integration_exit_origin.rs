@@ -0,0 +1,27 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexInstance};
+
+#[test]
+fn exit_origin_records_first_caller() {
+    let chex: &Chex = Chex::init(false);
+    let ci: ChexInstance = chex.get_instance();
+    assert!(chex.exit_origin().is_none());
+
+    let th = std::thread::Builder::new().name("signaller".to_string()).spawn({
+        move || {
+            let line = line!() + 1;
+            assert!(ci.signal_exit());
+            line
+        }
+    }).expect("Failed to spawn thread");
+    let line = th.join().expect("signaller panicked");
+
+    assert!(!chex.signal_exit());
+
+    let origin = chex.exit_origin().expect("exit origin recorded");
+    assert_eq!(origin.file(), file!());
+    assert_eq!(origin.line(), line);
+    assert_eq!(origin.thread_name(), Some("signaller"));
+    assert_eq!(chex.get_instance().exit_origin(), Some(origin));
+}
@@ -0,0 +1,21 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig,ExitReason,ExitReasonKind};
+use futures::FutureExt;
+use std::time::Duration;
+
+#[test]
+fn filtered_wait_ignores_other_reasons() {
+    let chex = Chex::new_local(ChexConfig::new());
+    let mut manual_only = chex.clone();
+    let mut deadline = chex.clone();
+
+    chex.signal_exit_after(Duration::from_millis(10));
+    assert!(chex.block_until_exit_timeout(Duration::from_secs(5)));
+    assert_eq!(chex.exit_reason(), Some(ExitReason::Deadline));
+    assert!(chex.poll_exit_for(&[ExitReasonKind::Deadline, ExitReasonKind::Watchdog]));
+    assert!(!chex.poll_exit_for(&[ExitReasonKind::Manual]));
+
+    assert!(deadline.check_exit_async_for(&[ExitReasonKind::Manual, ExitReasonKind::Deadline]).now_or_never().is_some());
+    assert!(manual_only.check_exit_async_for(&[ExitReasonKind::Manual]).now_or_never().is_none());
+}
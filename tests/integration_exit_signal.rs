@@ -0,0 +1,54 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig};
+use chex::signal::ExitSignal;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool,AtomicUsize,Ordering::Relaxed};
+
+/*
+ * What a library would write: it only knows about ExitSignal.
+ */
+async fn drain_queue(exit: &dyn ExitSignal, queue: &AtomicUsize) {
+    while queue.load(Relaxed) > 0 {
+        if exit.poll_exit() {
+            return;
+        }
+        if queue.fetch_sub(1, Relaxed) == 1 {
+            exit.signal_exit();
+        }
+    }
+    exit.wait_exit().await;
+}
+
+struct FakeSignal {
+    exit: AtomicBool,
+    signals: AtomicUsize,
+}
+
+impl ExitSignal for FakeSignal {
+    fn poll_exit(&self) -> bool {
+        self.exit.load(Relaxed)
+    }
+
+    fn signal_exit(&self) -> bool {
+        self.signals.fetch_add(1, Relaxed);
+        !self.exit.swap(true, Relaxed)
+    }
+
+    fn wait_exit(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(std::future::ready(()))
+    }
+}
+
+#[tokio::test]
+async fn libraries_take_exit_signal_and_tests_fake_it() {
+    let fake = FakeSignal { exit: AtomicBool::new(false), signals: AtomicUsize::new(0) };
+    drain_queue(&fake, &AtomicUsize::new(3)).await;
+    assert_eq!(fake.signals.load(Relaxed), 1);
+
+    let ci = Chex::new_local(ChexConfig::new());
+    drain_queue(&ci, &AtomicUsize::new(2)).await;
+    assert!(ci.poll_exit());
+    assert_eq!(ci.exit_origin().expect("origin").file(), file!());
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexInstance};
+use std::time::Duration;
+
+#[test]
+fn wait_for_shutdown_summarizes_listeners() {
+    let chex: &Chex = Chex::init(false);
+    let ci: ChexInstance = chex.get_instance();
+
+    let worker = std::thread::Builder::new().spawn({
+        let ci = ci.clone();
+        let listener = ci.register("worker");
+        move || {
+            while !ci.poll_exit() {
+                std::thread::yield_now();
+            }
+            std::thread::sleep(Duration::from_millis(20));
+            listener.ack();
+        }
+    }).expect("Failed to spawn thread");
+    let hung = ci.register("hung");
+
+    let summary = chex.wait_for_shutdown(Duration::from_millis(10)).expect("initialized");
+    assert!(!summary.exited());
+    assert!(!summary.is_complete());
+
+    chex.signal_exit();
+    let summary = chex.wait_for_shutdown(Duration::from_millis(500)).expect("initialized");
+    worker.join().expect("worker panicked");
+
+    assert!(summary.exited());
+    assert!(!summary.is_complete());
+    assert_eq!(summary.origin().expect("origin").file(), file!());
+    assert_eq!(summary.completions().len(), 1);
+    assert_eq!(summary.completions()[0].0.name(), "worker");
+    assert!(summary.completions()[0].1 >= Duration::from_millis(20));
+    assert_eq!(summary.stragglers().len(), 1);
+    assert_eq!(summary.stragglers()[0].name(), "hung");
+
+    drop(hung);
+    assert!(chex.wait_for_shutdown(Duration::from_millis(500)).expect("initialized").is_complete());
+}
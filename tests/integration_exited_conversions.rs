@@ -0,0 +1,35 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexExited};
+use std::io::{ErrorKind,Read};
+
+fn read_all(mut input: impl Read) -> std::io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut buf = [0u8; 4];
+    loop {
+        chex::checkpoint()?;
+        match input.read(&mut buf)? {
+            0 => return Ok(out),
+            n => out.extend_from_slice(&buf[..n]),
+        }
+    }
+}
+
+fn boxed() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    chex::bail_if_exiting!();
+    Ok(())
+}
+
+#[test]
+fn exited_converts_into_io_and_boxed_errors() {
+    let chex: &Chex = Chex::init(false);
+    assert_eq!(read_all(&b"hello"[..]).unwrap(), b"hello");
+
+    chex.signal_exit();
+    let err = read_all(&b"hello"[..]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Interrupted);
+    assert!(err.get_ref().is_some_and(|e| e.is::<ChexExited>()));
+
+    let err = boxed().unwrap_err();
+    assert!(err.downcast_ref::<ChexExited>().is_some());
+}
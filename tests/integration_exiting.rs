@@ -0,0 +1,17 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig};
+
+#[test]
+fn exiting_mirrors_only_the_global_exit_flag() {
+    assert!(!chex::exiting());
+
+    let chex: &Chex = Chex::init(false);
+    let local = Chex::new_local(ChexConfig::new());
+    local.signal_exit();
+    assert!(!chex::exiting());
+
+    Chex::get_chex_instance().signal_exit();
+    assert!(chex::exiting());
+    assert!(chex.poll_exit());
+}
@@ -0,0 +1,35 @@
+#![cfg(feature = "ffi")]
+
+use chex::ffi::{chex_exit_fd,chex_init,chex_poll_exit,chex_signal_exit};
+
+#[test]
+fn ffi_exit_protocol() {
+    assert_eq!(chex_poll_exit(), -1);
+    assert_eq!(chex_signal_exit(), -1);
+
+    assert_eq!(chex_init(false), 0);
+    assert_eq!(chex_poll_exit(), 0);
+
+    #[cfg(unix)]
+    let fd = {
+        let fd = chex_exit_fd();
+        assert!(fd >= 0);
+        assert_eq!(chex_exit_fd(), fd);
+        fd
+    };
+
+    assert_eq!(chex_signal_exit(), 1);
+    assert_eq!(chex_signal_exit(), 0);
+    assert_eq!(chex_poll_exit(), 1);
+
+    #[cfg(unix)]
+    {
+        use std::io::Read;
+        use std::os::fd::BorrowedFd;
+
+        let owned = unsafe { BorrowedFd::borrow_raw(fd) }.try_clone_to_owned().unwrap();
+        let mut file = std::fs::File::from(owned);
+        let mut buf = [0u8; 1];
+        assert_eq!(file.read(&mut buf).unwrap(), 1);
+    }
+}
@@ -0,0 +1,50 @@
+#![cfg(all(feature = "fork", unix))]
+#![allow(unsafe_code)]
+
+use chex::Chex;
+
+extern "C" {
+    fn fork() -> i32;
+    fn waitpid(pid: i32, status: *mut i32, options: i32) -> i32;
+    fn _exit(status: i32) -> !;
+}
+
+/// Runs in the forked child; returns the exit status reported to the parent.
+fn child() -> i32 {
+    let stale = Chex::get_chex_instance();
+    if !stale.poll_exit() {
+        return 1;
+    }
+    if !Chex::reinit_after_fork() || Chex::reinit_after_fork() {
+        return 2;
+    }
+    let fresh = Chex::get_chex_instance();
+    if fresh.poll_exit() || chex::checkpoint().is_err() {
+        return 3;
+    }
+    fresh.signal_exit();
+    if !Chex::get_chex_instance().poll_exit() {
+        return 4;
+    }
+    0
+}
+
+#[test]
+fn child_gets_a_fresh_coordinator() {
+    let chex = Chex::init(true);
+    assert!(!Chex::reinit_after_fork());
+    chex.get_instance().signal_exit();
+
+    let pid = unsafe { fork() };
+    assert!(pid >= 0);
+    if pid == 0 {
+        let status = std::panic::catch_unwind(child).unwrap_or(5);
+        unsafe { _exit(status) }
+    }
+
+    let mut status = 0;
+    assert_eq!(unsafe { waitpid(pid, &mut status, 0) }, pid);
+    assert_eq!(status & 0x7f, 0, "child killed by signal {}", status & 0x7f);
+    assert_eq!((status >> 8) & 0xff, 0);
+    assert!(chex.get_instance().poll_exit());
+}
@@ -0,0 +1,37 @@
+#![cfg(feature = "fswatch")]
+
+use chex::{Chex,ChexConfig};
+use chex::fswatch::PathWatch;
+use std::time::{Duration,Instant};
+
+#[test]
+fn stop_file_signals_exit() {
+    let dir = std::env::temp_dir().join(format!("chex-fswatch-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let stop = dir.join("stop");
+    let pidfile = dir.join("pid");
+    std::fs::write(&pidfile, "1").unwrap();
+
+    let stopped = Chex::new_local(ChexConfig::new());
+    stopped.exit_on_path(PathWatch::appears(&stop).poll_interval(Duration::from_secs(10)));
+    let orphaned = Chex::new_local(ChexConfig::new());
+    orphaned.exit_on_path(PathWatch::disappears(&pidfile).poll_interval(Duration::from_millis(20)));
+
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(!stopped.poll_exit());
+    assert!(!orphaned.poll_exit());
+
+    /*
+     * The long poll interval means only inotify (on Linux) notices promptly.
+     */
+    let start = Instant::now();
+    std::fs::write(&stop, "").unwrap();
+    assert!(stopped.block_until_exit_timeout(Duration::from_secs(20)));
+    if cfg!(target_os = "linux") {
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    std::fs::remove_file(&pidfile).unwrap();
+    assert!(orphaned.block_until_exit_timeout(Duration::from_secs(5)));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
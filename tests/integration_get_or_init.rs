@@ -0,0 +1,18 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig,InitError};
+
+#[test]
+fn get_or_init_instance_initializes_on_demand() {
+    assert!(!Chex::is_initialized());
+    assert!(Chex::try_get_chex_instance().is_err());
+
+    let ci = Chex::get_or_init_instance();
+    assert!(Chex::is_initialized());
+    assert!(!ci.poll_exit());
+    assert!(matches!(Chex::try_init(ChexConfig::new()), Err(InitError::AlreadyInitialized(_))));
+
+    Chex::get_chex_instance().signal_exit();
+    assert!(ci.poll_exit());
+    assert!(Chex::get_or_init_instance().poll_exit());
+}
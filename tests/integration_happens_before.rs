@@ -0,0 +1,28 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64,Ordering::Relaxed};
+
+#[test]
+fn poll_exit_publishes_writes_made_before_signal_exit() {
+    for round in 0..200 {
+        let ci = Chex::new_local(ChexConfig::new());
+        let why = Arc::new(AtomicU64::new(0));
+
+        let observer = {
+            let ci = ci.clone();
+            let why = why.clone();
+            std::thread::spawn(move || {
+                while !ci.poll_exit() {
+                    std::hint::spin_loop();
+                }
+                why.load(Relaxed)
+            })
+        };
+
+        why.store(round + 1, Relaxed);
+        ci.signal_exit();
+        assert_eq!(observer.join().unwrap(), round + 1);
+    }
+}
@@ -0,0 +1,18 @@
+#![cfg(feature = "std")]
+
+use chex::Chex;
+
+#[test]
+fn health_flags_follow_shutdown() {
+    let chex: &Chex = Chex::init(true);
+    assert!(!chex.is_ready());
+    assert!(chex.is_live());
+
+    chex.set_ready(true);
+    assert!(chex.is_ready());
+
+    assert!(std::panic::catch_unwind(|| panic!("worker died")).is_err());
+    assert!(chex.is_draining());
+    assert!(!chex.is_ready());
+    assert!(!chex.is_live());
+}
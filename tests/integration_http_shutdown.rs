@@ -0,0 +1,34 @@
+#![cfg(all(feature = "http", feature = "test-util"))]
+
+use chex::{Chex,ChexConfig};
+use chex::clock::MockClock;
+use futures::executor::block_on;
+use std::time::Duration;
+
+#[test]
+fn serve_with_deadline_gives_up_after_exit() {
+    let chex = Chex::new_local(ChexConfig::new());
+
+    let graceful = chex::http::shutdown_signal_for(chex.clone());
+    let stuck = std::future::pending::<()>();
+
+    let signaller = chex.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(20));
+        signaller.signal_exit();
+    });
+
+    assert_eq!(block_on(chex::http::serve_with_deadline(graceful, chex.clone(), Duration::from_secs(5))), Some(()));
+
+    /*
+     * The deadline runs on the coordinator's clock.
+     */
+    let clock = MockClock::new();
+    let mocked = Chex::new_local(ChexConfig::new().clock(clock.clone()));
+    mocked.signal_exit();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(20));
+        clock.advance(Duration::from_secs(3600));
+    });
+    assert_eq!(block_on(chex::http::serve_with_deadline(stuck, mocked, Duration::from_secs(3600))), None);
+}
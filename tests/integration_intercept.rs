@@ -0,0 +1,31 @@
+#![cfg(feature = "std")]
+
+use chex::Chex;
+use std::time::{Duration,Instant};
+
+#[test]
+fn interceptors_delay_exit_within_bound() {
+    let chex: &Chex = Chex::init(false);
+
+    let commit = chex.intercept("two-phase commit", Duration::from_secs(5)).expect("initialized");
+    let stuck = chex.intercept("stuck", Duration::from_millis(50)).expect("initialized");
+    let ci = chex.get_instance();
+
+    let worker = std::thread::spawn(move || {
+        while !commit.exit_pending() {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(!ci.poll_exit());
+        commit.release();
+    });
+
+    let start = Instant::now();
+    assert!(chex.signal_exit());
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(50), "exit fanned out after {elapsed:?}");
+    assert!(elapsed < Duration::from_secs(5));
+    assert!(chex.poll_exit());
+    assert!(stuck.exit_pending());
+    worker.join().unwrap();
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool,Ordering::SeqCst};
+use std::time::Duration;
+
+/*
+ * Another thread signalling while an interceptor holds must not release the listeners early.
+ */
+#[test]
+fn concurrent_signal_respects_interceptor_hold() {
+    let ci = Chex::new_local(ChexConfig::new());
+    let interceptor = ci.intercept("commit", Duration::from_secs(30));
+    let released = Arc::new(AtomicBool::new(false));
+
+    let first = std::thread::spawn({
+        let ci = ci.clone();
+        move || ci.signal_exit()
+    });
+    while !interceptor.exit_pending() {
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    let second = std::thread::spawn({
+        let ci = ci.clone();
+        let released = released.clone();
+        move || {
+            assert!(!ci.signal_exit());
+            released.load(SeqCst)
+        }
+    });
+    let listener = std::thread::spawn({
+        let ci = ci.clone();
+        let released = released.clone();
+        move || {
+            ci.block_until_exit();
+            released.load(SeqCst)
+        }
+    });
+
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(!ci.poll_exit());
+    released.store(true, SeqCst);
+    interceptor.release();
+
+    assert!(first.join().unwrap());
+    assert!(second.join().unwrap(), "second signaller returned while the interceptor held");
+    assert!(listener.join().unwrap(), "listener woke while the interceptor held");
+}
@@ -0,0 +1,24 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig};
+use std::io::Read;
+use std::net::{TcpListener,TcpStream};
+
+#[test]
+fn blocking_reads_return_on_exit() {
+    let ci = Chex::new_local(ChexConfig::new());
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let _client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (mut stream, _) = listener.accept().unwrap();
+
+    let _guard = ci.interrupt_on_exit(stream.try_clone().unwrap());
+    let signaller = ci.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        signaller.signal_exit();
+    });
+
+    let mut buf = [0u8; 16];
+    assert_eq!(stream.read(&mut buf).unwrap(), 0);
+    assert!(ci.poll_exit());
+}
@@ -0,0 +1,25 @@
+#![cfg(all(feature = "ipc", unix))]
+
+use chex::{Chex,ChexConfig};
+use std::time::Duration;
+
+#[test]
+fn exit_propagates_between_participants() {
+    let path = std::env::temp_dir().join(format!("chex-ipc-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let coordinator = Chex::new_local(ChexConfig::new());
+    let first = Chex::new_local(ChexConfig::new());
+    let second = Chex::new_local(ChexConfig::new());
+    let _links = [
+        chex::ipc::coordinate(&coordinator, &path).unwrap(),
+        chex::ipc::join(&first, &path).unwrap(),
+        chex::ipc::join(&second, &path).unwrap(),
+    ];
+
+    second.signal_exit();
+
+    assert!(coordinator.block_until_exit_timeout(Duration::from_secs(5)));
+    assert!(first.block_until_exit_timeout(Duration::from_secs(5)));
+    let _ = std::fs::remove_file(&path);
+}
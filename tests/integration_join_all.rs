@@ -0,0 +1,42 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig};
+use std::time::Duration;
+
+#[test]
+fn join_all_reports_joined_panicked_and_timed_out() {
+    let chex = Chex::new_local(ChexConfig::new());
+
+    let ci = chex.clone();
+    chex.adopt(std::thread::Builder::new().name("worker".to_string()).spawn(move || {
+        ci.block_until_exit();
+    }).unwrap());
+    let crasher = std::thread::Builder::new().name("crasher".to_string()).spawn(|| {
+        panic!("crashed");
+    }).unwrap();
+    /*
+     * With RUST_BACKTRACE set, printing the panic can outlast join_all()'s short timeout below.
+     */
+    while !crasher.is_finished() {
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    chex.adopt(crasher);
+    let ci = chex.clone();
+    chex.adopt(std::thread::Builder::new().name("straggler".to_string()).spawn(move || {
+        while !ci.poll_exit() {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        std::thread::sleep(Duration::from_millis(300));
+    }).unwrap());
+
+    chex.signal_exit();
+    let report = chex.join_all(Duration::from_millis(100));
+    assert_eq!(report.joined(), ["worker"]);
+    assert_eq!(report.panicked(), ["crasher"]);
+    assert_eq!(report.timed_out(), ["straggler"]);
+    assert!(!report.is_complete());
+
+    let report = chex.join_all(Duration::from_secs(5));
+    assert_eq!(report.joined(), ["straggler"]);
+    assert!(report.is_complete());
+}
@@ -0,0 +1,14 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig};
+
+#[test]
+fn library_safe_try_signal_exit() {
+    let chex: &Chex = Chex::try_init(ChexConfig::new())
+        .expect("first try_init should initialize");
+    let ci = chex.get_instance();
+
+    assert_eq!(chex.try_signal_exit(), Ok(true));
+    assert!(ci.poll_exit());
+    assert_eq!(ci.try_signal_exit(), Ok(false));
+}
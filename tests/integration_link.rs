@@ -0,0 +1,23 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig,ExitReason};
+
+#[test]
+fn linked_coordinators_exit_together() {
+    let chex: &Chex = Chex::init(false);
+    let framework = Chex::new_local(ChexConfig::new());
+    let other = Chex::new_local(ChexConfig::new());
+    chex.link(&framework);
+    framework.link(&other);
+
+    assert!(framework.signal_exit());
+    let line = line!() - 1;
+    assert!(chex.poll_exit());
+    assert!(other.poll_exit());
+    assert_eq!(chex.exit_reason(), Some(ExitReason::Manual));
+    assert_eq!(other.exit_origin().expect("origin propagated").line(), line);
+
+    let late = Chex::new_local(ChexConfig::new());
+    late.link(&chex.get_instance());
+    assert!(late.poll_exit());
+}
@@ -0,0 +1,28 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexInstance};
+use std::future::Future;
+use std::task::{Context,Poll};
+
+#[test]
+fn listener_count_tracks_clones() {
+    let chex: &Chex = Chex::init(false);
+    assert_eq!(chex.listener_count(), 1);
+
+    let ci: ChexInstance = chex.get_instance();
+    let clones: Vec<ChexInstance> = (0..3).map(|_| ci.clone()).collect();
+    assert_eq!(ci.listener_count(), 5);
+    assert_eq!(ci.waiter_count(), 0);
+
+    let mut waiter = ci.clone();
+    let mut wait = Box::pin(waiter.check_exit_async());
+    let waker = futures::task::noop_waker();
+    assert_eq!(wait.as_mut().poll(&mut Context::from_waker(&waker)), Poll::Pending);
+    assert_eq!(ci.waiter_count(), 1);
+    drop(wait);
+    drop(waiter);
+    assert_eq!(ci.waiter_count(), 0);
+
+    drop(clones);
+    assert_eq!(chex.listener_count(), 2);
+}
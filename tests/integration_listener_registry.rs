@@ -0,0 +1,35 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexInstance};
+use std::sync::{Arc,Barrier};
+
+#[test]
+fn enumerate_live_listeners() {
+    let chex: &Chex = Chex::init(false);
+    let ci: ChexInstance = chex.get_instance();
+    assert!(chex.listeners().is_empty());
+
+    let registered = Arc::new(Barrier::new(2));
+    let th = std::thread::Builder::new().name("db-writer".to_string()).spawn({
+        let ci = ci.clone();
+        let registered = registered.clone();
+        move || {
+            let _listener = ci.register("db-writer");
+            registered.wait();
+            ci.block_until_exit();
+        }
+    }).expect("Failed to spawn thread");
+    registered.wait();
+
+    let main_listener = ci.register("main");
+    let listeners = chex.listeners();
+    assert_eq!(listeners.len(), 2);
+    let writer = listeners.iter().find(|l| l.name() == "db-writer").expect("db-writer registered");
+    assert_eq!(writer.thread_name(), Some("db-writer"));
+    assert_eq!(writer.thread_id(), th.thread().id());
+
+    chex.signal_exit();
+    th.join().expect("db-writer panicked");
+    main_listener.ack();
+    assert!(chex.listeners().is_empty());
+}
@@ -0,0 +1,28 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig};
+
+#[test]
+fn local_instances_are_independent() {
+    let a = Chex::new_local(ChexConfig::new());
+    let b = Chex::new_local(ChexConfig::new().exit_on_panic(true));
+    let a2 = a.clone();
+
+    assert!(a.signal_exit());
+    assert!(a2.poll_exit());
+    assert!(!b.poll_exit());
+    assert!(Chex::try_get_chex_instance().is_err());
+
+    assert!(std::panic::catch_unwind(|| panic!("local")).is_err());
+    assert!(b.poll_exit());
+    assert_eq!(b.panic_info().expect("panic recorded").message(), "local");
+    assert!(a.panic_info().is_none());
+
+    for _ in 0..1000 {
+        drop(Chex::new_local(ChexConfig::new().exit_on_panic(true)));
+    }
+    let c = Chex::new_local(ChexConfig::new().exit_on_panic(true));
+    assert!(std::panic::catch_unwind(|| panic!("again")).is_err());
+    assert!(c.poll_exit());
+    assert_eq!(c.panic_info().expect("panic recorded").message(), "again");
+}
@@ -0,0 +1,24 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ExitReason};
+use std::time::Duration;
+
+fn run() -> Result<(), String> {
+    let chex = Chex::init_guarded(false);
+    let worker = chex.get_instance();
+    std::thread::spawn(move || worker.block_until_exit());
+    assert!(!chex.poll_exit());
+    Err("early return".to_string())?;
+    Ok(())
+}
+
+#[test]
+fn dropping_main_guard_signals_exit() {
+    assert!(run().is_err());
+
+    let ci = Chex::get_chex_instance();
+    assert!(ci.block_until_exit_timeout(Duration::from_secs(10)));
+    assert_eq!(ci.exit_reason(), Some(ExitReason::Manual));
+    let origin = ci.exit_origin().unwrap();
+    assert!(origin.to_string().contains("integration_main_guard.rs:7"), "{origin}");
+}
@@ -0,0 +1,42 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig,ChexInstance};
+use futures::FutureExt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize,Ordering::Relaxed};
+use tokio::task::JoinSet;
+
+#[tokio::test]
+async fn tens_of_thousands_of_listeners_wake_on_exit() {
+    let signaller = Chex::new_local(ChexConfig::new());
+    let idle: Vec<ChexInstance> = (0..50_000).map(|_| signaller.clone()).collect();
+
+    let waiting = Arc::new(AtomicUsize::new(0));
+    let mut set = JoinSet::new();
+    for _ in 0..20_000 {
+        let mut ci = signaller.clone();
+        let waiting = waiting.clone();
+        set.spawn(async move {
+            /*
+             * Waits cancelled by select!() must give their slot back.
+             */
+            for _ in 0..3 {
+                assert!(ci.check_exit_async().now_or_never().is_none());
+            }
+            waiting.fetch_add(1, Relaxed);
+            ci.check_exit_async().await;
+        });
+    }
+    while waiting.load(Relaxed) < 20_000 {
+        tokio::task::yield_now().await;
+    }
+    assert_eq!(signaller.listener_count(), 70_001);
+
+    signaller.signal_exit();
+    while let Some(res) = set.join_next().await {
+        res.unwrap();
+    }
+    assert!(idle.iter().all(|ci| ci.poll_exit()));
+    drop(idle);
+    assert_eq!(signaller.listener_count(), 1);
+}
@@ -0,0 +1,37 @@
+#![cfg(all(feature = "std", target_os = "linux"))]
+
+use chex::{Chex,ChexConfig,ExitReasonKind};
+use chex::memory::{MemoryWatermark,WatermarkAction};
+use std::time::{Duration,Instant};
+
+fn wait_until(timeout: Duration, f: impl Fn() -> bool) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        if f() {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    false
+}
+
+#[test]
+fn watermark_starts_soft_exit_or_signals_exit() {
+    let poll = Duration::from_millis(10);
+
+    let idle = Chex::new_local(ChexConfig::new());
+    idle.exit_on_memory(MemoryWatermark::rss(u64::MAX).poll_interval(poll)).unwrap();
+
+    let soft = Chex::new_local(ChexConfig::new());
+    soft.exit_on_memory(MemoryWatermark::rss(1).poll_interval(poll)).unwrap();
+    assert!(wait_until(Duration::from_secs(10), || soft.is_draining()));
+    assert!(!soft.poll_exit());
+
+    let hard = Chex::new_local(ChexConfig::new());
+    hard.exit_on_memory(MemoryWatermark::rss(1).action(WatermarkAction::Exit).poll_interval(poll)).unwrap();
+    assert!(hard.block_until_exit_timeout(Duration::from_secs(10)));
+    assert_eq!(hard.exit_reason().map(|r| r.kind()), Some(ExitReasonKind::Resource));
+    assert_eq!(hard.exit_reason().unwrap().exit_code(), 1);
+
+    assert!(!idle.is_draining());
+}
@@ -0,0 +1,131 @@
+#![cfg(feature = "test-util")]
+
+use chex::{Chex,ChexConfig,ExitReason};
+use chex::clock::MockClock;
+use std::time::Duration;
+
+#[test]
+fn mock_clock_drives_deadlines_and_watchdogs() {
+    let clock = MockClock::new();
+    let ci = Chex::new_local(ChexConfig::new().clock(clock.clone()));
+    ci.signal_exit_after(Duration::from_secs(3600));
+    clock.advance(Duration::from_secs(3599));
+    std::thread::sleep(Duration::from_millis(50));
+    assert!(!ci.poll_exit());
+    clock.advance(Duration::from_secs(1));
+    assert!(ci.block_until_exit_timeout(Duration::from_secs(10)));
+    assert_eq!(ci.exit_reason(), Some(ExitReason::Deadline));
+
+    let clock = MockClock::new();
+    let ci = Chex::new_local(ChexConfig::new().clock(clock.clone()));
+    let dog = ci.watchdog("worker", Duration::from_secs(60));
+    clock.advance(Duration::from_secs(50));
+    dog.beat();
+    clock.advance(Duration::from_secs(50));
+    std::thread::sleep(Duration::from_millis(50));
+    assert!(!ci.poll_exit());
+    clock.advance(Duration::from_secs(11));
+    assert!(ci.block_until_exit_timeout(Duration::from_secs(10)));
+    assert_eq!(ci.exit_reason(), Some(ExitReason::Watchdog { name: "worker".to_string() }));
+}
+
+#[test]
+fn mock_clock_drives_global_timeouts() {
+    let _lock = Chex::test_lock();
+    let clock = MockClock::new();
+    let chex: &Chex = Chex::try_init(ChexConfig::new().clock(clock.clone())).expect("first init");
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    chex.set_straggler_timeout(Some(Duration::from_secs(3600)));
+    chex.on_stragglers(move |stragglers| {
+        let _ = tx.send(stragglers.len());
+    });
+    let _subsystem = chex.register_subsystem("slow").expect("initialized");
+
+    /*
+     * Two panics count only within a minute of each other.
+     */
+    chex.set_panic_threshold(2, Some(Duration::from_secs(60)));
+    chex.set_exit_on_panic();
+    let panic_once = || assert!(std::thread::spawn(|| panic!("boom")).join().is_err());
+    panic_once();
+    clock.advance(Duration::from_secs(61));
+    panic_once();
+    assert!(!chex.poll_exit());
+    clock.advance(Duration::from_secs(59));
+    panic_once();
+    assert!(chex.poll_exit());
+
+    let advancer = std::thread::spawn({
+        let clock = clock.clone();
+        move || {
+            std::thread::sleep(Duration::from_millis(50));
+            clock.advance(Duration::from_secs(3600));
+        }
+    });
+    assert_eq!(chex.wait_for_subsystems(Duration::from_secs(3600)).map_err(|live| live.len()), Err(1));
+    advancer.join().unwrap();
+    assert_eq!(rx.recv_timeout(Duration::from_secs(5)), Ok(1));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn tokio_clock_follows_paused_time() {
+    use chex::clock::TokioClock;
+
+    let clock = TokioClock::new(tokio::runtime::Handle::current());
+    let mut ci = Chex::new_local(ChexConfig::new().clock(clock));
+    let start = std::time::Instant::now();
+    ci.signal_exit_after(Duration::from_secs(60));
+    ci.check_exit_async().await;
+    assert_eq!(ci.exit_reason(), Some(ExitReason::Deadline));
+    assert!(start.elapsed() < Duration::from_secs(30));
+}
+
+#[test]
+fn mock_clock_drives_interceptor_max_delay() {
+    let clock = MockClock::new();
+    let ci = Chex::new_local(ChexConfig::new().clock(clock.clone()));
+    let interceptor = ci.intercept("stuck", Duration::from_secs(3600));
+    let signaller = std::thread::spawn({
+        let ci = ci.clone();
+        move || ci.signal_exit()
+    });
+    while !interceptor.exit_pending() {
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(!ci.poll_exit());
+    clock.advance(Duration::from_secs(3600));
+    assert!(signaller.join().unwrap());
+    assert!(ci.poll_exit());
+}
+
+#[cfg(all(feature = "process", unix))]
+#[test]
+fn mock_clock_drives_child_grace() {
+    use chex::process::ChildOptions;
+    use std::io::{BufRead,BufReader};
+    use std::process::{Command,Stdio};
+
+    let clock = MockClock::new();
+    let ci = Chex::new_local(ChexConfig::new().clock(clock.clone()));
+    let mut child = Command::new("sh")
+        .args(["-c", "trap '' TERM; echo ready; exec sleep 60"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut line = String::new();
+    BufReader::new(child.stdout.take().unwrap()).read_line(&mut line).unwrap();
+    assert_eq!(line, "ready\n");
+    ci.adopt_child(child, ChildOptions::new().grace(Duration::from_secs(3600)));
+
+    /*
+     * The child ignores SIGTERM, so teardown waits for the grace period before SIGKILL.
+     */
+    ci.signal_exit();
+    assert!(!ci.wait_for_teardown(Duration::from_millis(200)));
+    clock.advance(Duration::from_secs(3600));
+    assert!(ci.wait_for_teardown(Duration::from_secs(10)));
+}
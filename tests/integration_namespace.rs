@@ -0,0 +1,16 @@
+#![cfg(feature = "std")]
+
+use chex::Chex;
+
+#[test]
+fn namespaces_are_shared_by_name_and_independent() {
+    let lib_a = Chex::namespace("mylib");
+    let lib_b = Chex::namespace("mylib");
+    let other = Chex::namespace("otherlib");
+
+    assert!(lib_a.signal_exit());
+    assert!(lib_b.poll_exit());
+    assert!(!other.poll_exit());
+    assert!(Chex::namespace("mylib").poll_exit());
+    assert!(Chex::try_get_chex_instance().is_err());
+}
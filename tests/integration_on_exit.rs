@@ -0,0 +1,32 @@
+#![cfg(feature = "std")]
+
+use chex::Chex;
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::Relaxed;
+use std::time::Duration;
+
+#[test]
+fn on_exit_runs_once_on_shutdown_thread() {
+    let chex: &Chex = Chex::init(false);
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let counter = calls.clone();
+    let main_thread = std::thread::current().id();
+    chex.on_exit(move || {
+        assert_ne!(std::thread::current().id(), main_thread);
+        counter.fetch_add(1, Relaxed);
+    });
+
+    chex.signal_exit();
+    chex.signal_exit();
+    assert!(chex.wait_for_teardown(Duration::from_secs(5)));
+    assert_eq!(calls.load(Relaxed), 1);
+
+    let counter = calls.clone();
+    chex.get_instance().on_exit(move || {
+        counter.fetch_add(1, Relaxed);
+    });
+    assert!(chex.wait_for_teardown(Duration::from_secs(5)));
+    assert_eq!(calls.load(Relaxed), 2);
+}
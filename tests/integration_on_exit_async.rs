@@ -0,0 +1,32 @@
+#![cfg(feature = "tokio")]
+
+use chex::Chex;
+use chex::teardown::AsyncExecutor;
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::Relaxed;
+use std::time::Duration;
+
+#[test]
+fn async_hooks_run_at_shutdown() {
+    let chex: &Chex = Chex::init(false);
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let counter = calls.clone();
+    runtime.block_on(async {
+        chex.on_exit_async(AsyncExecutor::Current, move || async move {
+            tokio::task::yield_now().await;
+            counter.fetch_add(1, Relaxed);
+        });
+    });
+
+    let counter = calls.clone();
+    chex.on_exit_async(AsyncExecutor::Dedicated, move || async move {
+        counter.fetch_add(1, Relaxed);
+    });
+
+    chex.signal_exit();
+    assert!(chex.wait_for_teardown(Duration::from_secs(5)));
+    assert_eq!(calls.load(Relaxed), 2);
+}
@@ -0,0 +1,19 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig,ExitReason,ExitReasonKind};
+
+#[test]
+fn os_signal_reason_is_visible_from_every_instance() {
+    let chex = Chex::new_local(ChexConfig::new());
+    let other = chex.clone();
+
+    assert!(chex.signal_exit_on_os_signal(15));
+    assert!(!chex.signal_exit_on_os_signal(2));
+
+    let reason = other.exit_reason().expect("reason recorded");
+    assert_eq!(reason, ExitReason::OsSignal(15));
+    assert_eq!(reason.kind(), ExitReasonKind::OsSignal);
+    assert_eq!(reason.exit_code(), 143);
+    assert_eq!(reason.to_string(), "os_signal 15");
+    assert!(other.poll_exit_for(&[ExitReasonKind::OsSignal]));
+}
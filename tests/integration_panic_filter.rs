@@ -0,0 +1,35 @@
+#![cfg(feature = "std")]
+
+use chex::Chex;
+
+fn panic_on_thread(name: &str, msg: &'static str) {
+    let th = std::thread::Builder::new().name(name.to_string()).spawn({
+        move || {
+            let res = std::panic::catch_unwind(|| {
+                std::panic::panic_any(msg);
+            });
+            assert!(res.is_err());
+        }
+    }).expect("Failed to spawn thread");
+    th.join().expect("thread should have caught its panic");
+}
+
+#[test]
+fn filtered_panics_do_not_signal_exit() {
+    let chex: &Chex = Chex::init(true);
+    chex.ignore_panics_on_thread("prober");
+    chex.set_panic_filter(|info| {
+        info.payload().downcast_ref::<&str>() != Some(&"recoverable")
+    });
+
+    panic_on_thread("prober", "fatal");
+    assert!(!chex.poll_exit());
+
+    panic_on_thread("worker", "recoverable");
+    assert!(!chex.poll_exit());
+
+    chex.clear_panic_filter();
+    panic_on_thread("worker", "fatal");
+    assert!(chex.poll_exit());
+    assert_eq!(chex.panic_info().expect("panic report").message(), "fatal");
+}
@@ -0,0 +1,28 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexInstance};
+use std::time::{Duration,Instant};
+
+#[test]
+fn panic_hook_waits_for_listeners() {
+    let chex: &Chex = Chex::init(true);
+    chex.set_panic_grace(Some(Duration::from_secs(10)));
+
+    let ci: ChexInstance = chex.get_instance();
+    let worker = std::thread::Builder::new().spawn({
+        move || {
+            while !ci.poll_exit() {
+                std::thread::yield_now();
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }).expect("Failed to spawn thread");
+
+    let start = Instant::now();
+    assert!(std::panic::catch_unwind(|| panic!("grace")).is_err());
+    let waited = start.elapsed();
+
+    assert!(waited >= Duration::from_millis(100));
+    assert!(waited < Duration::from_secs(10));
+    worker.join().expect("worker panicked");
+}
@@ -0,0 +1,41 @@
+#![cfg(feature = "std")]
+
+use chex::Chex;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize,Ordering};
+
+#[test]
+fn foreign_panic_hook_is_chained() {
+    let chex: &Chex = Chex::init(true);
+
+    let added = Arc::new(AtomicUsize::new(0));
+    chex.add_panic_hook({
+        let added = added.clone();
+        move |_| { added.fetch_add(1, Ordering::SeqCst); }
+    });
+
+    /*
+     * Another crate replaces the hook, but chains to the previous one as well-behaved crates do.
+     */
+    let foreign = Arc::new(AtomicUsize::new(0));
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new({
+        let foreign = foreign.clone();
+        move |info| {
+            foreign.fetch_add(1, Ordering::SeqCst);
+            previous(info);
+        }
+    }));
+
+    /*
+     * Looking up instances leaves the process hook alone.
+     */
+    let _ = chex.get_instance();
+    assert!(chex.ensure_panic_hook());
+    assert!(!chex.ensure_panic_hook());
+
+    assert!(std::panic::catch_unwind(|| panic!("after foreign hook")).is_err());
+    assert!(chex.poll_exit());
+    assert_eq!(added.load(Ordering::SeqCst), 1);
+    assert_eq!(foreign.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,22 @@
+#![cfg(feature = "std")]
+
+use chex::Chex;
+
+#[test]
+fn replaced_panic_hook_is_reinstalled_lazily() {
+    let chex: &Chex = Chex::init(true);
+    let ci = chex.get_instance();
+
+    /*
+     * Another crate installs its own hook after init, dropping chex's.
+     */
+    std::panic::set_hook(Box::new(|_| {}));
+
+    /*
+     * A worker polling for exit is enough to put chex's hook back in front.
+     */
+    assert!(!ci.poll_exit());
+    assert!(std::thread::spawn(|| panic!("after foreign hook")).join().is_err());
+    assert!(ci.poll_exit());
+    assert!(!chex.ensure_panic_hook());
+}
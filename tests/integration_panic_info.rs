@@ -0,0 +1,28 @@
+#![cfg(feature = "std")]
+
+use chex::Chex;
+
+#[test]
+fn panic_info_captured_by_hook() {
+    let chex: &Chex = Chex::init(true);
+    assert!(chex.panic_info().is_none());
+
+    let code: u32 = std::hint::black_box(42);
+    let th = std::thread::Builder::new().name("doomed".to_string()).spawn({
+        move || {
+            panic!("doomed worker failed: {code}");
+        }
+    }).expect("Failed to spawn thread");
+    assert!(th.join().is_err());
+
+    assert!(chex.poll_exit());
+    let report = chex.panic_info().expect("panic report recorded");
+    assert_eq!(report.message(), "doomed worker failed: 42");
+    assert_eq!(report.payload_type(), "String");
+    assert_eq!(report.thread_name(), Some("doomed"));
+    assert!(report.location().expect("location").starts_with(file!()));
+
+    let origin = chex.exit_origin().expect("exit origin recorded");
+    assert_eq!(origin.file(), file!());
+    assert_eq!(origin.thread_name(), Some("doomed"));
+}
@@ -0,0 +1,16 @@
+#![cfg(feature = "std")]
+
+use chex::Chex;
+use chex::panic::PanicHookMode;
+
+#[test]
+fn minimal_hook_only_raises_flag() {
+    let chex: &Chex = Chex::init(true);
+    chex.set_panic_hook_mode(PanicHookMode::Minimal);
+
+    assert!(std::panic::catch_unwind(|| panic!("minimal")).is_err());
+    assert!(chex.poll_exit());
+    assert!(chex.panic_info().is_none());
+    assert!(chex.exit_origin().is_none());
+    assert!(!chex.signal_exit());
+}
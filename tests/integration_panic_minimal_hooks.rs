@@ -0,0 +1,30 @@
+#![cfg(feature = "std")]
+
+use chex::Chex;
+use chex::panic::PanicHookMode;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool,Ordering};
+use std::time::Duration;
+
+#[test]
+fn minimal_panic_runs_hooks_on_next_signal() {
+    let chex: &Chex = Chex::init(true);
+    chex.set_panic_hook_mode(PanicHookMode::Minimal);
+    let ran = Arc::new(AtomicBool::new(false));
+    let r = ran.clone();
+    chex.on_exit(move || r.store(true, Ordering::SeqCst));
+
+    assert!(std::panic::catch_unwind(|| panic!("minimal")).is_err());
+    assert!(chex.poll_exit());
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(!ran.load(Ordering::SeqCst));
+
+    /*
+     * The panic stays the first signal, but this call records the origin and runs the hooks.
+     */
+    assert!(!chex.signal_exit());
+    assert!(chex.exit_origin().is_some());
+    assert!(matches!(chex.exit_reason(), Some(chex::ExitReason::Panic { .. })));
+    assert!(chex.wait_for_teardown(Duration::from_secs(5)));
+    assert!(ran.load(Ordering::SeqCst));
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "std")]
+
+use chex::Chex;
+use chex::panic::PanicHookMode;
+use chex::sink::ChexEvent;
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::Relaxed;
+
+#[test]
+fn quiet_hook_signals_without_output() {
+    let chex: &Chex = Chex::init(true);
+    chex.set_panic_hook_mode(PanicHookMode::Quiet);
+
+    let events = Arc::new(AtomicUsize::new(0));
+    let seen = events.clone();
+    chex.set_sink(move |event: ChexEvent<'_>| {
+        if !matches!(event, ChexEvent::ExitSignalled { .. }) {
+            seen.fetch_add(1, Relaxed);
+        }
+    });
+
+    let chained = Arc::new(AtomicUsize::new(0));
+    let calls = chained.clone();
+    chex.add_panic_hook(move |_| {
+        calls.fetch_add(1, Relaxed);
+    });
+
+    assert!(std::panic::catch_unwind(|| panic!("quiet")).is_err());
+    assert!(chex.poll_exit());
+    assert_eq!(chex.panic_info().expect("panic recorded").message(), "quiet");
+    assert_eq!(chained.load(Relaxed), 1);
+    assert_eq!(events.load(Relaxed), 0);
+}
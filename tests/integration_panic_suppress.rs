@@ -0,0 +1,21 @@
+#![cfg(feature = "std")]
+
+use chex::Chex;
+
+#[test]
+fn suppressed_panics_do_not_signal_exit() {
+    let chex: &Chex = Chex::init(true);
+
+    {
+        let _outer = chex::panic::suppress_exit();
+        {
+            let _inner = chex::panic::suppress_exit();
+            assert!(std::panic::catch_unwind(|| panic!("probe inner")).is_err());
+        }
+        assert!(std::panic::catch_unwind(|| panic!("probe outer")).is_err());
+    }
+    assert!(!chex.poll_exit());
+
+    assert!(std::panic::catch_unwind(|| panic!("unexpected")).is_err());
+    assert!(chex.poll_exit());
+}
@@ -0,0 +1,22 @@
+#![cfg(feature = "std")]
+
+use chex::Chex;
+use std::time::Duration;
+
+#[test]
+fn exit_after_panic_threshold() {
+    let chex: &Chex = Chex::init(true);
+
+    chex.set_panic_threshold(2, Some(Duration::from_millis(10)));
+    assert!(std::panic::catch_unwind(|| panic!("first")).is_err());
+    std::thread::sleep(Duration::from_millis(50));
+    assert!(std::panic::catch_unwind(|| panic!("outside window")).is_err());
+    assert!(!chex.poll_exit());
+
+    chex.set_panic_threshold(3, None);
+    assert!(std::panic::catch_unwind(|| panic!("one")).is_err());
+    assert!(std::panic::catch_unwind(|| panic!("two")).is_err());
+    assert!(!chex.poll_exit());
+    assert!(std::panic::catch_unwind(|| panic!("three")).is_err());
+    assert!(chex.poll_exit());
+}
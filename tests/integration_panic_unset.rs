@@ -0,0 +1,17 @@
+#![cfg(feature = "std")]
+
+use chex::Chex;
+
+#[test]
+fn unset_exit_on_panic_restores_default() {
+    let chex: &Chex = Chex::init(true);
+
+    chex.unset_exit_on_panic();
+    assert!(!chex.ensure_panic_hook());
+    assert!(std::panic::catch_unwind(|| panic!("during startup")).is_err());
+    assert!(!chex.poll_exit());
+
+    chex.set_exit_on_panic();
+    assert!(std::panic::catch_unwind(|| panic!("steady state")).is_err());
+    assert!(chex.poll_exit());
+}
@@ -0,0 +1,44 @@
+#![cfg(all(feature = "std", unix))]
+
+use chex::{Chex,ChexConfig,ExitReason};
+use chex::parent::ParentDeathOptions;
+use std::process::Command;
+use std::time::{Duration,Instant};
+
+/*
+ * The test re-runs itself under a short-lived sh, with CHEX_PARENT_DEATH_OUT set.  The grandchild
+ * writes the reason it exited for to that file once sh has gone.
+ */
+#[test]
+fn exit_when_parent_dies() {
+    if let Ok(out) = std::env::var("CHEX_PARENT_DEATH_OUT") {
+        let ci = Chex::new_local(ChexConfig::new());
+        ci.exit_on_parent_death(ParentDeathOptions::new().poll_interval(Duration::from_millis(20)));
+        ci.block_until_exit_timeout(Duration::from_secs(10));
+        let tmp = format!("{out}.tmp");
+        std::fs::write(&tmp, format!("{:?}", ci.exit_reason())).unwrap();
+        std::fs::rename(tmp, out).unwrap();
+        return;
+    }
+
+    let out = std::env::temp_dir().join(format!("chex-parent-death-{}", std::process::id()));
+    let _ = std::fs::remove_file(&out);
+    let status = Command::new("sh")
+        .args(["-c", "\"$0\" --exact exit_when_parent_dies --test-threads=1 >/dev/null 2>&1 & sleep 0.5"])
+        .arg(std::env::current_exe().unwrap())
+        .env("CHEX_PARENT_DEATH_OUT", &out)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let start = Instant::now();
+    let reason = loop {
+        if let Ok(reason) = std::fs::read_to_string(&out) {
+            break reason;
+        }
+        assert!(start.elapsed() < Duration::from_secs(10), "grandchild did not exit");
+        std::thread::sleep(Duration::from_millis(20));
+    };
+    let _ = std::fs::remove_file(&out);
+    assert_eq!(reason, format!("{:?}", Some(ExitReason::ParentExited)));
+}
@@ -0,0 +1,38 @@
+#![cfg(all(feature = "std", target_os = "linux"))]
+
+use chex::{Chex,ChexConfig,ExitReason};
+use chex::parent::ParentDeathOptions;
+use std::io::{BufRead,BufReader};
+use std::process::{Command,Stdio};
+use std::time::Duration;
+
+/*
+ * The test re-runs itself from a short-lived thread, with CHEX_DEATH_SIGNAL_CHILD set.  The
+ * parent process lives on, so only the death signal can tell the child that thread is gone.
+ */
+#[test]
+fn death_signal_reports_parent_exited() {
+    if std::env::var_os("CHEX_DEATH_SIGNAL_CHILD").is_some() {
+        let ci = Chex::new_local(ChexConfig::new());
+        ci.exit_on_parent_death(ParentDeathOptions::new()
+            .poll_interval(Duration::from_millis(20))
+            .death_signal(true));
+        println!("ready");
+        ci.block_until_exit_timeout(Duration::from_secs(10));
+        std::process::exit(if ci.exit_reason() == Some(ExitReason::ParentExited) { 0 } else { 1 });
+    }
+
+    let mut child = std::thread::spawn(|| {
+        let mut child = Command::new(std::env::current_exe().unwrap())
+            .args(["--exact", "death_signal_reports_parent_exited", "--nocapture", "--test-threads=1"])
+            .env("CHEX_DEATH_SIGNAL_CHILD", "1")
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut lines = BufReader::new(child.stdout.take().unwrap()).lines();
+        while !lines.next().expect("child exited early").unwrap().ends_with("ready") {}
+        child
+    }).join().unwrap();
+
+    assert!(child.wait().unwrap().success());
+}
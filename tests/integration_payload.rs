@@ -0,0 +1,26 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig};
+use std::time::Duration;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Shutdown {
+    reason: String,
+    deadline: Duration,
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn exit_carries_payload() {
+    let chex = Chex::new_local_with_payload::<Shutdown>(ChexConfig::new());
+    let mut listener = chex.clone();
+    assert_eq!(chex.exit_payload(), None);
+
+    let waiter = tokio::spawn(async move { listener.recv_exit_async().await });
+
+    let cmd = Shutdown { reason: "maintenance".to_string(), deadline: Duration::from_secs(5) };
+    assert!(chex.signal_exit_with(cmd.clone()));
+    assert!(!chex.signal_exit_with(Shutdown { reason: "late".to_string(), deadline: Duration::ZERO }));
+
+    assert_eq!(waiter.await.unwrap(), Some(cmd.clone()));
+    assert_eq!(chex.exit_payload(), Some(cmd));
+}
@@ -0,0 +1,28 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig};
+use std::sync::Barrier;
+
+#[test]
+fn payload_belongs_to_the_first_signaller() {
+    for _ in 0..200 {
+        let chex = Chex::new_local_with_payload::<usize>(ChexConfig::new());
+        let barrier = Barrier::new(4);
+        let winners: Vec<Option<usize>> = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..4).map(|i| {
+                let (chex, barrier) = (&chex, &barrier);
+                s.spawn(move || {
+                    barrier.wait();
+                    let first = if i == 0 { chex.signal_exit() } else { chex.signal_exit_with(i) };
+                    first.then_some(i)
+                })
+            }).collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        let winner = winners.into_iter().flatten().collect::<Vec<_>>();
+        assert_eq!(winner.len(), 1);
+        let expected = if winner[0] == 0 { None } else { Some(winner[0]) };
+        assert_eq!(chex.exit_payload(), expected);
+    }
+}
@@ -0,0 +1,69 @@
+#![cfg(all(feature = "pid1", unix))]
+#![allow(unsafe_code)]
+
+use chex::{Chex,ChexConfig,ExitReason};
+use chex::pid1::Pid1Options;
+use chex::process::ChildOptions;
+use std::io::{BufRead,BufReader};
+use std::process::{Command,Stdio};
+use std::time::{Duration,Instant};
+
+const SIGINT: i32 = 2;
+
+extern "C" {
+    fn kill(pid: i32, sig: i32) -> i32;
+}
+
+fn is_gone(pid: u32, timeout: Duration) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        if unsafe { kill(pid as i32, 0) } != 0 {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    false
+}
+
+#[test]
+fn forwards_signals_and_reaps_children() {
+    let ci = Chex::new_local(ChexConfig::new());
+    assert!(!ci.act_as_pid1(Pid1Options::new()));
+    assert!(ci.act_as_pid1(Pid1Options::new().require_pid1(false)));
+
+    /*
+     * Never waited for: only the reaper can make it go away.
+     */
+    #[allow(clippy::zombie_processes)]
+    let unadopted = Command::new("true").spawn().unwrap();
+    assert!(is_gone(unadopted.id(), Duration::from_secs(10)));
+
+    /*
+     * Waited for within the orphan grace, so the reaper leaves its status alone.
+     */
+    let mut waited = Command::new("sh").args(["-c", "exit 3"]).spawn().unwrap();
+    std::thread::sleep(Duration::from_millis(300));
+    assert_eq!(waited.wait().unwrap().code(), Some(3));
+
+    /*
+     * Ignores the SIGTERM sent during teardown, so only the forwarded SIGINT ends it in time.
+     */
+    let mut adopted = Command::new("sh")
+        .args(["-c", "trap '' TERM; echo ready; exec sleep 60"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut line = String::new();
+    BufReader::new(adopted.stdout.take().unwrap()).read_line(&mut line).unwrap();
+    assert_eq!(line, "ready\n");
+    let pid = adopted.id();
+    ci.adopt_child(adopted, ChildOptions::new().grace(Duration::from_secs(60)));
+
+    unsafe {
+        kill(std::process::id() as i32, SIGINT);
+    }
+    assert!(ci.block_until_exit_timeout(Duration::from_secs(10)));
+    assert_eq!(ci.exit_reason(), Some(ExitReason::OsSignal(SIGINT)));
+    assert!(ci.wait_for_teardown(Duration::from_secs(10)));
+    assert!(is_gone(pid, Duration::from_secs(10)));
+}
@@ -0,0 +1,36 @@
+#![cfg(all(feature = "pid1", feature = "sigterm", feature = "test-util", unix))]
+
+use chex::{Chex,ChexConfig,ExitReason};
+use chex::clock::MockClock;
+use chex::pid1::Pid1Options;
+use chex::sigterm::SigtermOptions;
+use std::time::Duration;
+
+fn sigterm_self() {
+    let killed = std::process::Command::new("kill")
+        .arg("-TERM")
+        .arg(std::process::id().to_string())
+        .status()
+        .unwrap();
+    assert!(killed.success());
+}
+
+#[test]
+fn pid1_sigterm_keeps_pre_stop_delay() {
+    let clock = MockClock::new();
+    let chex = Chex::new_local(ChexConfig::new().clock(clock.clone()));
+    assert!(chex.act_as_pid1(Pid1Options::new().require_pid1(false)));
+    chex.handle_sigterm(SigtermOptions::new().pre_stop_delay(Duration::from_secs(3600)));
+
+    /*
+     * PID 1's handler owns SIGTERM here, and must still leave the delay to the sigterm module.
+     */
+    sigterm_self();
+    std::thread::sleep(Duration::from_millis(200));
+    assert!(chex.is_draining());
+    assert!(!chex.poll_exit());
+
+    clock.advance(Duration::from_secs(3600));
+    assert!(chex.block_until_exit_timeout(Duration::from_secs(5)));
+    assert_eq!(chex.exit_reason(), Some(ExitReason::OsSignal(15)));
+}
@@ -0,0 +1,25 @@
+#![cfg(feature = "rayon")]
+
+use chex::{Chex,ChexConfig};
+use chex::rayon::ParallelIteratorExt;
+use rayon::prelude::*;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::Relaxed;
+use std::time::Duration;
+
+#[test]
+fn parallel_iterator_stops_on_exit() {
+    let ci = Chex::new_local(ChexConfig::new());
+    let processed = AtomicUsize::new(0);
+
+    (0..10_000).into_par_iter().until_exit(&ci).for_each(|i| {
+        if i == 0 {
+            ci.signal_exit();
+        }
+        processed.fetch_add(1, Relaxed);
+        std::thread::sleep(Duration::from_millis(1));
+    });
+
+    assert!(ci.poll_exit());
+    assert!(processed.load(Relaxed) < 10_000);
+}
@@ -0,0 +1,27 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ExitReason};
+use std::time::Duration;
+
+#[test]
+fn readiness_barrier() {
+    let chex: &Chex = Chex::init(false);
+
+    let ci = chex.get_instance();
+    let worker = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(20));
+        ci.ready("db");
+        ci.ready("api");
+    });
+    assert_eq!(chex.wait_all_ready(&["db", "api"], Duration::from_secs(5)), Ok(()));
+    worker.join().unwrap();
+
+    let err = chex.wait_all_ready(&["db", "cache"], Duration::from_millis(20)).unwrap_err();
+    assert_eq!(err.missing(), ["cache".to_string()]);
+    assert!(!err.exited());
+    assert!(!chex.poll_exit());
+
+    assert!(chex.wait_all_ready_or_exit(&["cache"], Duration::from_millis(20)).is_err());
+    assert_eq!(chex.exit_reason(), Some(ExitReason::NotReady));
+    assert!(chex.wait_all_ready(&["cache"], Duration::from_secs(5)).unwrap_err().exited());
+}
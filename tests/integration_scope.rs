@@ -0,0 +1,35 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig,ExitReason,ExitReasonKind};
+use chex::scope::{self,ScopePolicy};
+
+#[test]
+fn failed_worker_cancels_scope_and_escalates_by_policy() {
+    let parent = Chex::new_local(ChexConfig::new());
+    let result = scope::scope_with(&parent, ScopePolicy::Escalate, |s| {
+        s.spawn("waiter", |ci| ci.block_until_exit());
+        s.spawn("failer", |_| -> Result<(), String> { Err("boom".to_string()) });
+        7
+    });
+    let error = result.expect_err("failer failed");
+    assert_eq!(error.failures().len(), 1);
+    assert_eq!(error.first().worker(), "failer");
+    assert_eq!(error.first().reason(), ExitReasonKind::Error);
+    assert_eq!(error.first().message(), "boom");
+    assert_eq!(parent.exit_reason(), Some(ExitReason::Error { message: "failer: boom".to_string() }));
+
+    let parent = Chex::new_local(ChexConfig::new());
+    let result = scope::scope_with(&parent, ScopePolicy::Contain, |s| {
+        s.spawn("waiter", |ci| ci.block_until_exit());
+        s.spawn("crasher", |_| -> () { panic!("crashed") });
+    });
+    assert_eq!(result.expect_err("crasher panicked").first().reason(), ExitReasonKind::Panic);
+    assert!(!parent.poll_exit());
+
+    let result = scope::scope_with(&parent, ScopePolicy::Contain, |s| {
+        s.spawn("waiter", |ci| ci.block_until_exit());
+        parent.signal_exit();
+        "done"
+    });
+    assert_eq!(result, Ok("done"));
+}
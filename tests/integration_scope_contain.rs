@@ -0,0 +1,24 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ExitReasonKind};
+use chex::scope::{self,ScopePolicy};
+
+#[test]
+fn contained_panic_leaves_global_exit_on_panic_alone() {
+    let chex: &Chex = Chex::init(true);
+    let global = chex.get_instance();
+
+    let result = scope::scope_with(&global, ScopePolicy::Contain, |s| {
+        s.spawn("waiter", |ci| ci.block_until_exit());
+        s.spawn("crasher", |_| -> () { panic!("crashed") });
+    });
+    assert_eq!(result.expect_err("crasher panicked").first().reason(), ExitReasonKind::Panic);
+    assert!(!global.poll_exit());
+    assert!(!chex::exiting());
+
+    /*
+     * Outside the scope, the hook still signals exit.
+     */
+    assert!(std::thread::spawn(|| panic!("escaped")).join().is_err());
+    assert!(global.poll_exit());
+}
@@ -0,0 +1,36 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig,ChexError};
+
+fn deep_inside() -> bool {
+    let ci = Chex::get_chex_instance();
+    ci.signal_exit()
+}
+
+#[test]
+fn scoped_instance_stands_in_for_the_global_one() {
+    assert_eq!(Chex::try_get_chex_instance().err(), Some(ChexError::NotInitialized));
+
+    let outer = Chex::new_local(ChexConfig::new());
+    let inner = Chex::new_local(ChexConfig::new());
+    Chex::with_scoped_instance(outer.clone(), || {
+        let seen = Chex::with_scoped_instance(inner.clone(), deep_inside);
+        assert!(seen);
+        assert!(inner.poll_exit());
+        assert!(!outer.poll_exit());
+
+        let other_thread = std::thread::spawn(|| Chex::try_get_chex_instance().is_err());
+        assert!(other_thread.join().unwrap());
+
+        assert!(!Chex::get_or_init_instance().poll_exit());
+        assert!(!Chex::is_initialized());
+    });
+
+    let scoped = outer.clone();
+    let unwound = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        Chex::with_scoped_instance(scoped, || panic!("test failed"))
+    }));
+    assert!(unwound.is_err());
+    assert_eq!(Chex::try_get_chex_instance().err(), Some(ChexError::NotInitialized));
+    assert!(!outer.poll_exit());
+}
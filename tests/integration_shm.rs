@@ -0,0 +1,27 @@
+#![cfg(all(feature = "shm", unix, target_pointer_width = "64"))]
+
+use chex::{Chex,ChexConfig};
+use chex::shm::ShmFlag;
+use std::time::Duration;
+
+#[test]
+fn shared_flag_propagates_exit() {
+    let name = format!("test-{}", std::process::id());
+    let _ = ShmFlag::remove(&name);
+
+    let ci = Chex::new_local(ChexConfig::new());
+    let _link = chex::shm::link(&ci, &name).unwrap();
+    let sibling = ShmFlag::open(&name).unwrap();
+    assert!(!sibling.is_raised());
+    assert!(!sibling.wait_timeout(Duration::from_millis(10)));
+
+    ci.signal_exit();
+    assert!(sibling.wait_timeout(Duration::from_secs(5)));
+
+    let other = Chex::new_local(ChexConfig::new());
+    let _link = chex::shm::link(&other, &name).unwrap();
+    assert!(other.block_until_exit_timeout(Duration::from_secs(5)));
+
+    ShmFlag::remove(&name).unwrap();
+    assert!(ShmFlag::open("../escape").is_err());
+}
@@ -0,0 +1,24 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ExitReason};
+
+#[test]
+fn later_signal_attempts_are_recorded() {
+    let chex: &Chex = Chex::init(false);
+    let ci = chex.get_instance();
+
+    assert!(chex.signal_exit());
+    let worker = std::thread::spawn(move || ci.signal_exit_on_os_signal(15));
+    assert!(!worker.join().unwrap());
+
+    let state = chex.dump_state();
+    let attempts = state.signal_attempts();
+    assert_eq!(attempts.len(), 2);
+    assert!(attempts[0].first());
+    assert_eq!(attempts[0].reason(), &ExitReason::Manual);
+    assert!(!attempts[1].first());
+    assert_eq!(attempts[1].reason(), &ExitReason::OsSignal(15));
+    assert_eq!(attempts[1].origin().file(), file!());
+    assert!(attempts[1].at() >= attempts[0].at());
+    assert!(state.to_string().contains("signal attempts: 2"));
+}
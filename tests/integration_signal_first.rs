@@ -0,0 +1,15 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexInstance};
+
+#[test]
+fn only_first_signal_reports_first() {
+    let chex: &Chex = Chex::init(false);
+    let ci_a: ChexInstance = chex.get_instance();
+    let ci_b: ChexInstance = ci_a.clone();
+
+    assert!(ci_a.signal_exit());
+    assert!(!ci_b.signal_exit());
+    assert!(!chex.signal_exit());
+    assert!(ci_b.poll_exit());
+}
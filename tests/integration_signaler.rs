@@ -0,0 +1,20 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexInstance,ChexSignaler,ExitReasonKind};
+
+#[test]
+fn signaler_triggers_exit_without_listening() {
+    let chex: &Chex = Chex::init(false);
+    let ci: ChexInstance = chex.get_instance();
+
+    let signalers: Vec<ChexSignaler> = (0..3).map(|_| chex.signaler().expect("initialized")).collect();
+    let from_instance = ci.signaler();
+    assert_eq!(ci.listener_count(), 2);
+    assert!(!from_instance.poll_exit());
+
+    assert!(signalers[1].signal_exit());
+    assert!(!from_instance.signal_exit());
+    assert!(ci.poll_exit());
+    assert_eq!(ci.exit_reason().map(|r| r.kind()), Some(ExitReasonKind::Manual));
+    assert_eq!(ci.exit_origin().expect("origin").file(), file!());
+}
@@ -0,0 +1,34 @@
+#![cfg(all(feature = "sigterm", feature = "test-util", unix))]
+
+use chex::{Chex,ChexConfig,ExitReason};
+use chex::clock::MockClock;
+use chex::sigterm::SigtermOptions;
+use std::time::Duration;
+
+fn sigterm_self() {
+    let killed = std::process::Command::new("kill")
+        .arg("-TERM")
+        .arg(std::process::id().to_string())
+        .status()
+        .unwrap();
+    assert!(killed.success());
+}
+
+#[test]
+fn sigterm_after_drain_keeps_delay() {
+    let clock = MockClock::new();
+    let chex = Chex::new_local(ChexConfig::new().clock(clock.clone()));
+    chex.handle_sigterm(SigtermOptions::new().pre_stop_delay(Duration::from_secs(3600)));
+
+    /*
+     * Draining for some other reason doesn't count as the first SIGTERM.
+     */
+    assert!(chex.start_draining());
+    sigterm_self();
+    std::thread::sleep(Duration::from_millis(200));
+    assert!(!chex.poll_exit());
+
+    sigterm_self();
+    assert!(chex.block_until_exit_timeout(Duration::from_secs(5)));
+    assert_eq!(chex.exit_reason(), Some(ExitReason::OsSignal(15)));
+}
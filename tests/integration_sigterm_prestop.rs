@@ -0,0 +1,32 @@
+#![cfg(all(feature = "sigterm", feature = "test-util", unix))]
+
+use chex::{Chex,ChexConfig,ExitReason};
+use chex::clock::MockClock;
+use chex::sigterm::SigtermOptions;
+use std::time::Duration;
+
+fn sigterm_self() {
+    let killed = std::process::Command::new("kill")
+        .arg("-TERM")
+        .arg(std::process::id().to_string())
+        .status()
+        .unwrap();
+    assert!(killed.success());
+}
+
+#[test]
+fn sigterm_drains_before_exit() {
+    let clock = MockClock::new();
+    let chex = Chex::new_local(ChexConfig::new().clock(clock.clone()));
+    chex.handle_sigterm(SigtermOptions::new().pre_stop_delay(Duration::from_secs(3600)));
+    assert!(!chex.is_draining());
+
+    sigterm_self();
+    std::thread::sleep(Duration::from_millis(50));
+    assert!(chex.is_draining());
+    assert!(!chex.poll_exit());
+
+    clock.advance(Duration::from_secs(3600));
+    assert!(chex.block_until_exit_timeout(Duration::from_secs(5)));
+    assert_eq!(chex.exit_reason(), Some(ExitReason::OsSignal(15)));
+}
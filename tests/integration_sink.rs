@@ -0,0 +1,27 @@
+#![cfg(feature = "std")]
+
+use chex::Chex;
+use chex::sink::ChexEvent;
+use std::sync::{Arc,Mutex};
+
+#[test]
+fn sink_receives_events() {
+    let chex: &Chex = Chex::init(false);
+
+    let events: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+    let seen = events.clone();
+    chex.set_sink(move |event: ChexEvent<'_>| {
+        let name = match event {
+            ChexEvent::ExitSignalled { reason, origin } => {
+                assert_eq!(origin.expect("origin recorded").file(), file!());
+                format!("exit:{reason}")
+            }
+            other => format!("{other:?}"),
+        };
+        seen.lock().unwrap().push(name);
+    });
+
+    assert!(chex.signal_exit());
+    assert!(!chex.signal_exit());
+    assert_eq!(*events.lock().unwrap(), vec!["exit:manual".to_string()]);
+}
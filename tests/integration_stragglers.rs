@@ -0,0 +1,45 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexInstance};
+use std::sync::mpsc;
+use std::time::Duration;
+
+#[test]
+fn unacked_listeners_reported_as_stragglers() {
+    let chex: &Chex = Chex::init(false);
+    chex.set_straggler_timeout(Some(Duration::from_millis(50)));
+
+    let (tx, rx) = mpsc::channel();
+    chex.on_stragglers(move |stragglers| {
+        let names: Vec<String> = stragglers.iter().map(|l| l.name().to_string()).collect();
+        let _ = tx.send(names);
+    });
+
+    let ci: ChexInstance = chex.get_instance();
+    let quick = std::thread::Builder::new().name("quick".to_string()).spawn({
+        let ci = ci.clone();
+        move || {
+            let listener = ci.register("quick");
+            while !ci.poll_exit() {
+                std::thread::yield_now();
+            }
+            listener.ack();
+        }
+    }).expect("Failed to spawn thread");
+
+    let slow = ci.register("slow");
+    assert!(chex.stragglers().is_empty());
+    assert!(chex.listeners().iter().any(|l| l.name() == "slow"));
+
+    chex.signal_exit();
+    quick.join().expect("quick listener panicked");
+
+    let names = rx.recv_timeout(Duration::from_secs(5)).expect("stragglers reported");
+    assert_eq!(names, vec!["slow".to_string()]);
+
+    let stragglers = chex.stragglers();
+    assert_eq!(stragglers.len(), 1);
+    assert_eq!(stragglers[0].thread_name(), Some("unacked_listeners_reported_as_stragglers"));
+    drop(slow);
+    assert!(chex.stragglers().is_empty());
+}
@@ -0,0 +1,27 @@
+#![cfg(feature = "std")]
+
+use chex::Chex;
+use std::time::Duration;
+
+#[test]
+fn wait_for_subsystems_accounts_for_every_handle() {
+    let chex: &Chex = Chex::init(false);
+
+    let uploader = chex.register_subsystem("uploader").expect("initialized");
+    let indexer = chex.register_subsystem("indexer").expect("initialized");
+    assert_eq!(uploader.name(), "uploader");
+
+    chex.signal_exit();
+    uploader.done();
+
+    let pending = chex.wait_for_subsystems(Duration::from_millis(20)).unwrap_err();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].name(), "indexer");
+
+    let worker = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(20));
+        drop(indexer);
+    });
+    assert!(chex.wait_for_subsystems(Duration::from_secs(5)).is_ok());
+    worker.join().unwrap();
+}
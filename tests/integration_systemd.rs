@@ -0,0 +1,42 @@
+#![cfg(all(feature = "systemd", target_os = "linux"))]
+
+use chex::{Chex,ChexConfig,ExitReason};
+use chex::systemd::SystemdOptions;
+use std::os::unix::net::UnixDatagram;
+use std::time::Duration;
+
+#[test]
+fn systemd_notifications_and_sigterm() {
+    let path = std::env::temp_dir().join(format!("chex-notify-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let socket = UnixDatagram::bind(&path).unwrap();
+    socket.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    std::env::set_var("NOTIFY_SOCKET", &path);
+    std::env::set_var("WATCHDOG_USEC", "20000");
+
+    let chex = Chex::new_local(ChexConfig::new());
+    chex.enable_systemd(SystemdOptions::new());
+    chex.on_teardown(0, || std::thread::sleep(Duration::from_millis(50)));
+
+    let killed = std::process::Command::new("kill")
+        .arg("-TERM")
+        .arg(std::process::id().to_string())
+        .status()
+        .unwrap();
+    assert!(killed.success());
+    assert!(chex.block_until_exit_timeout(Duration::from_secs(5)));
+    assert_eq!(chex.exit_reason(), Some(ExitReason::OsSignal(15)));
+
+    let mut buf = [0u8; 64];
+    let mut states = Vec::new();
+    while let Ok(n) = socket.recv(&mut buf) {
+        states.push(String::from_utf8_lossy(&buf[..n]).into_owned());
+        if states.len() >= 3 {
+            break;
+        }
+    }
+    assert_eq!(states[0], "STOPPING=1");
+    assert!(states[1..].iter().all(|s| s == "WATCHDOG=1"));
+    assert!(chex.wait_for_teardown(Duration::from_secs(5)));
+    let _ = std::fs::remove_file(&path);
+}
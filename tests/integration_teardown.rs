@@ -0,0 +1,24 @@
+#![cfg(feature = "std")]
+
+use chex::Chex;
+use std::sync::{Arc,Mutex};
+use std::time::Duration;
+
+#[test]
+fn teardown_hooks_run_in_priority_order() {
+    let chex: &Chex = Chex::init(false);
+    let order: Arc<Mutex<Vec<&'static str>>> = Arc::new(Mutex::new(Vec::new()));
+
+    for (priority, name) in [(20, "close telemetry"), (0, "stop accepting"), (10, "flush db"), (0, "health flag")] {
+        let order = order.clone();
+        chex.on_teardown(priority, move || order.lock().unwrap().push(name));
+    }
+    chex.on_teardown(5, || panic!("hook failure"));
+
+    assert!(!chex.wait_for_teardown(Duration::from_millis(10)));
+    assert!(order.lock().unwrap().is_empty());
+
+    chex.signal_exit();
+    assert!(chex.wait_for_teardown(Duration::from_secs(5)));
+    assert_eq!(*order.lock().unwrap(), ["stop accepting", "health flag", "flush db", "close telemetry"]);
+}
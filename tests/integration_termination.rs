@@ -0,0 +1,17 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ExitReason};
+use std::process::Termination;
+
+#[test]
+fn termination_reports_reason_exit_code() {
+    let chex: &Chex = Chex::init(true);
+    assert_eq!(chex.termination().exit_code(), 0);
+    assert_eq!(chex.termination().reason(), None);
+
+    assert!(std::panic::catch_unwind(|| panic!("worker died")).is_err());
+    let termination = chex.termination();
+    assert_eq!(termination.reason(), Some(&ExitReason::Panic { message: "worker died".to_string() }));
+    assert_eq!(termination.exit_code(), 101);
+    assert_eq!(termination.report(), std::process::ExitCode::from(101));
+}
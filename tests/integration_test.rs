@@ -1,3 +1,5 @@
+#![cfg(feature = "std")]
+
 use chex::{Chex,ChexInstance};
 
 #[test]
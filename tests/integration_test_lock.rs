@@ -0,0 +1,56 @@
+#![cfg(feature = "test-util")]
+
+use chex::{Chex,ChexInstance};
+use std::time::Duration;
+
+/*
+ * Both tests signal exit on the global Chex and expect to start from a clean one, which only
+ * holds if the lock serializes them and resets the global Chex in between.
+ */
+fn exits_from_a_clean_global() {
+    let _lock = Chex::test_lock();
+    let chex: &Chex = Chex::init(true);
+    assert!(!chex.poll_exit());
+    assert!(!chex::exiting());
+
+    let ci: ChexInstance = Chex::get_chex_instance();
+    std::thread::sleep(Duration::from_millis(20));
+    ci.signal_exit();
+    assert!(chex.poll_exit());
+    assert!(Chex::get_chex_instance().poll_exit());
+}
+
+#[test]
+fn first_test_using_the_global_chex() {
+    exits_from_a_clean_global();
+}
+
+#[test]
+fn second_test_using_the_global_chex() {
+    exits_from_a_clean_global();
+}
+
+#[test]
+fn panicking_test_still_resets() {
+    let unwound = std::thread::spawn(|| {
+        let _lock = Chex::test_lock();
+        Chex::init(true);
+        panic!("test failed");
+    }).join();
+    assert!(unwound.is_err());
+    exits_from_a_clean_global();
+}
+
+#[test]
+fn previous_generation_does_not_mark_exiting() {
+    let previous: ChexInstance = {
+        let _lock = Chex::test_lock();
+        Chex::init(true);
+        Chex::get_chex_instance()
+    };
+
+    let _lock = Chex::test_lock();
+    previous.signal_exit();
+    assert!(!chex::exiting());
+    assert!(!Chex::get_chex_instance().poll_exit());
+}
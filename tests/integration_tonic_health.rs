@@ -0,0 +1,33 @@
+#![cfg(feature = "tonic")]
+
+use chex::{Chex,ChexConfig};
+use futures::FutureExt;
+use tonic_health::pb::HealthCheckRequest;
+use tonic_health::pb::health_check_response::ServingStatus;
+use tonic_health::pb::health_server::Health;
+use tonic_health::server::{HealthService,health_reporter};
+
+async fn status(health: &HealthService, service: &str) -> i32 {
+    let request = tonic::Request::new(HealthCheckRequest { service: service.to_string() });
+    health.check(request).await.expect("known service").into_inner().status
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn tonic_health_turns_not_serving_while_draining() {
+    let chex = Chex::new_local(ChexConfig::new());
+    let (reporter, _server) = health_reporter();
+    reporter.set_service_status("app.Greeter", tonic_health::ServingStatus::Serving).await;
+    let health = HealthService::from_health_reporter(reporter.clone());
+
+    let mut shutdown = Box::pin(chex::tonic::shutdown_signal_with_health(chex.clone(), reporter, ["app.Greeter"]));
+    assert!(shutdown.as_mut().now_or_never().is_none());
+    assert_eq!(status(&health, "").await, ServingStatus::Serving as i32);
+
+    assert!(chex.start_draining());
+    assert!(shutdown.as_mut().now_or_never().is_none());
+    assert_eq!(status(&health, "").await, ServingStatus::NotServing as i32);
+    assert_eq!(status(&health, "app.Greeter").await, ServingStatus::NotServing as i32);
+
+    chex.signal_exit();
+    shutdown.await;
+}
@@ -0,0 +1,19 @@
+#![cfg(feature = "tonic")]
+
+use chex::{Chex,ChexConfig};
+use futures::FutureExt;
+
+#[test]
+fn tonic_shutdown_resolves_on_soft_exit() {
+    let chex = Chex::new_local(ChexConfig::new());
+    let mut shutdown = Box::pin(chex::tonic::shutdown_signal_for(chex.clone()));
+    assert!(shutdown.as_mut().now_or_never().is_none());
+
+    let drainer = chex.clone();
+    std::thread::spawn(move || assert!(drainer.start_draining())).join().unwrap();
+    futures::executor::block_on(shutdown);
+
+    assert!(chex.is_draining());
+    assert!(!chex.poll_exit());
+    assert!(!chex.start_draining());
+}
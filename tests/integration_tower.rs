@@ -0,0 +1,51 @@
+#![cfg(feature = "tower")]
+
+use chex::{Chex,ChexConfig};
+use chex::tower::ChexLayer;
+use futures::executor::block_on;
+use std::convert::Infallible;
+use std::future::Ready;
+use std::task::{Context,Poll};
+use std::time::Duration;
+use tower_layer::Layer;
+use tower_service::Service;
+
+#[derive(Clone)]
+struct Echo;
+
+impl Service<&'static str> for Echo {
+    type Response = http::Response<String>;
+    type Error = Infallible;
+    type Future = Ready<Result<Self::Response, Infallible>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: &'static str) -> Self::Future {
+        std::future::ready(Ok(http::Response::new(req.to_string())))
+    }
+}
+
+#[test]
+fn layer_rejects_new_requests_once_exiting() {
+    let chex = Chex::new_local(ChexConfig::new());
+    let mut service = ChexLayer::new(chex.clone()).layer(Echo);
+
+    let in_flight = service.call("before");
+    assert_eq!(chex.outstanding_work_permits(), 1);
+    chex.signal_exit();
+
+    let rejected = block_on(service.call("after")).unwrap();
+    assert_eq!(rejected.status(), http::StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(rejected.body(), "");
+
+    let response = block_on(in_flight).unwrap();
+    assert_eq!(response.body(), "before");
+    assert!(chex.wait_for_work_permits(Duration::from_secs(1)));
+
+    let mut custom = ChexLayer::new(chex)
+        .with_response(|| http::Response::new("draining".to_string()))
+        .layer(Echo);
+    assert_eq!(block_on(custom.call("late")).unwrap().body(), "draining");
+}
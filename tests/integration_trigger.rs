@@ -0,0 +1,55 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig,ChexSignaler,ExitReason};
+use chex::trigger::ExitTrigger;
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use std::time::Duration;
+
+/*
+ * Signals exit once the queue grows past max.
+ */
+struct QueueDepth {
+    depth: Arc<AtomicUsize>,
+    max: usize,
+}
+
+impl ExitTrigger for QueueDepth {
+    fn watch(self, signaler: ChexSignaler) {
+        while !signaler.poll_exit() {
+            let depth = self.depth.load(SeqCst);
+            if depth > self.max {
+                signaler.signal_exit_for_resource(format!("queue depth {depth}"));
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+#[test]
+fn triggers_signal_exit() {
+    let ci = Chex::new_local(ChexConfig::new());
+    let depth = Arc::new(AtomicUsize::new(0));
+    ci.install_trigger(QueueDepth { depth: depth.clone(), max: 10 });
+    std::thread::sleep(Duration::from_millis(50));
+    assert!(!ci.poll_exit());
+
+    depth.store(11, SeqCst);
+    assert!(ci.block_until_exit_timeout(Duration::from_secs(10)));
+    assert_eq!(ci.exit_reason(), Some(ExitReason::Resource { message: "queue depth 11".to_string() }));
+
+    let draining = Chex::new_local(ChexConfig::new());
+    draining.install_trigger(|signaler: ChexSignaler| {
+        assert!(signaler.start_draining());
+    });
+    for _ in 0..1000 {
+        if draining.is_draining() {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert!(draining.is_draining());
+    assert!(!draining.poll_exit());
+}
@@ -0,0 +1,24 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig,InitError};
+
+#[test]
+fn try_init_reports_earlier_init() {
+    let chex: &Chex = Chex::try_init(ChexConfig::new()).expect("first try_init should initialize");
+    assert!(!chex.poll_exit());
+
+    match Chex::try_init(ChexConfig::new()) {
+        Err(InitError::AlreadyInitialized(c)) => assert!(!c.poll_exit()),
+        Err(e) => panic!("expected AlreadyInitialized, got {e:?}"),
+        Ok(_) => panic!("expected AlreadyInitialized, got Ok"),
+    }
+
+    match Chex::try_init(ChexConfig::new().exit_on_panic(true)) {
+        Err(InitError::ConflictingConfig { existing, requested }) => {
+            assert_eq!(existing, ChexConfig::new());
+            assert_eq!(requested, ChexConfig::new().exit_on_panic(true));
+        }
+        Err(e) => panic!("expected ConflictingConfig, got {e:?}"),
+        Ok(_) => panic!("expected ConflictingConfig, got Ok"),
+    }
+}
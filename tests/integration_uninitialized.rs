@@ -0,0 +1,16 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexError};
+
+#[test]
+fn try_variants_before_init() {
+    assert_eq!(Chex::try_get_chex_instance().err(), Some(ChexError::NotInitialized));
+
+    let chex: &Chex = Chex::init(false);
+    let ci = chex.try_get_instance().expect("initialized");
+    assert_eq!(chex.try_poll_exit(), Ok(false));
+
+    ci.signal_exit();
+    assert_eq!(chex.try_poll_exit(), Ok(true));
+    assert!(Chex::try_get_chex_instance().expect("initialized").poll_exit());
+}
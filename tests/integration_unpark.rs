@@ -0,0 +1,28 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig};
+use std::time::{Duration,Instant};
+
+#[test]
+fn parked_threads_are_unparked_on_exit() {
+    let ci = Chex::new_local(ChexConfig::new());
+
+    let worker = ci.clone();
+    let handle = std::thread::spawn(move || {
+        let _guard = worker.unpark_on_exit(std::thread::current());
+        let start = Instant::now();
+        while !worker.poll_exit() {
+            std::thread::park();
+        }
+        start.elapsed()
+    });
+
+    std::thread::sleep(Duration::from_millis(50));
+    ci.signal_exit();
+    assert!(handle.join().unwrap() < Duration::from_secs(5));
+
+    let _guard = ci.unpark_on_exit(std::thread::current());
+    let start = Instant::now();
+    std::thread::park_timeout(Duration::from_secs(5));
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "std")]
+
+use chex::{Chex,ChexConfig};
+use chex::latency::{LatencySummary,WakeupKind};
+use std::time::Duration;
+
+#[test]
+fn wakeup_latencies_cover_every_listener_kind() {
+    let chex = Chex::new_local(ChexConfig::new());
+    let blocking = chex.clone();
+    let poller = chex.clone();
+    let guard = chex.register("api");
+
+    let sleeper = std::thread::Builder::new()
+        .name("sleeper".to_string())
+        .spawn(move || blocking.block_until_exit())
+        .unwrap();
+    std::thread::sleep(Duration::from_millis(20));
+
+    chex.signal_exit();
+    sleeper.join().unwrap();
+    std::thread::sleep(Duration::from_millis(20));
+    assert!(poller.poll_exit());
+    assert!(poller.poll_exit());
+    guard.ack();
+
+    let samples = chex.wakeup_latencies();
+    let kinds: Vec<WakeupKind> = samples.iter().map(|s| s.kind()).collect();
+    assert_eq!(kinds, [WakeupKind::Blocking, WakeupKind::Poll, WakeupKind::Ack]);
+    assert_eq!(samples[0].thread_name(), Some("sleeper"));
+    assert_eq!(samples[2].listener(), Some("api"));
+    assert!(samples[1].latency() >= Duration::from_millis(20));
+
+    let summary = LatencySummary::of(&samples);
+    assert_eq!(summary.count(), 3);
+    assert!(summary.min() <= summary.p50() && summary.p50() <= summary.max());
+    assert_eq!(LatencySummary::of(samples.iter().filter(|s| s.kind() == WakeupKind::Poll)).count(), 1);
+}
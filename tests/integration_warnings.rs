@@ -0,0 +1,25 @@
+#![cfg(feature = "std")]
+
+use chex::Chex;
+
+#[tokio::test(flavor = "current_thread")]
+async fn warnings_do_not_signal_exit() {
+    let chex: &Chex = Chex::init(false);
+    let mut early = chex.subscribe_warnings().expect("initialized");
+
+    chex.signal_warning("low disk");
+    let line = line!() - 1;
+    let mut late = chex.subscribe_warnings().expect("initialized");
+    chex.get_instance().signal_warning("degraded");
+
+    assert!(!chex.poll_exit());
+
+    let first = early.recv().await.expect("warning delivered");
+    assert_eq!(first.message(), "low disk");
+    assert_eq!(first.origin().line(), line);
+    assert_eq!(early.try_recv().expect("second warning").message(), "degraded");
+    assert!(early.try_recv().is_none());
+
+    assert_eq!(late.try_recv().expect("only later warnings").message(), "degraded");
+    assert!(late.try_recv().is_none());
+}
@@ -0,0 +1,26 @@
+#![cfg(feature = "std")]
+
+use chex::Chex;
+use std::time::{Duration,Instant};
+
+#[test]
+fn missed_heartbeat_signals_exit() {
+    let chex: &Chex = Chex::init(false);
+
+    let healthy = chex.watchdog("healthy", Duration::from_millis(200)).expect("initialized");
+    let hung = chex.watchdog("hung", Duration::from_millis(50)).expect("initialized");
+    let line = line!() - 1;
+
+    let start = Instant::now();
+    while !chex.poll_exit() {
+        assert!(start.elapsed() < Duration::from_secs(5), "watchdog never fired");
+        healthy.beat();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert!(start.elapsed() >= Duration::from_millis(50));
+
+    let origin = chex.exit_origin().expect("exit origin recorded");
+    assert_eq!(origin.file(), file!());
+    assert_eq!(origin.line(), line);
+    drop(hung);
+}
@@ -0,0 +1,25 @@
+#![cfg(feature = "std")]
+
+use chex::Chex;
+use std::time::Duration;
+
+#[test]
+fn no_new_work_after_shutdown_and_wait_for_in_flight() {
+    let chex: &Chex = Chex::init(false);
+
+    let in_flight = chex.acquire_work_permit().expect("permit before shutdown");
+    let done = chex.acquire_work_permit().expect("permit before shutdown");
+    drop(done);
+    assert_eq!(chex.get_instance().outstanding_work_permits(), 1);
+
+    chex.signal_exit();
+    assert!(chex.acquire_work_permit().is_none());
+    assert!(!chex.wait_for_work_permits(Duration::from_millis(10)));
+
+    let worker = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(20));
+        drop(in_flight);
+    });
+    assert!(chex.wait_for_work_permits(Duration::from_secs(5)));
+    worker.join().unwrap();
+}
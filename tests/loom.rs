@@ -0,0 +1,89 @@
+//! Model-checks the signal/poll/wait paths with loom.  Run with
+//! `RUSTFLAGS="--cfg loom" cargo test --release --test loom`; under cfg(loom) a coordinator can
+//! only be used from within loom::model(), so the other tests don't apply.
+#![cfg(loom)]
+
+use chex::{Chex,ChexConfig,ExitReasonKind};
+use loom::sync::Arc;
+use loom::sync::atomic::{AtomicUsize,Ordering::Relaxed};
+use loom::thread;
+
+#[test]
+fn poll_exit_publishes_writes_and_reason() {
+    loom::model(|| {
+        let ci = Chex::new_local(ChexConfig::new());
+        let why = Arc::new(AtomicUsize::new(0));
+
+        let signaller = {
+            let ci = ci.clone();
+            let why = why.clone();
+            thread::spawn(move || {
+                why.store(42, Relaxed);
+                ci.signal_exit();
+            })
+        };
+
+        if ci.poll_exit() {
+            assert_eq!(why.load(Relaxed), 42);
+            assert_eq!(ci.exit_reason().map(|r| r.kind()), Some(ExitReasonKind::Manual));
+            assert!(ci.exit_origin().is_some());
+        }
+        signaller.join().unwrap();
+        assert!(ci.poll_exit());
+    });
+}
+
+#[test]
+fn concurrent_signals_have_exactly_one_first() {
+    loom::model(|| {
+        let ci = Chex::new_local(ChexConfig::new());
+        let other = ci.clone();
+        let t = thread::spawn(move || other.signal_exit());
+        let first_here = ci.signal_exit();
+        let first_there = t.join().unwrap();
+        assert!(first_here ^ first_there);
+    });
+}
+
+#[test]
+fn block_until_exit_does_not_miss_the_signal() {
+    loom::model(|| {
+        let ci = Chex::new_local(ChexConfig::new());
+        let signaller = ci.clone();
+        let t = thread::spawn(move || {
+            signaller.signal_exit();
+        });
+        ci.block_until_exit();
+        t.join().unwrap();
+    });
+}
+
+#[test]
+fn check_exit_async_does_not_miss_the_signal() {
+    loom::model(|| {
+        let mut ci = Chex::new_local(ChexConfig::new());
+        let signaller = ci.clone();
+        let t = thread::spawn(move || {
+            signaller.signal_exit();
+        });
+        loom::future::block_on(ci.check_exit_async());
+        t.join().unwrap();
+    });
+}
+
+#[test]
+fn panic_hook_signals_exit_to_waiters() {
+    let mut builder = loom::model::Builder::new();
+    builder.preemption_bound = Some(2);
+    builder.check(|| {
+        let ci = Chex::new_local(ChexConfig::new().exit_on_panic(true));
+        let worker = ci.clone();
+        let t = thread::spawn(move || {
+            let _worker = worker;
+            let _ = std::panic::catch_unwind(|| panic!("worker failed"));
+        });
+        ci.block_until_exit();
+        assert_eq!(ci.exit_reason().map(|r| r.kind()), Some(ExitReasonKind::Panic));
+        t.join().unwrap();
+    });
+}